@@ -0,0 +1,66 @@
+use crate::backup;
+use crate::config::{Config, CredentialStore};
+use crate::revocation;
+use crate::store::IdentityStore;
+use identity::account::{Account, IdentityCreate, IdentitySnapshot, Result};
+use identity::core::Url;
+use identity::iota::{IotaDID, IotaDocument};
+
+pub const ISSUER_NAME: &str = "issuer";
+/// Fragment of the signing method every account identity is created with.
+pub const SIGNING_METHOD: &str = "_sign-0";
+
+/// Opens the account backed by the storage of the store.
+pub async fn open(store: &dyn IdentityStore) -> Result<Account> {
+    let account: Account = Account::builder()
+        .storage(store.account_storage())
+        .build()
+        .await?;
+    Ok(account)
+}
+
+/// Loads the issuer identity from the snapshot, creating and publishing it on first run.
+/// With the HTTP store, the document names the store as service so verifiers fetch the
+/// status list from there.
+pub async fn issuer(account: &Account, config: &Config) -> Result<IotaDocument> {
+    let snapshot: IdentitySnapshot = match account.find_identity(ISSUER_NAME).await? {
+        Some(snapshot) => snapshot,
+        None => {
+            backup::create(config)?;
+            account
+                .create_identity(IdentityCreate::new().name(ISSUER_NAME))
+                .await?
+        }
+    };
+    let did: &IotaDID = snapshot.identity().try_did()?;
+
+    let document: IotaDocument = account.resolve_identity(did).await?;
+    if config.credential_store != CredentialStore::Http
+        || revocation::is_hosted_by(&document, &Url::parse(&revocation::url(config))?)
+    {
+        return Ok(document);
+    }
+    backup::create(config)?;
+    // A store URL changed since, the service is published again with the new one.
+    if document
+        .service()
+        .iter()
+        .any(|service| service.id().fragment() == Some(revocation::SERVICE))
+    {
+        account
+            .update_identity(did)
+            .delete_service()
+            .fragment(revocation::SERVICE)
+            .apply()
+            .await?;
+    }
+    account
+        .update_identity(did)
+        .create_service()
+        .fragment(revocation::SERVICE)
+        .type_(revocation::STATUS_TYPE)
+        .endpoint(Url::parse(&config.store_url)?)
+        .apply()
+        .await?;
+    account.resolve_identity(did).await
+}
@@ -0,0 +1,288 @@
+use crate::profile::Capability;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Action {
+    ShowHome,
+    ShowIssue,
+    ShowVerify,
+    ShowNodes,
+    ShowRestore,
+    ShowApply,
+    ShowPending,
+    ShowWallet,
+    ShowLog,
+    ShowChain,
+    ShowSettings,
+    ShowDashboard,
+    ShowHistory,
+    ShowKeys,
+    CollectCredentials,
+    AcceptOffer,
+    CreateIdentity,
+    IssueCredential,
+    PreviewCredential,
+    IssueFromForm,
+    VerifyCredential,
+    TrustIssuer,
+    ResolveIssuer,
+    ResolveIdentity,
+    /// A background verification finished.
+    VerifyFinished,
+    /// A background resolution finished.
+    ResolveFinished,
+    CancelTask,
+    TogglePin,
+    ExportCredential,
+    ExportJwks,
+    ExportResolution,
+    PinVersion,
+    CompareNodes,
+    ExportReport,
+    RequestPresentation,
+    ReceivePresentation,
+    BackupSnapshot,
+    ExportBundle,
+    ImportBundle,
+    SendRequest,
+    AcceptRequest,
+    QueueCredential,
+    IssueQueue,
+    ClearQueue,
+    RejectRequest,
+    RestoreBackup,
+    CheckReceipts,
+    RepublishReceipt,
+    TagCredential,
+    FileCredential,
+    CustomizeCredential,
+    ExportFolder,
+    ExportWalletCredential,
+    ExportWallet,
+    ImportWallet,
+    PickLanPeer,
+    TransferCredential,
+    EditCredentialJson,
+    SwitchPseudonym,
+    RefreshCredential,
+    PairDevice,
+    AcceptPairing,
+    DeleteTag,
+    ArchiveCredential,
+    DeleteCredential,
+    SupersedeCredential,
+    ResolveDuplicate,
+    PresentSelected,
+    DeriveAgeOver,
+    SelfAttest,
+    ExportKey,
+    ImportKey,
+    PublishKey,
+    AddMethod,
+    ToggleConsole,
+    RunConsole,
+    PublishJson,
+    LockScreens,
+    TogglePresentationMode,
+    ShiftClock,
+    FinishAndQuit,
+    DiscardAndQuit,
+    Quit,
+}
+
+impl Action {
+    /// Actions offered in the command palette, the others act on the selection of a screen.
+    pub const ALL: [Action; 26] = [
+        Action::ShowHome,
+        Action::ShowIssue,
+        Action::ShowVerify,
+        Action::ShowNodes,
+        Action::ShowRestore,
+        Action::ShowApply,
+        Action::ShowPending,
+        Action::ShowWallet,
+        Action::ShowLog,
+        Action::ShowChain,
+        Action::ShowSettings,
+        Action::ShowDashboard,
+        Action::ShowHistory,
+        Action::ShowKeys,
+        Action::CollectCredentials,
+        Action::CreateIdentity,
+        Action::IssueCredential,
+        Action::VerifyCredential,
+        Action::ResolveIssuer,
+        Action::ExportCredential,
+        Action::ExportJwks,
+        Action::BackupSnapshot,
+        Action::ExportBundle,
+        Action::LockScreens,
+        Action::TogglePresentationMode,
+        Action::Quit,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Action::ShowHome => "Go to Home",
+            Action::ShowIssue => "Go to Issue",
+            Action::ShowVerify => "Go to Verify",
+            Action::ShowNodes => "Go to Nodes",
+            Action::ShowRestore => "Go to Restore",
+            Action::ShowApply => "Request a credential (holder)",
+            Action::ShowPending => "Review credential requests (issuer)",
+            Action::ShowWallet => "Go to Wallet",
+            Action::ShowLog => "Go to Event log",
+            Action::ShowChain => "Explore the message chain of a DID",
+            Action::ShowSettings => "Go to Settings",
+            Action::ShowDashboard => "Show issuer statistics",
+            Action::ShowHistory => "Show published DID messages",
+            Action::ShowKeys => "Show signatures per key",
+            Action::CollectCredentials => "Collect delivered credentials into the wallet",
+            Action::CreateIdentity => "Create holder identity",
+            Action::IssueCredential => "Issue degree credential",
+            Action::PreviewCredential => "Preview credential from the form",
+            Action::IssueFromForm => "Issue credential from the form",
+            Action::VerifyCredential => "Verify credential",
+            Action::TrustIssuer => "Decide about an unknown issuer",
+            Action::ResolveIssuer => "Resolve issuer DID",
+            Action::ResolveIdentity => "Resolve a pinned DID",
+            Action::VerifyFinished => "Show the verification result",
+            Action::ResolveFinished => "Show the resolved DID document",
+            Action::CancelTask => "Cancel the running resolution or verification",
+            Action::TogglePin => "Pin to the Home tab or unpin",
+            Action::ExportCredential => "Export credential to file",
+            Action::RequestPresentation => "Show a presentation request as QR code",
+            Action::ReceivePresentation => "Check the response to the presentation request",
+            Action::ExportReport => "Export the last verification report",
+            Action::ExportJwks => "Export verification methods as JWK Set",
+            Action::ExportResolution => "Export the DID Resolution Result",
+            Action::PinVersion => "Pin the issuer document version to verify against",
+            Action::CompareNodes => "Compare the resolution of a DID across all nodes",
+            Action::BackupSnapshot => "Back up Stronghold snapshot",
+            Action::ExportBundle => "Export the demo scenario as zip bundle",
+            Action::ImportBundle => "Import a demo scenario bundle",
+            Action::SendRequest => "Send credential request",
+            Action::AcceptRequest => "Accept credential request",
+            Action::AcceptOffer => "Accept offered credential into the wallet",
+            Action::QueueCredential => "Queue credential from the Issue form",
+            Action::IssueQueue => "Sign and deliver queued credentials",
+            Action::ClearQueue => "Clear delivered credentials from the queue",
+            Action::RejectRequest => "Reject credential request",
+            Action::RestoreBackup => "Restore snapshot backup",
+            Action::CheckReceipts => "Check confirmation of published DID messages",
+            Action::RepublishReceipt => "Republish an unconfirmed DID message",
+            Action::TagCredential => "Tag wallet credential",
+            Action::FileCredential => "Move wallet credential to a folder",
+            Action::CustomizeCredential => "Rename wallet credential or change its color and icon",
+            Action::ExportFolder => "Export wallet folder",
+            Action::ExportWalletCredential => "Export wallet credential with its acceptance",
+            Action::ExportWallet => "Export the shown wallet credentials as VC JSON",
+            Action::ImportWallet => "Import and verify a VC JSON wallet export",
+            Action::PickLanPeer => "Pick a demo instance on the LAN to send to",
+            Action::TransferCredential => "Send wallet credential to the picked LAN instance",
+            Action::EditCredentialJson => "Save the edited credential JSON into the preview",
+            Action::SwitchPseudonym => "Switch the previewed subject between DID and pseudonym",
+            Action::RefreshCredential => "Renew wallet credential at its refresh service",
+            Action::PairDevice => "Pair a device to receive credentials encrypted",
+            Action::AcceptPairing => "Accept the pairing invitation of a device",
+            Action::DeleteTag => "Delete wallet tag",
+            Action::ArchiveCredential => "Archive or restore wallet credential",
+            Action::DeleteCredential => "Delete wallet credential permanently",
+            Action::SupersedeCredential => "Replace a superseded wallet credential or keep both",
+            Action::ResolveDuplicate => "Merge, skip or keep an imported near duplicate",
+            Action::DeriveAgeOver => "Derive an over-X credential from a birthdate",
+            Action::SelfAttest => "Self-attest a credential about the holder",
+            Action::PresentSelected => "Present marked wallet credentials",
+            Action::ExportKey => "Export holder private key",
+            Action::ImportKey => "Import holder private key",
+            Action::PublishKey => "Publish a DID document for an existing key",
+            Action::AddMethod => "Add holder verification method from JWK",
+            Action::ToggleConsole => "Open or leave the developer console",
+            Action::RunConsole => "Run a developer console command",
+            Action::PublishJson => "Publish a DID document from a JSON file",
+            Action::LockScreens => "Lock wallet and signing screens",
+            Action::TogglePresentationMode => "Toggle presentation mode for screen sharing",
+            Action::ShiftClock => "Shift the simulated clock",
+            Action::FinishAndQuit => "Finish pending work and quit",
+            Action::DiscardAndQuit => "Discard pending work and quit",
+            Action::Quit => "Quit",
+        }
+    }
+
+    /// Whether the action signs with a key or reveals one and is refused while locked.
+    pub fn needs_unlock(&self) -> bool {
+        matches!(
+            self,
+            Action::IssueCredential
+                | Action::IssueFromForm
+                | Action::IssueQueue
+                | Action::AcceptRequest
+                | Action::SendRequest
+                | Action::PresentSelected
+                | Action::DeriveAgeOver
+                | Action::SelfAttest
+                | Action::ExportKey
+                | Action::ImportKey
+                | Action::PublishKey
+                | Action::AddMethod
+                | Action::ExportBundle
+                | Action::ExportWalletCredential
+                | Action::ExportWallet
+                | Action::PairDevice
+        )
+    }
+
+    /// Actions publishing a message to the Tangle, confirmed with their payload first.
+    pub fn writes_tangle(&self) -> bool {
+        matches!(
+            self,
+            Action::CreateIdentity
+                | Action::PublishKey
+                | Action::AddMethod
+                | Action::RepublishReceipt
+                | Action::PublishJson
+        )
+    }
+
+    /// Actions writing backups or exports to disk, disabled in ephemeral runs.
+    pub fn writes_files(&self) -> bool {
+        matches!(
+            self,
+            Action::BackupSnapshot
+                | Action::ExportCredential
+                | Action::ExportJwks
+                | Action::ExportResolution
+                | Action::ExportReport
+                | Action::ExportBundle
+                | Action::ExportFolder
+                | Action::ExportWalletCredential
+                | Action::ExportWallet
+                | Action::ExportKey
+        )
+    }
+
+    /// The capability a profile needs to run the action.
+    pub fn capability(&self) -> Option<Capability> {
+        match self {
+            Action::IssueCredential
+            | Action::PreviewCredential
+            | Action::EditCredentialJson
+            | Action::SwitchPseudonym
+            | Action::IssueFromForm
+            | Action::QueueCredential
+            | Action::IssueQueue
+            | Action::AcceptRequest
+            | Action::RejectRequest
+            // Both have the issuer signer sign a new credential.
+            | Action::DeriveAgeOver
+            | Action::RefreshCredential => Some(Capability::Issue),
+            Action::CreateIdentity
+            | Action::RestoreBackup
+            | Action::AddMethod
+            | Action::RepublishReceipt
+            | Action::PublishKey
+            | Action::PublishJson => Some(Capability::Publish),
+            Action::ExportKey => Some(Capability::ExportKeys),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,4184 @@
+use crate::actions::Action;
+use crate::bus::Event;
+use crate::config::Config;
+use crate::proof::ProofFormat;
+use crate::{
+    account, armor, backup, branding, bundle, bus, claims, clock, compare, config, confirm,
+    consent, console, core, dates, derive, discovery, editor, events, explorer, handle, history,
+    home, hooks, ids, integrity, issue, issued, jwk, keys, link, lint, lock, mobile, modal, nodes,
+    oob, pairing, palette, paths, portable, present, profile, pseudonym, qr, queue, receipts,
+    redact, refresh, renderers, report, request, resolution, retry, revocation, seed, session,
+    sharing, signer, state, stats, status, store, toast, trust, usage, verification, verify,
+    wallet,
+};
+use crossterm::{
+    cursor::Show,
+    event::{Event as CEvent, EventStream, KeyCode, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
+use futures::StreamExt;
+use identity::account::{Account, Result};
+use identity::core::{FromJson, Object, Timestamp, ToJson};
+use identity::credential::{Credential, Presentation};
+use identity::crypto::KeyPair;
+use identity::iota::{Client, IotaDID, IotaDocument, Receipt};
+use qrcode::EcLevel;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap},
+    Terminal,
+};
+use zeroize::Zeroize;
+/// Single-line inputs asked for at the bottom of a screen.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Prompt {
+    RejectReason,
+    Tags,
+    Folder,
+    Appearance,
+    WalletImport,
+    Filter,
+    KeyFormat,
+    KeyPassword,
+    ImportKey,
+    PublishKey,
+    MethodJwk,
+    Trust,
+    Link,
+    AgeThreshold,
+    SelfAttest,
+    RequestTypes,
+    Presentation,
+    ConfirmDelete,
+    Bundle,
+    Supersede,
+    Duplicate,
+    PeerLabel,
+    ClockOffset,
+    CredentialJson,
+    LanPeer,
+    Console,
+    PinVersion,
+    CompareNodes,
+}
+
+impl Prompt {
+    fn label(&self) -> &'static str {
+        match self {
+            Prompt::RejectReason => "Reason for rejecting",
+            Prompt::Tags => "Tags (comma separated)",
+            Prompt::Folder => "Folder (empty removes)",
+            Prompt::Appearance => "Display as name=..., color=#12107c, icon=... (empty resets)",
+            Prompt::WalletImport => "Path of the wallet export (directory or credentials.json)",
+            Prompt::Filter => "Filter by tag (empty clears)",
+            Prompt::KeyFormat => "Format (pem, jwk, hex)",
+            Prompt::KeyPassword => "Stronghold password",
+            Prompt::ImportKey => "Path of a pem, jwk or hex key, or the key itself",
+            Prompt::PublishKey => "Ed25519 key to publish a DID for (path or pem, jwk, hex)",
+            Prompt::MethodJwk => "Ed25519 public JWK of the new holder method",
+            Prompt::Trust => "Trust (o)nce, (a)lways or (b)lock",
+            Prompt::AgeThreshold => "Derive a credential proving the holder is over (empty is 18)",
+            Prompt::SelfAttest => "Claims about yourself (preferredName=Ali, ...)",
+            Prompt::RequestTypes => {
+                "Credential types to request (comma separated, empty accepts any)"
+            }
+            Prompt::Presentation => "Presentation JSON or armor",
+            Prompt::ConfirmDelete => "Type delete to remove the selected credential",
+            Prompt::Bundle => "Path of the scenario bundle (.zip) to import",
+            Prompt::Supersede => "(r)eplace the older credential or (k)eep both",
+            Prompt::Duplicate => "(m)erge keeping the newer, (s)kip the import or (k)eep both",
+            Prompt::PeerLabel => "Label of the device to pair with",
+            Prompt::ClockOffset => "Simulated clock offset (+30d, -2h, empty resets)",
+            Prompt::CredentialJson => "Unsigned credential JSON",
+            Prompt::LanPeer => "Number of the instance to send to (empty clears)",
+            Prompt::Console => "console> (resolve <did>, publish-json <file>, dump-state)",
+            Prompt::CompareNodes => "DID to resolve on every node (empty takes the resolved DID)",
+            Prompt::PinVersion => {
+                "Message id of the issuer version to verify against (empty unpins)"
+            }
+            Prompt::Link => {
+                "Credential JSON, JWS, armor or link (https://...?vc=..., openid-credential-offer://...)"
+            }
+        }
+    }
+
+    /// Prompts taking pasted documents, Enter adds a line instead of confirming.
+    fn multiline(&self) -> bool {
+        matches!(
+            self,
+            Prompt::ImportKey
+                | Prompt::PublishKey
+                | Prompt::MethodJwk
+                | Prompt::Link
+                | Prompt::Presentation
+                | Prompt::CredentialJson
+        )
+    }
+
+    fn open(self) -> Option<(Prompt, editor::Editor)> {
+        Some((self, editor::Editor::new(self.multiline())))
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum MenuItem {
+    Home,
+    Issue,
+    Verify,
+    Nodes,
+    Restore,
+    Apply,
+    Pending,
+    Wallet,
+    Log,
+    Chain,
+    Settings,
+    Dashboard,
+    History,
+    Keys,
+    /// Developer console, hidden from the tabs and opened with F12.
+    Console,
+}
+
+impl MenuItem {
+    /// Tabs in the order of their titles.
+    const ALL: [MenuItem; 14] = [
+        MenuItem::Home,
+        MenuItem::Issue,
+        MenuItem::Verify,
+        MenuItem::Nodes,
+        MenuItem::Restore,
+        MenuItem::Apply,
+        MenuItem::Pending,
+        MenuItem::Wallet,
+        MenuItem::Log,
+        MenuItem::Chain,
+        MenuItem::Settings,
+        MenuItem::Dashboard,
+        MenuItem::History,
+        MenuItem::Keys,
+    ];
+}
+
+impl From<MenuItem> for usize {
+    fn from(input: MenuItem) -> usize {
+        match input {
+            MenuItem::Home => 0,
+            MenuItem::Issue => 1,
+            MenuItem::Verify => 2,
+            MenuItem::Nodes => 3,
+            MenuItem::Restore => 4,
+            MenuItem::Apply => 5,
+            MenuItem::Pending => 6,
+            MenuItem::Wallet => 7,
+            MenuItem::Log => 8,
+            MenuItem::Chain => 9,
+            MenuItem::Settings => 10,
+            MenuItem::Dashboard => 11,
+            MenuItem::History => 12,
+            MenuItem::Keys => 13,
+            // The console writes to the event log, its tab stays highlighted.
+            MenuItem::Console => 8,
+        }
+    }
+}
+
+/// What the command line adds to the config of a run.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// Text the holder keys are derived from, like `--seed`.
+    pub seed: Option<String>,
+    /// Profile limiting the actions, the default one of the config if `None`.
+    pub profile: Option<String>,
+}
+
+/// Raw mode of the terminal while the TUI runs. Dropping it restores the terminal, also when
+/// an error or a panic ends the event loop early.
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<RawMode> {
+        enable_raw_mode()?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), Show);
+    }
+}
+
+/// Runs the TUI with the config until the user quits.
+pub async fn run_app(config: Config) -> Result<(), Box<dyn std::error::Error>> {
+    run_app_with(config, Options::default()).await
+}
+
+/// Runs the TUI with the config and the options of the command line until the user quits.
+pub async fn run_app_with(
+    config: Config,
+    options: Options,
+) -> Result<(), Box<dyn std::error::Error>> {
+    clock::set_offset(clock::parse_offset(&config.clock_offset)?);
+    dates::set(&config.locale, &config.timezone)?;
+    // Kiosk-style runs keep everything in memory and disable what would write files.
+    let ephemeral: bool = config.storage == config::Storage::Memory;
+    let seed: Option<String> = options.seed;
+    let profile: profile::Profile = config
+        .profile(options.profile.as_deref())
+        .ok_or("unknown profile")?;
+
+    let nodes = nodes::from_urls(&config.nodes);
+    nodes::spawn_monitor(nodes.clone(), config.poll_interval());
+
+    let store: Arc<dyn store::IdentityStore> = store::open(&config);
+    let service = core::Service::new(&config, nodes.clone());
+
+    let client = nodes::client(&nodes).await?;
+    store.secure()?;
+    let mut account = Arc::new(account::open(&*store).await?);
+    let issuer_doc: IotaDocument = account::issuer(&account, &config).await?;
+    let mut signer: Box<dyn signer::Signer> =
+        signer::open(&config, account.clone(), &issuer_doc, &client).await?;
+    // The snapshot only exists once the issuer was stored.
+    store.secure()?;
+    let mut issued: issued::IssuanceLog = store.load_issued();
+    let did_id = issuer_doc.id().as_str();
+    let qr_level: EcLevel = qr::parse_level(&config.qr_ec_level);
+
+    let mut session: session::Session = if config.restore_session {
+        session::Session::load(&config.session_path)
+    } else {
+        session::Session::default()
+    };
+    // Only seeded holders can be derived again, without a seed the demo starts with a new one.
+    let mut holder_label: Option<String> = match (&seed, &session.holder) {
+        (Some(_), Some(label)) => Some(label.clone()),
+        _ => Some("holder".to_string()),
+    };
+    let label: &str = holder_label.as_deref().unwrap_or("holder");
+    // Create a signed DID Document/KeyPair for the credential subject (see create_did.rs).
+    let (mut subject_doc, mut subject_key, subject_receipt): (IotaDocument, KeyPair, Receipt) =
+        service
+            .create_identity(seed::keypair(seed.as_deref(), label)?)
+            .await?;
+    let mut holders_created: usize = session::holders_created(label);
+    store.save_receipt(receipts::PublishReceipt::new(
+        &subject_doc,
+        &subject_receipt,
+        receipts::Operation::Create,
+    )?)?;
+
+    let state: state::State = Arc::new(RwLock::new(state::AppState {
+        issuer: issuer_doc.id().to_string(),
+        holder: subject_doc.id().to_string(),
+        wallet: store.load_wallet(),
+        ..Default::default()
+    }));
+    let (bus, mut rx) = bus::Bus::new();
+    status::spawn_monitor(&config, state.clone(), nodes.clone(), bus.clone());
+    receipts::spawn_monitor(&config, store.clone(), nodes.clone(), bus.clone());
+    request::spawn_watcher(
+        &config.inbox_dir,
+        config.poll_interval(),
+        state.clone(),
+        bus.clone(),
+    );
+
+    // Profiles that may not issue start without a credential.
+    let mut credential_str = String::new();
+    if !session.credential.is_empty() && session.holder_did == subject_doc.id().as_str() {
+        // The restored holder still holds the credential of the last session.
+        credential_str = session.credential.clone();
+    } else if profile.check(Action::IssueCredential).is_ok() {
+        let id = ids::generate(
+            config.id_strategy_for(issue::DEGREE),
+            &mut issued,
+            subject_doc.id().as_str(),
+            issue::DEGREE,
+            "",
+        );
+        match issue_degree_credential(
+            &service,
+            &*signer,
+            &id,
+            &subject_doc,
+            config.branding.as_ref(),
+        )
+        .await?
+        {
+            Some(credential) => {
+                issued.record(&credential);
+                store.save_issued(&issued)?;
+                credential_str = credential.to_string();
+            }
+            None => return Err("prerequisite enrollment credential could not be verified".into()),
+        }
+    }
+
+    // The server renews credentials with its own handle on the issuer key.
+    let refresher: signer::Slot = Arc::new(std::sync::RwLock::new(None));
+    if config.credential_store == config::CredentialStore::Http {
+        *refresher.write().unwrap() = Some(Arc::from(
+            signer::open(&config, account.clone(), &issuer_doc, &client).await?,
+        ));
+        revocation::publish(&config, &issued, issuer_doc.id().as_str())?;
+        handle::spawn_server(
+            &config,
+            service.clone(),
+            refresher.clone(),
+            store.clone(),
+            bus.clone(),
+        )?;
+    }
+    // Other demo instances on the LAN, found if built with the `discovery` feature.
+    let discovery: Option<discovery::Discovery> =
+        match discovery::Discovery::start(&config, issuer_doc.id().as_str()) {
+            Ok(discovery) => discovery,
+            Err(error) => {
+                bus.log(format!("LAN discovery is off: {}", error));
+                None
+            }
+        };
+    // Instances listed when picking one, and the one credentials and presentations go to.
+    let mut lan_instances: Vec<discovery::Instance> = Vec::new();
+    let mut transfer_target: Option<discovery::Instance> = None;
+    // The QR only carries a retrieval handle if a credential store is configured.
+    let mut qr_payload: String = String::new();
+    if !credential_str.is_empty() {
+        qr_payload = handle::share(&config, &client, &credential_str).await?;
+    }
+
+    let _raw_mode = RawMode::enable().expect("can run in raw mode");
+
+    // Key presses arrive as a stream on the runtime, ticks redraw while nothing else happens.
+    let mut input = EventStream::new();
+    let mut ticks = tokio::time::interval(Duration::from_millis(200));
+
+    let stdout = io::stdout();
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+    terminal.clear()?;
+
+    // Titles with the position of their key, the first letter unless another tab has it.
+    let menu_titles = vec![
+        ("Home", 0),
+        ("Issue", 0),
+        ("Verify", 0),
+        ("Nodes", 0),
+        ("Restore", 0),
+        ("Apply", 0),
+        ("Pending", 0),
+        ("Wallet", 0),
+        ("Log", 0),
+        ("Chain", 0),
+        ("Settings", 0),
+        ("Dashboard", 0),
+        ("History", 6),
+        ("Keys", 0),
+    ];
+    let mut active_menu_item = menu_titles
+        .iter()
+        .position(|(title, _)| *title == session.tab)
+        .map_or(MenuItem::Home, |index| MenuItem::ALL[index]);
+    let mut node_list_state = ListState::default();
+    node_list_state.select(Some(0));
+    let mut backups: Vec<backup::Backup> = backup::list(&config)?;
+    let mut published: Vec<receipts::PublishReceipt> = store.receipts();
+    let mut published_list_state = ListState::default();
+    published_list_state.select(Some(0));
+    let mut backup_list_state = ListState::default();
+    backup_list_state.select(Some(0));
+    let mut restore_status = String::new();
+    let mut palette = palette::Palette::default();
+    let mut toasts = toast::Toasts::default();
+    let mut status = String::new();
+    // Signatures of keys past their rotation date, warned about once they grow.
+    let mut overdue_signatures: usize = usage::overdue(&usage::per_method(
+        &issued,
+        &config.rotation_due,
+        signer.document(),
+        &subject_doc,
+    ));
+    let mut request_form =
+        claims::ClaimsForm::new(issue::DEGREE, "name=Alice").with_types(&config.credential_types);
+    let mut issue_form = claims::ClaimsForm::new(
+        issue::DEGREE,
+        "degree.type=BachelorDegree, degree.name=Bachelor of Science and Arts, GPA:number=4.0",
+    )
+    .with_types(&config.credential_types)
+    .with_contexts(&config.contexts_for(issue::DEGREE).join(", "));
+    let mut submitted_claims: Object = Object::new();
+    let claim_hooks = hooks::Registry::with_defaults();
+    let detail_renderers = renderers::Registry::with_defaults();
+    // Unsigned credential waiting for the issuer to confirm it.
+    let mut issue_preview: Option<Credential> = None;
+    // Whether the preview shows the credential as card of a mobile wallet instead of its JSON.
+    let mut wallet_preview: bool = false;
+    // Whether the form issues to a pairwise pseudonym of the holder instead of its DID.
+    let mut pseudonymous: bool = config.pseudonymous_subjects;
+    let mut pseudonyms = pseudonym::Pseudonyms::load(&config.pseudonyms_path);
+    // Why the edited credential JSON was not saved, shown in its editor.
+    let mut json_error: Option<String> = None;
+    // Tangle write waiting for confirmation, and the confirmed one its action publishes.
+    let mut pending_write: Option<confirm::TangleWrite> = None;
+    let mut confirmed_write: Option<confirm::TangleWrite> = None;
+    // Tab the console returns to, and the file its publish-json publishes.
+    let mut console_return: MenuItem = MenuItem::Home;
+    let mut console_file: Option<String> = None;
+    // Credential offered by a pasted link, waiting for the holder to accept it.
+    let mut offer: Option<String> = None;
+    let mut peers = pairing::Peers::load(&config.peers_path);
+    // Invitation of a device scanned from its pairing QR, waiting for a label.
+    let mut pending_invitation: Option<String> = None;
+    // Link of the pairing QR shown to the other device.
+    let mut pairing_link: Option<String> = None;
+    // Credentials drafted for one signing and delivery run.
+    let mut issue_queue = queue::IssueQueue::default();
+    // Unfinished work listed in the quit dialog, empty while it is closed.
+    let mut pending_work: Vec<String> = Vec::new();
+    let mut request_status = String::new();
+    let mut incoming: Vec<request::Incoming> = Vec::new();
+    let mut incoming_list_state = ListState::default();
+    incoming_list_state.select(Some(0));
+    let mut prompt: Option<(Prompt, editor::Editor)> = None;
+    let mut prompt_input = String::new();
+    let mut key_format: Option<keys::KeyFormat> = None;
+    let mut wallet_list_state = ListState::default();
+    // Cards of the Home tab, what F pins or unpins and the pinned DID enter resolves.
+    let mut home_list_state = ListState::default();
+    let mut pin_target: Option<home::Pin> = None;
+    let mut resolve_did: Option<String> = None;
+    // How the Verify tab shows the public keys of the resolved document.
+    let mut key_encoding = jwk::Encoding::Jwk;
+    // The resolution or verification running in the background, Esc aborts it, and its outcome.
+    let mut running: Option<(u64, tokio::task::JoinHandle<()>)> = None;
+    let mut tasks_started: u64 = 0;
+    let mut finished: Option<bus::Done> = None;
+    wallet_list_state.select(session.wallet_selected.or(Some(0)));
+    let mut wallet_filter: Option<String> = session.wallet_filter.clone();
+    let mut wallet_archived = session.wallet_archived;
+    // Statistics instead of the selected credential next to the wallet list.
+    let mut wallet_stats = false;
+    // Masks claim values and shortens DIDs for screen sharing and recordings.
+    let mut presentation_mode: bool = config.presentation_mode;
+    // Wallet entries marked to be bundled into one presentation.
+    let mut presentation_selection: BTreeSet<String> = BTreeSet::new();
+    let mut presentation_report: Option<verify::PresentationReport> = None;
+    let mut chain_messages: Vec<explorer::Message> = Vec::new();
+    let mut chain_list_state = ListState::default();
+    let mut chain_did = session.chain_did.clone();
+    let mut integrity_report: Option<integrity::Report> = None;
+    let mut resolved_doc: Option<IotaDocument> = None;
+    let mut trust: trust::TrustStore = store.load_trust();
+    // Issuer and verification outcome waiting for a trust decision.
+    let mut pending_trust: Option<(String, String)> = None;
+    // Newly stored credentials and the older ones they supersede, waiting for a decision.
+    let mut pending_supersessions: Vec<(String, String)> = Vec::new();
+    // Imported credentials and the held near duplicates they match, waiting for a decision.
+    let mut pending_duplicates: Vec<(String, Credential)> = Vec::new();
+    // Outcome of the last credential verification, exported on demand.
+    let mut last_report: Option<report::VerificationReport> = None;
+    let mut verification_history: history::VerificationHistory = store.load_history();
+    let mut history_list_state = ListState::default();
+    history_list_state.select(Some(0));
+    // Past report reopened from the history.
+    let mut opened_report: Option<report::VerificationReport> = None;
+    // What each node resolved a DID to, from the Nodes tab.
+    let mut node_comparison: Option<String> = None;
+    // Credential to verify instead of the issued one, like one opened in the wallet.
+    let mut verify_input: Option<String> = None;
+    // Out-of-band request of the verifier waiting for a presentation.
+    let mut presentation_request: Option<oob::PresentationRequest> = None;
+    let mut lock = lock::Lock::new(
+        config.pin_hash.clone(),
+        Some(store::password()).filter(|_| config.lock_with_password),
+        Duration::from_secs(config.lock_after),
+        Some(Duration::from_secs(config.session_timeout)).filter(|_| config.session_timeout > 0),
+    );
+
+    loop {
+        lock.check_idle();
+        let current = session::Session {
+            tab: menu_titles[usize::from(active_menu_item)].0.to_string(),
+            holder: holder_label.clone(),
+            holder_did: subject_doc.id().to_string(),
+            credential: credential_str.clone(),
+            wallet_selected: wallet_list_state.selected(),
+            wallet_filter: wallet_filter.clone(),
+            wallet_archived,
+            chain_did: chain_did.clone(),
+        };
+        if config.restore_session && current != session {
+            if let Err(error) = current.save(&config.session_path) {
+                bus.log(format!("Saving the session failed: {}", error));
+            }
+            session = current;
+        }
+        // Wallet and signing screens are hidden behind the PIN while locked.
+        let lock_screen: bool = lock.is_locked()
+            && matches!(
+                active_menu_item,
+                MenuItem::Issue | MenuItem::Wallet | MenuItem::Apply
+            );
+        // Ask about superseded credentials one after the other once nothing else is asked.
+        if prompt.is_none() && pending_trust.is_none() && !pending_supersessions.is_empty() {
+            prompt = Prompt::Supersede.open();
+        }
+        if prompt.is_none()
+            && pending_trust.is_none()
+            && pending_supersessions.is_empty()
+            && !pending_duplicates.is_empty()
+        {
+            prompt = Prompt::Duplicate.open();
+        }
+        let masked_input: String = match &prompt {
+            Some((Prompt::KeyPassword, input)) => "*".repeat(input.text().chars().count()),
+            Some((_, input)) => input.text(),
+            None => String::new(),
+        };
+        let trust_warning: String = pending_trust
+            .as_ref()
+            .map(|(issuer, _)| {
+                format!(
+                    "{} issued a valid credential but is not known yet, accept it?",
+                    issuer
+                )
+            })
+            .unwrap_or_default();
+        let supersede_warning: String = match (&prompt, pending_supersessions.first()) {
+            (Some((Prompt::Supersede, _)), Some((_, older))) => {
+                let app = state.read().await;
+                let older = app.wallet.entries.iter().find(|entry| &entry.id == older);
+                older
+                    .map(|older| {
+                        format!(
+                            "A new {} of the same issuer and subject supersedes the one added {}",
+                            older.title(),
+                            older.added
+                        )
+                    })
+                    .unwrap_or_default()
+            }
+            _ => String::new(),
+        };
+        let duplicate_warning: String = match (&prompt, pending_duplicates.first()) {
+            (Some((Prompt::Duplicate, _)), Some((held, imported))) => {
+                let app = state.read().await;
+                let held = app.wallet.entries.iter().find(|entry| &entry.id == held);
+                held.map(|held| {
+                    format!(
+                        "The imported {} issued {} duplicates the held one issued {}",
+                        held.title(),
+                        dates::date(imported.issuance_date),
+                        dates::date(held.credential.issuance_date)
+                    )
+                })
+                .unwrap_or_default()
+            }
+            _ => String::new(),
+        };
+        let prompt_title: Option<String> = prompt
+            .as_ref()
+            .map(|(prompt, _)| format!("{} (enter confirms): {}", prompt.label(), masked_input));
+        // Background tasks only read the state, the guard is dropped before waiting for input.
+        let app = state.read().await;
+        let wallet_entries: Vec<wallet::Entry> = app
+            .wallet
+            .filtered(wallet_filter.as_deref(), wallet_archived)
+            .into_iter()
+            .cloned()
+            .collect();
+        let home_pins: Vec<home::Pin> = home::pins(&app.wallet);
+
+        let shown_log: Vec<events::LogEntry> = if presentation_mode {
+            redact::log(&app.log)
+        } else {
+            app.log.clone()
+        };
+        let shown_did: String = if presentation_mode {
+            redact::dids(did_id)
+        } else {
+            did_id.to_string()
+        };
+
+        terminal.draw(|rect| {
+            let size = rect.size();
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .margin(2)
+                .constraints(
+                    [
+                        Constraint::Length(3),
+                        Constraint::Min(2),
+                        Constraint::Length(3),
+                    ]
+                    .as_ref(),
+                )
+                .split(size);
+
+            // A cancelled task may have reported progress just before it was aborted.
+            let shown_status: &String = match &app.progress {
+                Some(progress) if running.is_some() => progress,
+                _ => &status,
+            };
+            let footer = if shown_status.is_empty() {
+                "Do what you want.".to_string()
+            } else if presentation_mode {
+                redact::dids(shown_status)
+            } else {
+                shown_status.clone()
+            };
+            // A shifted clock changes every expiry, it must never go unnoticed.
+            let (footer, footer_color) = match clock::label() {
+                Some(offset) => (
+                    format!("[SIMULATED CLOCK {}] {}", offset, footer),
+                    Color::LightYellow,
+                ),
+                None => (footer, Color::LightCyan),
+            };
+            // How long the unlocked wallet and signing screens stay open.
+            let footer = match lock.remaining() {
+                Some(remaining) => format!(
+                    "{} [locks in {}:{:02}]",
+                    footer,
+                    remaining.as_secs() / 60,
+                    remaining.as_secs() % 60
+                ),
+                None => footer,
+            };
+            let copyright = Paragraph::new(footer)
+                .style(Style::default().fg(footer_color))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .style(Style::default().fg(Color::White))
+                        .title("Copyright")
+                        .border_type(BorderType::Plain),
+                );
+
+            let menu = menu_titles
+                .iter()
+                .map(|(t, key)| {
+                    let (before, rest) = t.split_at(*key);
+                    let (key, after) = rest.split_at(1);
+                    Spans::from(vec![
+                        Span::styled(before, Style::default().fg(Color::White)),
+                        Span::styled(
+                            key,
+                            Style::default()
+                                .fg(Color::Yellow)
+                                .add_modifier(Modifier::UNDERLINED),
+                        ),
+                        Span::styled(after, Style::default().fg(Color::White)),
+                    ])
+                })
+                .collect();
+
+            let mut menu_title = vec![Span::raw(format!("Menu (profile: {})", profile.name))];
+            if presentation_mode {
+                menu_title.push(Span::styled(
+                    " PRESENTATION MODE ",
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::LightMagenta)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            if ephemeral {
+                menu_title.push(Span::styled(
+                    " EPHEMERAL, NOTHING IS SAVED ",
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::LightYellow)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            if seed.is_some() {
+                menu_title.push(Span::styled(
+                    " SEEDED, INSECURE KEYS ",
+                    Style::default()
+                        .fg(Color::White)
+                        .bg(Color::Red)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            let tabs = Tabs::new(menu)
+                .select(active_menu_item.into())
+                .block(
+                    Block::default()
+                        .title(Spans::from(menu_title))
+                        .borders(Borders::ALL),
+                )
+                .style(Style::default().fg(Color::White))
+                .highlight_style(Style::default().fg(Color::Yellow))
+                .divider(Span::raw("|"));
+
+            rect.render_widget(tabs, chunks[0]);
+            match active_menu_item {
+                _ if lock_screen => rect.render_widget(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .style(Style::default().fg(Color::White))
+                        .title("Locked")
+                        .border_type(BorderType::Plain),
+                    chunks[1],
+                ),
+                MenuItem::Home => rect.render_stateful_widget(
+                    home::render(
+                        &home_pins,
+                        &app.wallet,
+                        &app.statuses,
+                        subject_doc.id().as_str(),
+                        issuer_doc.id().as_str(),
+                        presentation_mode,
+                    ),
+                    chunks[1],
+                    &mut home_list_state,
+                ),
+                MenuItem::Issue if issue_queue.is_empty() => rect.render_widget(
+                    render_issue(&shown_did, &qr_payload, qr_level, &issue_form),
+                    chunks[1],
+                ),
+                MenuItem::Issue => {
+                    let rows = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+                        .split(chunks[1]);
+                    rect.render_widget(
+                        render_issue(&shown_did, &qr_payload, qr_level, &issue_form),
+                        rows[0],
+                    );
+                    rect.render_widget(queue::render(&issue_queue), rows[1]);
+                }
+                MenuItem::Verify => {
+                    let columns = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+                        .split(chunks[1]);
+                    rect.render_widget(
+                        render_verify(
+                            integrity_report.as_ref(),
+                            resolved_doc.as_ref(),
+                            presentation_report.as_ref(),
+                            presentation_request.as_ref(),
+                            qr_level,
+                            presentation_mode,
+                            key_encoding,
+                        ),
+                        columns[0],
+                    );
+                    rect.render_stateful_widget(
+                        history::render(&verification_history),
+                        columns[1],
+                        &mut history_list_state,
+                    );
+                }
+                MenuItem::Nodes => {
+                    let routed = nodes::route(&nodes);
+                    let list = render_nodes(&nodes.lock().unwrap(), &routed);
+                    rect.render_stateful_widget(list, chunks[1], &mut node_list_state);
+                }
+                MenuItem::Restore => {
+                    let list = render_restore(&backups, &restore_status);
+                    rect.render_stateful_widget(list, chunks[1], &mut backup_list_state);
+                }
+                MenuItem::Apply => {
+                    let holder = subject_doc.id().as_str();
+                    let responses = request::responses(&config.inbox_dir, holder);
+                    rect.render_widget(
+                        request::render_request(&request_form, holder, &request_status, &responses),
+                        chunks[1],
+                    )
+                }
+                MenuItem::Pending => {
+                    let list = request::render_inbox(&incoming, prompt_title.as_deref());
+                    rect.render_stateful_widget(list, chunks[1], &mut incoming_list_state);
+                }
+                MenuItem::Wallet => {
+                    let entries: Vec<&wallet::Entry> = wallet_entries.iter().collect();
+                    let list = wallet::render(
+                        &entries,
+                        &app.statuses,
+                        &presentation_selection,
+                        wallet_filter.as_deref(),
+                        wallet_archived,
+                        &pseudonyms,
+                        prompt_title.as_deref(),
+                    );
+                    let columns = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                        .split(chunks[1]);
+                    rect.render_stateful_widget(list, columns[0], &mut wallet_list_state);
+                    let selected = wallet_list_state.selected().and_then(|i| entries.get(i));
+                    if wallet_stats {
+                        rect.render_widget(
+                            stats::render_wallet(
+                                &stats::wallet(&app.wallet, &app.statuses),
+                                presentation_mode,
+                            ),
+                            columns[1],
+                        );
+                    } else if let Some(entry) = selected {
+                        let chain = app.wallet.chain(&entry.id);
+                        let detail = Layout::default()
+                            .direction(Direction::Vertical)
+                            .constraints([
+                                Constraint::Min(8),
+                                Constraint::Length(if chain.len() > 1 {
+                                    chain.len() as u16 + 2
+                                } else {
+                                    0
+                                }),
+                            ])
+                            .split(columns[1]);
+                        rect.render_widget(
+                            branding::card(
+                                &entry.credential,
+                                detail_renderers.rows(&entry.credential),
+                                presentation_mode,
+                            ),
+                            detail[0],
+                        );
+                        if chain.len() > 1 {
+                            rect.render_widget(wallet::render_chain(&chain, &entry.id), detail[1]);
+                        }
+                    }
+                }
+                MenuItem::Log => rect.render_widget(events::render(&shown_log), chunks[1]),
+                MenuItem::Console => console::render(rect, chunks[1], &shown_log),
+                MenuItem::Dashboard => stats::render(rect, chunks[1], &issued),
+                MenuItem::Keys => rect.render_widget(
+                    usage::render(&usage::per_method(
+                        &issued,
+                        &config.rotation_due,
+                        signer.document(),
+                        &subject_doc,
+                    )),
+                    chunks[1],
+                ),
+                MenuItem::History => rect.render_stateful_widget(
+                    receipts::render(&published, &app.inclusions, presentation_mode),
+                    chunks[1],
+                    &mut published_list_state,
+                ),
+                MenuItem::Settings => rect.render_widget(
+                    render_settings(&config, &profile, &*store, &*signer),
+                    chunks[1],
+                ),
+                MenuItem::Chain => {
+                    let columns = explorer::layout(chunks[1]);
+                    let list = explorer::render_list(&chain_messages, &chain_did);
+                    rect.render_stateful_widget(list, columns[0], &mut chain_list_state);
+                    let selected = chain_list_state
+                        .selected()
+                        .and_then(|i| chain_messages.get(i));
+                    let receipt = selected
+                        .and_then(|message| receipts::by_message(&published, &message.message_id));
+                    rect.render_widget(explorer::render_detail(selected, receipt), columns[1]);
+                }
+            }
+            rect.render_widget(copyright, chunks[2]);
+            toasts.render(rect, chunks[1], &shown_log);
+            match prompt.as_ref() {
+                Some((kind @ Prompt::KeyFormat, _)) | Some((kind @ Prompt::KeyPassword, _)) => {
+                    modal::render(
+                        rect,
+                        chunks[1],
+                        "Export private key",
+                        keys::EXPORT_WARNING,
+                        kind.label(),
+                        &masked_input,
+                    )
+                }
+                Some((kind @ Prompt::ImportKey, editor))
+                | Some((kind @ Prompt::PublishKey, editor)) => editor.render(
+                    rect,
+                    chunks[1],
+                    "Import private key",
+                    Some(keys::IMPORT_WARNING),
+                    kind.label(),
+                ),
+                Some((kind @ Prompt::CredentialJson, editor)) => editor.render(
+                    rect,
+                    chunks[1],
+                    "Edit the unsigned credential",
+                    json_error.as_deref(),
+                    kind.label(),
+                ),
+                Some((kind @ Prompt::MethodJwk, editor)) => {
+                    // Checked as it is typed, so collisions show before anything is published.
+                    let text: String = editor.text();
+                    let error: Option<String> = match text.trim() {
+                        "" => None,
+                        input => jwk::parse_method(&subject_doc, input)
+                            .err()
+                            .map(|error| format!("Can't add: {}", error)),
+                    };
+                    editor.render(rect, chunks[1], "Add verification method", error.as_deref(), kind.label())
+                }
+                Some((kind, editor)) if editor.is_multiline() => {
+                    editor.render(rect, chunks[1], "Paste", None, kind.label())
+                }
+                Some((kind @ Prompt::ConfirmDelete, _)) => modal::render(
+                    rect,
+                    chunks[1],
+                    "Delete credential",
+                    wallet::DELETE_WARNING,
+                    kind.label(),
+                    &masked_input,
+                ),
+                Some((kind @ Prompt::LanPeer, _)) => modal::render(
+                    rect,
+                    chunks[1],
+                    "Demo instances on the LAN",
+                    &lan_instances
+                        .iter()
+                        .enumerate()
+                        .map(|(i, instance)| {
+                            format!("{}. {} at {} ({})", i + 1, instance.name, instance.url, instance.did)
+                        })
+                        .collect::<Vec<String>>()
+                        .join("\n"),
+                    kind.label(),
+                    &masked_input,
+                ),
+                Some((kind @ Prompt::Supersede, _)) => modal::render(
+                    rect,
+                    chunks[1],
+                    "Superseded credential",
+                    &supersede_warning,
+                    kind.label(),
+                    &masked_input,
+                ),
+                Some((kind @ Prompt::Duplicate, _)) => modal::render(
+                    rect,
+                    chunks[1],
+                    "Duplicate credential",
+                    &duplicate_warning,
+                    kind.label(),
+                    &masked_input,
+                ),
+                Some((kind @ Prompt::Trust, _)) => modal::render(
+                    rect,
+                    chunks[1],
+                    "Unknown issuer",
+                    &trust_warning,
+                    kind.label(),
+                    &masked_input,
+                ),
+                _ => {}
+            }
+            if !pending_work.is_empty() {
+                modal::render_document(
+                    rect,
+                    chunks[1],
+                    "Quit with pending work?",
+                    &pending_work.join("\n"),
+                    "f finishes pending work and quits, d discards it and quits, esc cancels",
+                );
+            } else if let Some(write) = &pending_write {
+                modal::render_document(
+                    rect,
+                    chunks[1],
+                    &format!("Confirm: {}", write.action.title()),
+                    &if presentation_mode {
+                        redact::dids(&write.text())
+                    } else {
+                        write.text()
+                    },
+                    "enter publishes it to the Tangle, esc cancels",
+                );
+            } else if let Some(comparison) = &node_comparison {
+                modal::render_document(
+                    rect,
+                    chunks[1],
+                    "Resolution across nodes",
+                    &if presentation_mode {
+                        redact::dids(comparison)
+                    } else {
+                        comparison.clone()
+                    },
+                    "esc closes",
+                );
+            } else if let Some(report) = &opened_report {
+                modal::render_document(
+                    rect,
+                    chunks[1],
+                    "Verification report",
+                    &if presentation_mode {
+                        redact::dids(&report.text())
+                    } else {
+                        report.text()
+                    },
+                    "x exports it, esc closes",
+                );
+            } else if let Some(link) = &pairing_link {
+                let code = qr::render(link, qr_level).unwrap_or_else(|error| error.to_string());
+                modal::render_document(
+                    rect,
+                    chunks[1],
+                    "Pairing QR",
+                    &format!("{}\n{}", code, link),
+                    "scan it or paste the link with u on the Verify tab of the other device, esc closes",
+                );
+            } else if let Some(offer) = &offer {
+                let document = if presentation_mode {
+                    redact::document(offer)
+                } else {
+                    serde_json::from_str::<serde_json::Value>(offer)
+                        .and_then(|value| serde_json::to_string_pretty(&value))
+                        .unwrap_or_else(|_| offer.clone())
+                };
+                modal::render_document(
+                    rect,
+                    chunks[1],
+                    "Credential offer",
+                    &document,
+                    "enter verifies and accepts it into the wallet, esc declines",
+                );
+            } else if let Some(preview) = issue_preview
+                .as_ref()
+                .filter(|_| prompt.is_none() && wallet_preview)
+            {
+                let mut rows: Vec<(String, String)> = detail_renderers.rows(preview);
+                if presentation_mode {
+                    for (_, value) in rows.iter_mut() {
+                        *value = redact::MASK.to_string();
+                    }
+                }
+                let mut card = mobile::MobileCard::new(preview, rows);
+                if presentation_mode {
+                    card.subtitle = redact::dids(&card.subtitle);
+                }
+                card.render(
+                    rect,
+                    chunks[1],
+                    "w shows the JSON, enter signs and issues, esc cancels",
+                );
+            } else if let Some(preview) = issue_preview.as_ref().filter(|_| prompt.is_none()) {
+                let mut document: String = lint::credential(preview)
+                    .iter()
+                    .map(|warning| format!("Warning: {}\n", warning))
+                    .collect();
+                document.push_str(&match preview.to_json_pretty() {
+                    Ok(json) if presentation_mode => redact::document(&json),
+                    Ok(json) => json,
+                    Err(error) => error.to_string(),
+                });
+                modal::render_document(
+                    rect,
+                    chunks[1],
+                    if pseudonymous {
+                        "Preview of the unsigned credential, issued to a pseudonym"
+                    } else {
+                        "Preview of the unsigned credential"
+                    },
+                    &document,
+                    if config.expert_mode {
+                        "enter signs and issues, p switches the pseudonym, w previews the wallet card, e edits the JSON, esc cancels"
+                    } else {
+                        "enter signs and issues, p switches the pseudonym, w previews the wallet card, esc cancels"
+                    },
+                );
+            }
+            if lock_screen {
+                modal::render(
+                    rect,
+                    chunks[1],
+                    "Locked",
+                    lock.error
+                        .as_deref()
+                        .unwrap_or("Wallet and signing screens are locked"),
+                    lock.label(),
+                    &lock.masked(),
+                );
+            }
+            if palette.visible {
+                palette.render(rect, chunks[1]);
+            }
+        })?;
+        drop(app);
+
+        let event: Event = tokio::select! {
+            Some(event) = rx.recv() => event,
+            Some(input) = input.next() => match input? {
+                CEvent::Key(key) => Event::Input(key),
+                // A resize only needs the redraw every event gets.
+                _ => Event::Tick,
+            },
+            _ = ticks.tick() => Event::Tick,
+        };
+        if let Event::Input(_) = &event {
+            lock.touch();
+        }
+        let action: Option<Action> = match event {
+            // Raw mode turns Ctrl-C into a key press instead of a signal.
+            Event::Input(event)
+                if event.code == KeyCode::Char('c')
+                    && event.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                Some(Action::Quit)
+            }
+            Event::Input(event) if running.is_some() && event.code == KeyCode::Esc => {
+                Some(Action::CancelTask)
+            }
+            Event::Input(event) if !pending_work.is_empty() => match event.code {
+                KeyCode::Char('f') => Some(Action::FinishAndQuit),
+                KeyCode::Char('d') => Some(Action::DiscardAndQuit),
+                KeyCode::Esc => {
+                    pending_work.clear();
+                    None
+                }
+                _ => None,
+            },
+            Event::Input(event) if lock_screen => match event.code {
+                KeyCode::Esc => Some(Action::ShowHome),
+                _ => {
+                    if lock.handle_key(event) {
+                        status = "Unlocked".to_string();
+                    }
+                    None
+                }
+            },
+            Event::Input(event) if palette.visible => palette.handle_key(event),
+            Event::Input(event) if prompt.is_some() => match event.code {
+                KeyCode::Esc => {
+                    // Cancelling the supersession question keeps both credentials,
+                    // cancelling the duplicate question skips the import.
+                    match prompt.take() {
+                        Some((Prompt::Supersede, _)) if !pending_supersessions.is_empty() => {
+                            pending_supersessions.remove(0);
+                        }
+                        Some((Prompt::Duplicate, _)) if !pending_duplicates.is_empty() => {
+                            pending_duplicates.remove(0);
+                        }
+                        _ => {}
+                    }
+                    None
+                }
+                _ => match prompt
+                    .as_mut()
+                    .and_then(|(_, input)| input.handle_key(event))
+                {
+                    None => None,
+                    Some(input) => {
+                        let kind = prompt.take().map_or(Prompt::Filter, |(kind, _)| kind);
+                        prompt_input = input;
+                        match kind {
+                            Prompt::RejectReason => Some(Action::RejectRequest),
+                            Prompt::Tags => Some(Action::TagCredential),
+                            Prompt::Folder => Some(Action::FileCredential),
+                            Prompt::Appearance => Some(Action::CustomizeCredential),
+                            Prompt::WalletImport => Some(Action::ImportWallet),
+                            Prompt::KeyFormat => {
+                                match prompt_input.parse::<keys::KeyFormat>() {
+                                    Ok(format) => {
+                                        key_format = Some(format);
+                                        prompt = Prompt::KeyPassword.open();
+                                    }
+                                    Err(error) => status = error,
+                                }
+                                None
+                            }
+                            Prompt::KeyPassword => Some(Action::ExportKey),
+                            Prompt::ImportKey => Some(Action::ImportKey),
+                            Prompt::PublishKey => Some(Action::PublishKey),
+                            Prompt::MethodJwk => {
+                                match jwk::parse_method(&subject_doc, &prompt_input) {
+                                    Ok(_) => Some(Action::AddMethod),
+                                    Err(error) => {
+                                        // Nothing is published, the JWK stays in the editor to fix it.
+                                        status = format!("Not added: {}", error);
+                                        prompt = Some((
+                                            Prompt::MethodJwk,
+                                            editor::Editor::with_text(&prompt_input, true),
+                                        ));
+                                        None
+                                    }
+                                }
+                            }
+                            Prompt::Trust => Some(Action::TrustIssuer),
+                            Prompt::AgeThreshold => Some(Action::DeriveAgeOver),
+                            Prompt::SelfAttest => Some(Action::SelfAttest),
+                            Prompt::RequestTypes => Some(Action::RequestPresentation),
+                            Prompt::Presentation => Some(Action::ReceivePresentation),
+                            Prompt::ConfirmDelete if prompt_input.trim() == "delete" => {
+                                Some(Action::DeleteCredential)
+                            }
+                            Prompt::ConfirmDelete => {
+                                status = "Nothing deleted".to_string();
+                                None
+                            }
+                            Prompt::Bundle => Some(Action::ImportBundle),
+                            Prompt::PeerLabel if pending_invitation.is_some() => {
+                                Some(Action::AcceptPairing)
+                            }
+                            Prompt::PeerLabel => Some(Action::PairDevice),
+                            Prompt::ClockOffset => Some(Action::ShiftClock),
+                            Prompt::LanPeer => Some(Action::PickLanPeer),
+                            Prompt::Console => Some(Action::RunConsole),
+                            Prompt::PinVersion => Some(Action::PinVersion),
+                            Prompt::CompareNodes => Some(Action::CompareNodes),
+                            Prompt::CredentialJson => Some(Action::EditCredentialJson),
+                            Prompt::Supersede => Some(Action::SupersedeCredential),
+                            Prompt::Duplicate => Some(Action::ResolveDuplicate),
+                            Prompt::Link => match link::decode(&prompt_input) {
+                                Ok(link::Payload::Verify(payload)) => {
+                                    qr_payload = payload;
+                                    Some(Action::VerifyCredential)
+                                }
+                                Ok(link::Payload::Offer(payload)) => {
+                                    offer = Some(payload);
+                                    None
+                                }
+                                Ok(link::Payload::Pair(invitation)) => {
+                                    pending_invitation = Some(invitation);
+                                    prompt = Prompt::PeerLabel.open();
+                                    None
+                                }
+                                Err(error) => {
+                                    status = error;
+                                    None
+                                }
+                            },
+                            Prompt::Filter => {
+                                wallet_filter = Some(prompt_input.trim().to_string())
+                                    .filter(|tag| !tag.is_empty());
+                                wallet_list_state.select(Some(0));
+                                None
+                            }
+                        }
+                    }
+                },
+            },
+            Event::Input(event) if pending_write.is_some() => match event.code {
+                KeyCode::Enter => {
+                    confirmed_write = pending_write.take();
+                    confirmed_write.as_ref().map(|write| write.action)
+                }
+                KeyCode::Esc => {
+                    pending_write = None;
+                    prompt_input.zeroize();
+                    status = "Cancelled, nothing was published".to_string();
+                    None
+                }
+                _ => None,
+            },
+            Event::Input(event) if node_comparison.is_some() => match event.code {
+                KeyCode::Esc => {
+                    node_comparison = None;
+                    None
+                }
+                _ => None,
+            },
+            Event::Input(event) if opened_report.is_some() => match event.code {
+                KeyCode::Char('x') => Some(Action::ExportReport),
+                KeyCode::Esc => {
+                    opened_report = None;
+                    None
+                }
+                _ => None,
+            },
+            Event::Input(event) if pairing_link.is_some() => match event.code {
+                KeyCode::Esc => {
+                    pairing_link = None;
+                    None
+                }
+                _ => None,
+            },
+            Event::Input(event) if offer.is_some() => match event.code {
+                KeyCode::Enter => Some(Action::AcceptOffer),
+                KeyCode::Esc => {
+                    offer = None;
+                    status = "Offer declined".to_string();
+                    None
+                }
+                _ => None,
+            },
+            Event::Input(event) if issue_preview.is_some() => match event.code {
+                KeyCode::Enter => Some(Action::IssueFromForm),
+                KeyCode::Char('p') => Some(Action::SwitchPseudonym),
+                KeyCode::Char('w') => {
+                    wallet_preview = !wallet_preview;
+                    None
+                }
+                KeyCode::Char('e') if config.expert_mode => {
+                    if let Some(Ok(json)) = issue_preview.as_ref().map(|c| c.to_json_pretty()) {
+                        json_error = None;
+                        prompt = Some((
+                            Prompt::CredentialJson,
+                            editor::Editor::with_text(&json, true),
+                        ));
+                    }
+                    None
+                }
+                KeyCode::Esc => {
+                    issue_preview = None;
+                    status = "Issuance cancelled, nothing was signed".to_string();
+                    None
+                }
+                _ => None,
+            },
+            Event::Input(event) if request_form.editing => {
+                request_form.handle_key(event).map(|claims| {
+                    submitted_claims = claims;
+                    Action::SendRequest
+                })
+            }
+            Event::Input(event) if issue_form.editing => {
+                issue_form.handle_key(event).map(|claims| {
+                    submitted_claims = claims;
+                    Action::PreviewCredential
+                })
+            }
+            Event::Input(event) => match event.code {
+                KeyCode::Char('p') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    palette.open();
+                    None
+                }
+                KeyCode::Char('q') => Some(Action::Quit),
+                KeyCode::Char('h') => Some(Action::ShowHome),
+                KeyCode::Char('i') => Some(Action::ShowIssue),
+                KeyCode::Char('v') => Some(Action::ShowVerify),
+                KeyCode::Char('n') => Some(Action::ShowNodes),
+                KeyCode::Char('r') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Some(Action::TogglePresentationMode)
+                }
+                KeyCode::Char('r') => Some(Action::ShowRestore),
+                KeyCode::Char('a') => Some(Action::ShowApply),
+                KeyCode::Char('p') => Some(Action::ShowPending),
+                KeyCode::Char('w') => Some(Action::ShowWallet),
+                KeyCode::Char('l') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Some(Action::LockScreens)
+                }
+                KeyCode::Char('l') => Some(Action::ShowLog),
+                KeyCode::Char('c') => Some(Action::ShowChain),
+                KeyCode::Char('s') => Some(Action::ShowSettings),
+                KeyCode::Char('y') => Some(Action::ShowHistory),
+                KeyCode::F(12) => Some(Action::ToggleConsole),
+                KeyCode::Enter if matches!(active_menu_item, MenuItem::Console) => {
+                    prompt = Prompt::Console.open();
+                    None
+                }
+                // On the Wallet tab d deletes a tag.
+                KeyCode::Char('d') if !matches!(active_menu_item, MenuItem::Wallet) => {
+                    Some(Action::ShowDashboard)
+                }
+                // On the Apply and Verify tabs k is taken.
+                KeyCode::Char('k')
+                    if !matches!(active_menu_item, MenuItem::Apply | MenuItem::Verify) =>
+                {
+                    Some(Action::ShowKeys)
+                }
+                KeyCode::Esc => {
+                    toasts.dismiss(&state.read().await.log);
+                    None
+                }
+                KeyCode::Char('o') if matches!(active_menu_item, MenuItem::Settings) => {
+                    prompt = Prompt::ClockOffset.open();
+                    None
+                }
+                KeyCode::Char('e') if matches!(active_menu_item, MenuItem::Apply) => {
+                    request_form.editing = true;
+                    None
+                }
+                KeyCode::Char('k') if matches!(active_menu_item, MenuItem::Apply) => {
+                    // Refuse before the password is typed in.
+                    match profile.check(Action::ExportKey) {
+                        Ok(()) => prompt = Prompt::KeyFormat.open(),
+                        Err(capability) => {
+                            status = format!(
+                                "Profile {} lacks the {:?} capability",
+                                profile.name, capability
+                            )
+                        }
+                    }
+                    None
+                }
+                KeyCode::Char('o') if matches!(active_menu_item, MenuItem::Apply) => {
+                    prompt = Prompt::ImportKey.open();
+                    None
+                }
+                KeyCode::Char('g') if matches!(active_menu_item, MenuItem::Apply) => {
+                    prompt = Prompt::PublishKey.open();
+                    None
+                }
+                KeyCode::Char('m') if matches!(active_menu_item, MenuItem::Apply) => {
+                    prompt = Prompt::MethodJwk.open();
+                    None
+                }
+                KeyCode::Char('j') if matches!(active_menu_item, MenuItem::Verify) => {
+                    Some(Action::ExportJwks)
+                }
+                KeyCode::Char('D') if matches!(active_menu_item, MenuItem::Verify) => {
+                    Some(Action::ExportResolution)
+                }
+                KeyCode::Char('k') if matches!(active_menu_item, MenuItem::Verify) => {
+                    key_encoding = key_encoding.next();
+                    None
+                }
+                KeyCode::Char('V') if matches!(active_menu_item, MenuItem::Verify) => {
+                    prompt = Prompt::PinVersion.open();
+                    None
+                }
+                KeyCode::Up | KeyCode::Down if matches!(active_menu_item, MenuItem::Verify) => {
+                    if !verification_history.reports.is_empty() {
+                        let count = verification_history.reports.len();
+                        let selected = history_list_state.selected().unwrap_or(0);
+                        let selected = match event.code {
+                            KeyCode::Up => (selected + count - 1) % count,
+                            _ => (selected + 1) % count,
+                        };
+                        history_list_state.select(Some(selected));
+                    }
+                    None
+                }
+                KeyCode::Char('F') if matches!(active_menu_item, MenuItem::Verify) => {
+                    pin_target = resolved_doc
+                        .as_ref()
+                        .map(|document| home::Pin::Identity(document.id().to_string()));
+                    Some(Action::TogglePin)
+                }
+                KeyCode::Up | KeyCode::Down if matches!(active_menu_item, MenuItem::Home) => {
+                    if !home_pins.is_empty() {
+                        let count = home_pins.len();
+                        let selected = home_list_state.selected().unwrap_or(0);
+                        let selected = match event.code {
+                            KeyCode::Up => (selected + count - 1) % count,
+                            _ => (selected + 1) % count,
+                        };
+                        home_list_state.select(Some(selected));
+                    }
+                    None
+                }
+                KeyCode::Enter if matches!(active_menu_item, MenuItem::Home) => {
+                    match home_list_state.selected().and_then(|i| home_pins.get(i)) {
+                        Some(home::Pin::Credential(id)) => state
+                            .read()
+                            .await
+                            .wallet
+                            .entries
+                            .iter()
+                            .find(|entry| entry.id == *id)
+                            .and_then(|entry| entry.credential.to_json().ok())
+                            .map(|json| {
+                                verify_input = Some(json);
+                                Action::VerifyCredential
+                            }),
+                        Some(home::Pin::Identity(did)) => {
+                            resolve_did = Some(did.clone());
+                            Some(Action::ResolveIdentity)
+                        }
+                        None => None,
+                    }
+                }
+                KeyCode::Char('b') if matches!(active_menu_item, MenuItem::Home) => {
+                    match home_list_state.selected().and_then(|i| home_pins.get(i)) {
+                        Some(home::Pin::Credential(id)) => {
+                            // Presents the card alone, whatever is marked in the wallet.
+                            presentation_selection.clear();
+                            presentation_selection.insert(id.clone());
+                            Some(Action::PresentSelected)
+                        }
+                        _ => None,
+                    }
+                }
+                KeyCode::Char('F') if matches!(active_menu_item, MenuItem::Home) => {
+                    pin_target = home_list_state
+                        .selected()
+                        .and_then(|i| home_pins.get(i))
+                        .cloned();
+                    Some(Action::TogglePin)
+                }
+                KeyCode::Enter if matches!(active_menu_item, MenuItem::Verify) => {
+                    // Reopened as it was recorded, without running the checks again.
+                    opened_report = history_list_state
+                        .selected()
+                        .and_then(|i| verification_history.reports.get(i))
+                        .cloned();
+                    None
+                }
+                KeyCode::Char('g') if matches!(active_menu_item, MenuItem::Verify) => {
+                    prompt = Prompt::RequestTypes.open();
+                    None
+                }
+                KeyCode::Char('e') if matches!(active_menu_item, MenuItem::Verify) => {
+                    prompt = Prompt::Presentation.open();
+                    None
+                }
+                KeyCode::Char('f') if matches!(active_menu_item, MenuItem::Verify) => {
+                    // Nothing pasted, the response is read from the inbox.
+                    prompt_input.clear();
+                    Some(Action::ReceivePresentation)
+                }
+                KeyCode::Char('x') if matches!(active_menu_item, MenuItem::Verify) => {
+                    Some(Action::ExportReport)
+                }
+                KeyCode::Char('u') if matches!(active_menu_item, MenuItem::Verify) => {
+                    prompt = Prompt::Link.open();
+                    None
+                }
+                KeyCode::Char('e') if matches!(active_menu_item, MenuItem::Issue) => {
+                    issue_form.editing = true;
+                    None
+                }
+                KeyCode::Char('u') if matches!(active_menu_item, MenuItem::Issue) => {
+                    Some(Action::QueueCredential)
+                }
+                KeyCode::Char('g') if matches!(active_menu_item, MenuItem::Issue) => {
+                    Some(Action::IssueQueue)
+                }
+                KeyCode::Char('x') if matches!(active_menu_item, MenuItem::Issue) => {
+                    Some(Action::ClearQueue)
+                }
+                KeyCode::Up | KeyCode::Down if matches!(active_menu_item, MenuItem::Restore) => {
+                    if !backups.is_empty() {
+                        let count = backups.len();
+                        let selected = backup_list_state.selected().unwrap_or(0);
+                        let selected = match event.code {
+                            KeyCode::Up => (selected + count - 1) % count,
+                            _ => (selected + 1) % count,
+                        };
+                        backup_list_state.select(Some(selected));
+                    }
+                    None
+                }
+                KeyCode::Up | KeyCode::Down if matches!(active_menu_item, MenuItem::Chain) => {
+                    if !chain_messages.is_empty() {
+                        let count = chain_messages.len();
+                        let selected = chain_list_state.selected().unwrap_or(0);
+                        let selected = match event.code {
+                            KeyCode::Up => (selected + count - 1) % count,
+                            _ => (selected + 1) % count,
+                        };
+                        chain_list_state.select(Some(selected));
+                    }
+                    None
+                }
+                KeyCode::Enter if matches!(active_menu_item, MenuItem::Restore) => {
+                    Some(Action::RestoreBackup)
+                }
+                KeyCode::Char('x') if matches!(active_menu_item, MenuItem::Restore) => {
+                    Some(Action::ExportBundle)
+                }
+                KeyCode::Char('m') if matches!(active_menu_item, MenuItem::Restore) => {
+                    prompt = Prompt::Bundle.open();
+                    None
+                }
+                KeyCode::Up | KeyCode::Down if matches!(active_menu_item, MenuItem::History) => {
+                    if !published.is_empty() {
+                        let count = published.len();
+                        let selected = published_list_state.selected().unwrap_or(0);
+                        let selected = match event.code {
+                            KeyCode::Up => (selected + count - 1) % count,
+                            _ => (selected + 1) % count,
+                        };
+                        published_list_state.select(Some(selected));
+                    }
+                    None
+                }
+                KeyCode::Char('u') if matches!(active_menu_item, MenuItem::History) => {
+                    Some(Action::CheckReceipts)
+                }
+                KeyCode::Char('e') if matches!(active_menu_item, MenuItem::History) => {
+                    Some(Action::RepublishReceipt)
+                }
+                KeyCode::Up | KeyCode::Down if matches!(active_menu_item, MenuItem::Pending) => {
+                    if !incoming.is_empty() {
+                        let count = incoming.len();
+                        let selected = incoming_list_state.selected().unwrap_or(0);
+                        let selected = match event.code {
+                            KeyCode::Up => (selected + count - 1) % count,
+                            _ => (selected + 1) % count,
+                        };
+                        incoming_list_state.select(Some(selected));
+                    }
+                    None
+                }
+                KeyCode::Enter if matches!(active_menu_item, MenuItem::Pending) => {
+                    Some(Action::AcceptRequest)
+                }
+                KeyCode::Char('x') if matches!(active_menu_item, MenuItem::Pending) => {
+                    prompt = Prompt::RejectReason.open();
+                    None
+                }
+                KeyCode::Up | KeyCode::Down if matches!(active_menu_item, MenuItem::Wallet) => {
+                    if !wallet_entries.is_empty() {
+                        let count = wallet_entries.len();
+                        let selected = wallet_list_state.selected().unwrap_or(0);
+                        let selected = match event.code {
+                            KeyCode::Up => (selected + count - 1) % count,
+                            _ => (selected + 1) % count,
+                        };
+                        wallet_list_state.select(Some(selected));
+                    }
+                    None
+                }
+                KeyCode::Char('t') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    prompt = Prompt::Tags.open();
+                    None
+                }
+                KeyCode::Char('f') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    prompt = Prompt::Folder.open();
+                    None
+                }
+                KeyCode::Char('N') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    prompt = Prompt::Appearance.open();
+                    None
+                }
+                KeyCode::Char('/') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    prompt = Prompt::Filter.open();
+                    None
+                }
+                KeyCode::Char('x') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    Some(Action::ExportFolder)
+                }
+                KeyCode::Char('X') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    Some(Action::ExportWallet)
+                }
+                KeyCode::Char('I') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    prompt = Prompt::WalletImport.open();
+                    None
+                }
+                KeyCode::Char('d') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    Some(Action::DeleteTag)
+                }
+                KeyCode::Char('g') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    Some(Action::CollectCredentials)
+                }
+                KeyCode::Char(' ') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    let selected = wallet_list_state
+                        .selected()
+                        .and_then(|i| wallet_entries.get(i));
+                    if let Some(entry) = selected {
+                        if !presentation_selection.remove(&entry.id) {
+                            presentation_selection.insert(entry.id.clone());
+                        }
+                    }
+                    None
+                }
+                KeyCode::Char('o') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    prompt = Prompt::AgeThreshold.open();
+                    None
+                }
+                KeyCode::Char('A') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    prompt = Prompt::SelfAttest.open();
+                    None
+                }
+                KeyCode::Char('b') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    Some(Action::PresentSelected)
+                }
+                KeyCode::Char('e') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    Some(Action::ExportWalletCredential)
+                }
+                KeyCode::Char('L') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    match &discovery {
+                        Some(discovery) => {
+                            lan_instances = discovery.instances();
+                            prompt = Prompt::LanPeer.open();
+                        }
+                        None => {
+                            status = "LAN discovery needs the discovery feature and the HTTP store"
+                                .to_string()
+                        }
+                    }
+                    None
+                }
+                KeyCode::Char('T') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    Some(Action::TransferCredential)
+                }
+                KeyCode::Char('R') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    Some(Action::RefreshCredential)
+                }
+                KeyCode::Char('P') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    prompt = Prompt::PeerLabel.open();
+                    None
+                }
+                KeyCode::Enter if matches!(active_menu_item, MenuItem::Wallet) => {
+                    let selected = wallet_list_state
+                        .selected()
+                        .and_then(|i| wallet_entries.get(i));
+                    match selected.map(|entry| entry.credential.to_json()) {
+                        Some(Ok(json)) => {
+                            verify_input = Some(json);
+                            Some(Action::VerifyCredential)
+                        }
+                        _ => None,
+                    }
+                }
+                KeyCode::Char('z') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    Some(Action::ArchiveCredential)
+                }
+                KeyCode::Char('F') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    pin_target = wallet_list_state
+                        .selected()
+                        .and_then(|i| wallet_entries.get(i))
+                        .map(|entry| home::Pin::Credential(entry.id.clone()));
+                    Some(Action::TogglePin)
+                }
+                KeyCode::Char('Z') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    wallet_archived = !wallet_archived;
+                    wallet_list_state.select(Some(0));
+                    None
+                }
+                KeyCode::Char('S') if matches!(active_menu_item, MenuItem::Wallet) => {
+                    wallet_stats = !wallet_stats;
+                    None
+                }
+                KeyCode::Delete if matches!(active_menu_item, MenuItem::Wallet) => {
+                    if wallet_list_state
+                        .selected()
+                        .and_then(|i| wallet_entries.get(i))
+                        .is_some()
+                    {
+                        prompt = Prompt::ConfirmDelete.open();
+                    }
+                    None
+                }
+                KeyCode::Up | KeyCode::Down if matches!(active_menu_item, MenuItem::Nodes) => {
+                    let count = nodes.lock().unwrap().len();
+                    if count > 0 {
+                        let selected = node_list_state.selected().unwrap_or(0);
+                        let selected = match event.code {
+                            KeyCode::Up => (selected + count - 1) % count,
+                            _ => (selected + 1) % count,
+                        };
+                        node_list_state.select(Some(selected));
+                    }
+                    None
+                }
+                KeyCode::Char(' ') if matches!(active_menu_item, MenuItem::Nodes) => {
+                    if let Some(selected) = node_list_state.selected() {
+                        nodes::toggle(&nodes, selected);
+                    }
+                    None
+                }
+                KeyCode::Char('C') if matches!(active_menu_item, MenuItem::Nodes) => {
+                    prompt = Prompt::CompareNodes.open();
+                    None
+                }
+                KeyCode::Char('x') if matches!(active_menu_item, MenuItem::Nodes) => {
+                    if let Some(selected) = node_list_state.selected() {
+                        nodes::pin(&nodes, selected);
+                    }
+                    None
+                }
+                _ => None,
+            },
+            Event::Message(message) => {
+                // The server reports renewals, the issuance log is only written here.
+                if let bus::Message::Renewed(credential) = &message {
+                    issued.record(credential);
+                    store.save_issued(&issued)?;
+                }
+                state.write().await.apply(message);
+                None
+            }
+            Event::Action(action) => Some(action),
+            // The task may have finished just before it was cancelled.
+            Event::Done(task, done) if running.as_ref().map(|(id, _)| *id) == Some(task) => {
+                running = None;
+                let action = match done {
+                    bus::Done::Verified(_) => Action::VerifyFinished,
+                    bus::Done::Resolved(_) => Action::ResolveFinished,
+                };
+                finished = Some(done);
+                Some(action)
+            }
+            Event::Done(..) => None,
+            Event::Tick => None,
+        };
+
+        if let Some(Err(capability)) = action.map(|action| profile.check(action)) {
+            status = format!(
+                "Profile {} lacks the {:?} capability",
+                profile.name, capability
+            );
+            prompt = None;
+            continue;
+        }
+        if ephemeral && action.map_or(false, |action| action.writes_files()) {
+            status = "Ephemeral run, backups and exports are disabled".to_string();
+            continue;
+        }
+        if running.is_some()
+            && matches!(
+                action,
+                Some(Action::VerifyCredential)
+                    | Some(Action::ResolveIssuer)
+                    | Some(Action::ResolveIdentity)
+            )
+        {
+            status = "A verification or resolution is running, Esc cancels it".to_string();
+            continue;
+        }
+        if lock.is_locked() && action.map_or(false, |action| action.needs_unlock()) {
+            status = format!("Locked, enter the {} on the Wallet tab first", lock.label());
+            active_menu_item = MenuItem::Wallet;
+            continue;
+        }
+        let confirmed: Option<confirm::TangleWrite> = confirmed_write.take();
+        if config.confirm_tangle_writes
+            && confirmed.is_none()
+            && action.map_or(false, |action| action.writes_tangle())
+        {
+            let prepared: Option<Result<confirm::TangleWrite, Box<dyn std::error::Error>>> =
+                match action {
+                    Some(action @ Action::CreateIdentity) => Some(
+                        seed::keypair(seed.as_deref(), &format!("holder-{}", holders_created))
+                            .map_err(Into::into)
+                            .and_then(|key| confirm::TangleWrite::new_identity(action, key)),
+                    ),
+                    Some(action @ Action::PublishKey) => Some(
+                        keys::read(&prompt_input)
+                            .and_then(|key| confirm::TangleWrite::new_identity(action, key)),
+                    ),
+                    Some(Action::AddMethod) => {
+                        Some(jwk::parse_method(&subject_doc, &prompt_input).and_then(
+                            |(public, fragment)| {
+                                confirm::TangleWrite::add_method(
+                                    &subject_doc,
+                                    &subject_key,
+                                    &fragment,
+                                    &public,
+                                )
+                            },
+                        ))
+                    }
+                    Some(Action::PublishJson) => console_file
+                        .as_deref()
+                        .map(confirm::TangleWrite::publish_json),
+                    Some(Action::RepublishReceipt) => {
+                        let state = state.read().await;
+                        published_list_state
+                            .selected()
+                            .filter(|i| *i < published.len())
+                            .map(|i| &published[i])
+                            .filter(|receipt| {
+                                receipt.republished_as.is_none()
+                                    && state
+                                        .inclusions
+                                        .get(&receipt.message_id)
+                                        .map_or(false, |inclusion| inclusion.needs_republish())
+                            })
+                            .map(confirm::TangleWrite::republish)
+                    }
+                    _ => None,
+                };
+            match prepared {
+                Some(Ok(write)) => {
+                    pending_write = Some(write);
+                    continue;
+                }
+                Some(Err(error)) => {
+                    prompt_input.zeroize();
+                    status = format!("Preparing the Tangle write failed: {}", error);
+                    continue;
+                }
+                None => {}
+            }
+        }
+
+        match action {
+            None => {}
+            Some(Action::TogglePresentationMode) => {
+                presentation_mode = !presentation_mode;
+                status = if presentation_mode {
+                    "Presentation mode on, claim values are masked and DIDs shortened".to_string()
+                } else {
+                    "Presentation mode off".to_string()
+                };
+            }
+            Some(Action::ShiftClock) => {
+                status = match clock::parse_offset(&prompt_input) {
+                    Ok(offset) => {
+                        clock::set_offset(offset);
+                        match clock::label() {
+                            Some(label) => format!("Clock shifted by {}", label),
+                            None => "Clock reset to the real time".to_string(),
+                        }
+                    }
+                    Err(error) => error,
+                };
+                bus.log(status.clone());
+            }
+            Some(Action::LockScreens) => {
+                lock.lock();
+                status = if lock.is_enabled() {
+                    "Wallet and signing screens locked".to_string()
+                } else {
+                    "No pin_hash configured, nothing to lock".to_string()
+                };
+            }
+            Some(Action::Quit) => {
+                // Publishes run inline in this loop, so only drafted work can be pending here.
+                pending_work.clear();
+                if let Some(preview) = &issue_preview {
+                    pending_work.push(format!(
+                        "Unsigned {} waiting for confirmation, finishing signs and issues it",
+                        preview.types.iter().last().cloned().unwrap_or_default()
+                    ));
+                }
+                let drafted = issue_queue.open().count();
+                if drafted > 0 {
+                    pending_work.push(format!(
+                        "{} queued credentials not delivered yet, finishing signs and delivers them",
+                        drafted
+                    ));
+                }
+                if request_form.editing || issue_form.editing {
+                    pending_work.push("A claims form is being edited".to_string());
+                }
+                if offer.is_some() {
+                    pending_work.push("A credential offer is waiting to be accepted".to_string());
+                }
+                if let Some((kind, _)) = &prompt {
+                    pending_work.push(format!("Unanswered prompt: {}", kind.label()));
+                }
+                if pending_work.is_empty() {
+                    store.save_wallet(&state.read().await.wallet)?;
+                    store.save_trust(&trust)?;
+                    terminal.show_cursor()?;
+                    break;
+                }
+                pending_work.push(String::new());
+                pending_work.push(if ephemeral {
+                    "Nothing is saved, wallet and identities are gone after quitting.".to_string()
+                } else {
+                    "Wallet and trust decisions are saved either way.".to_string()
+                });
+            }
+            Some(Action::FinishAndQuit) | Some(Action::DiscardAndQuit) => {
+                if action == Some(Action::FinishAndQuit) {
+                    if let Some(mut credential) = issue_preview.take() {
+                        service.issue_credential(&*signer, &mut credential).await?;
+                        issued.record(&credential);
+                        let client = nodes::client(&nodes).await?;
+                        handle::share(&config, &client, &credential.to_string()).await?;
+                    }
+                    if issue_queue.open().count() > 0 {
+                        let client = nodes::client(&nodes).await?;
+                        deliver_queue(
+                            &config,
+                            &*signer,
+                            &client,
+                            &peers,
+                            &mut issue_queue,
+                            &mut issued,
+                        )
+                        .await;
+                    }
+                }
+                store.save_issued(&issued)?;
+                store.save_wallet(&state.read().await.wallet)?;
+                store.save_trust(&trust)?;
+                terminal.show_cursor()?;
+                break;
+            }
+            Some(Action::ShowHome) => active_menu_item = MenuItem::Home,
+            Some(Action::ShowIssue) => active_menu_item = MenuItem::Issue,
+            Some(Action::ShowVerify) => active_menu_item = MenuItem::Verify,
+            Some(Action::ShowNodes) => active_menu_item = MenuItem::Nodes,
+            Some(Action::ShowRestore) => {
+                backups = backup::list(&config)?;
+                active_menu_item = MenuItem::Restore;
+            }
+            Some(Action::ShowApply) => active_menu_item = MenuItem::Apply,
+            Some(Action::ShowPending) => {
+                match pending_requests(&nodes, &config.inbox_dir).await {
+                    Ok(received) => incoming = received,
+                    Err(error) => status = format!("Reading the requests failed: {}", error),
+                }
+                active_menu_item = MenuItem::Pending;
+            }
+            Some(Action::CreateIdentity) => {
+                if let Err(error) = backup::create(&config) {
+                    status = format!("Nothing changed, backing up the snapshot failed: {}", error);
+                    continue;
+                }
+                let label = format!("holder-{}", holders_created);
+                let (document, key, receipt) = match confirmed {
+                    Some(write) => {
+                        let key = write.key.ok_or("the confirmed identity has no key")?;
+                        service.publish_identity(write.document, key).await?
+                    }
+                    None => {
+                        service
+                            .create_identity(seed::keypair(seed.as_deref(), &label)?)
+                            .await?
+                    }
+                };
+                holders_created += 1;
+                holder_label = Some(label);
+                status = format!("Created holder {}", document.id());
+                store.save_receipt(receipts::PublishReceipt::new(
+                    &document,
+                    &receipt,
+                    receipts::Operation::Create,
+                )?)?;
+                published = store.receipts();
+                bus.log(format!(
+                    "Publish of {} confirmed in {}",
+                    document.id(),
+                    receipt.message_id()
+                ));
+                subject_doc = document;
+                subject_key = key;
+                state.write().await.holder = subject_doc.id().to_string();
+            }
+            Some(Action::IssueCredential) => {
+                let client = nodes::client(&nodes).await?;
+                let id = ids::generate(
+                    config.id_strategy_for(issue::DEGREE),
+                    &mut issued,
+                    subject_doc.id().as_str(),
+                    issue::DEGREE,
+                    "",
+                );
+                match issue_degree_credential(
+                    &service,
+                    &*signer,
+                    &id,
+                    &subject_doc,
+                    config.branding.as_ref(),
+                )
+                .await?
+                {
+                    Some(credential) => {
+                        issued.record(&credential);
+                        store.save_issued(&issued)?;
+                        credential_str = credential.to_string();
+                        qr_payload = handle::share(&config, &client, &credential_str).await?;
+                        status = format!("Issued degree to {}", subject_doc.id());
+                        active_menu_item = MenuItem::Issue;
+                    }
+                    None => {
+                        status =
+                            "Prerequisite enrollment credential could not be verified".to_string();
+                    }
+                }
+            }
+            Some(Action::PreviewCredential) => {
+                let mut claims: Object = submitted_claims.clone();
+                // The preview already shows the claims as the hooks left them.
+                if let Err(error) =
+                    claim_hooks.run(config.hooks_for(&issue_form.credential_type), &mut claims)
+                {
+                    issue_form.error = Some(error);
+                    continue;
+                }
+                // Switching with p only holds for one credential.
+                pseudonymous = config.pseudonymous_subjects;
+                let subject: String = if pseudonymous {
+                    let did = pseudonyms.for_issuer(
+                        &subject_doc,
+                        &subject_key,
+                        signer.document().id().as_str(),
+                    )?;
+                    pseudonyms.save(&config.pseudonyms_path)?;
+                    did
+                } else {
+                    subject_doc.id().to_string()
+                };
+                let id = ids::generate(
+                    config.id_strategy_for(&issue_form.credential_type),
+                    &mut issued,
+                    &subject,
+                    &issue_form.credential_type,
+                    &issue_form.claims,
+                );
+                let mut credential: Credential = issue::issue_claims(
+                    signer.document(),
+                    &id,
+                    &subject,
+                    &issue_form.credential_type,
+                    &issue_form.context_list(),
+                    claims,
+                )?;
+                branding::attach(&mut credential, config.branding.as_ref());
+                issue::set_validity(&mut credential, config.validity_days);
+                refresh::attach(&mut credential, &config)?;
+                revocation::attach(&mut credential, &config)?;
+                issue_preview = Some(credential);
+            }
+            Some(Action::SwitchPseudonym) => {
+                let pseudonym: String = pseudonyms.for_issuer(
+                    &subject_doc,
+                    &subject_key,
+                    signer.document().id().as_str(),
+                )?;
+                pseudonyms.save(&config.pseudonyms_path)?;
+                let holder: String = subject_doc.id().to_string();
+                let (from, to) = if pseudonymous {
+                    (pseudonym, holder)
+                } else {
+                    (holder, pseudonym)
+                };
+                if let Some(credential) = issue_preview.as_mut() {
+                    issue::replace_subject(credential, &from, &to)?;
+                }
+                pseudonymous = !pseudonymous;
+                status = format!("The credential is issued to {}", to);
+            }
+            Some(Action::EditCredentialJson) => {
+                match issue::parse_edited(&prompt_input, signer.document()) {
+                    Ok(credential) => {
+                        json_error = None;
+                        issue_preview = Some(credential);
+                        status = "Saved the edited JSON into the preview".to_string();
+                    }
+                    Err(error) => {
+                        // Keep the editor open with the text so nothing typed gets lost.
+                        json_error = Some(format!("Not saved: {}", error));
+                        prompt = Some((
+                            Prompt::CredentialJson,
+                            editor::Editor::with_text(&prompt_input, true),
+                        ));
+                    }
+                }
+            }
+            Some(Action::IssueFromForm) => {
+                // Sign exactly what the issuer confirmed in the preview.
+                let mut credential: Credential = match issue_preview.take() {
+                    Some(credential) => credential,
+                    None => continue,
+                };
+                service.issue_credential(&*signer, &mut credential).await?;
+                issued.record(&credential);
+                store.save_issued(&issued)?;
+                credential_str = credential.to_string();
+                let client = nodes::client(&nodes).await?;
+                qr_payload = handle::share(&config, &client, &credential_str).await?;
+                status = format!(
+                    "Issued {} to {}",
+                    issue_form.credential_type,
+                    subject_doc.id()
+                );
+            }
+            Some(Action::QueueCredential) => {
+                match claims::parse(&issue_form.claims).and_then(|mut claims| {
+                    claim_hooks
+                        .run(config.hooks_for(&issue_form.credential_type), &mut claims)
+                        .map(|_| claims)
+                }) {
+                    Ok(claims) => {
+                        // The subject is the current holder, creating a new holder queues for that one.
+                        let id = ids::generate(
+                            config.id_strategy_for(&issue_form.credential_type),
+                            &mut issued,
+                            subject_doc.id().as_str(),
+                            &issue_form.credential_type,
+                            &issue_form.claims,
+                        );
+                        // The queue outlives the session, its ids must not be drawn again.
+                        store.save_issued(&issued)?;
+                        let mut credential: Credential = issue::issue_claims(
+                            signer.document(),
+                            &id,
+                            subject_doc.id().as_str(),
+                            &issue_form.credential_type,
+                            &issue_form.context_list(),
+                            claims,
+                        )?;
+                        branding::attach(&mut credential, config.branding.as_ref());
+                        issue::set_validity(&mut credential, config.validity_days);
+                        refresh::attach(&mut credential, &config)?;
+                        revocation::attach(&mut credential, &config)?;
+                        for warning in lint::credential(&credential) {
+                            bus.log(format!("Queued credential: {}", warning));
+                        }
+                        issue_queue.push(subject_doc.id().as_str(), credential);
+                        status = format!(
+                            "Queued {} for {}",
+                            issue_form.credential_type,
+                            subject_doc.id()
+                        );
+                    }
+                    Err(error) => issue_form.error = Some(error),
+                }
+            }
+            Some(Action::IssueQueue) => {
+                let client = nodes::client(&nodes).await?;
+                let delivered = deliver_queue(
+                    &config,
+                    &*signer,
+                    &client,
+                    &peers,
+                    &mut issue_queue,
+                    &mut issued,
+                )
+                .await;
+                store.save_issued(&issued)?;
+                if let Some((credential, payload)) = delivered {
+                    credential_str = credential;
+                    qr_payload = payload;
+                }
+                let delivered = issue_queue
+                    .items
+                    .iter()
+                    .filter(|item| item.status == queue::ItemStatus::Delivered)
+                    .count();
+                status = format!(
+                    "{} of {} queued credentials delivered",
+                    delivered,
+                    issue_queue.items.len()
+                );
+                bus.log(status.clone());
+            }
+            Some(Action::ClearQueue) => {
+                let cleared = issue_queue.clear_delivered();
+                status = format!("Cleared {} delivered credentials from the queue", cleared);
+            }
+            Some(Action::VerifyCredential) => {
+                // Verify what a verifier scanning the QR would get.
+                let input: String = verify_input.take().unwrap_or_else(|| qr_payload.clone());
+                tasks_started += 1;
+                running = Some((
+                    tasks_started,
+                    retry::verify(
+                        service.clone(),
+                        bus.clone(),
+                        retry::Backoff::new(&config),
+                        tasks_started,
+                        input,
+                    ),
+                ));
+                status = "Verifying, Esc cancels".to_string();
+            }
+            Some(Action::VerifyFinished) => {
+                let checked = match finished.take() {
+                    Some(bus::Done::Verified(checked)) => checked,
+                    _ => continue,
+                };
+                if let Some(mismatch) = checked.as_ref().ok().and_then(|c| c.mismatch.as_ref()) {
+                    bus.log(mismatch.to_string());
+                }
+                status = match checked {
+                    Ok(core::OnNetwork {
+                        value: Some(result),
+                        ..
+                    }) => {
+                        let (verified, format) = (result.verified, result.format);
+                        let issuer: String = result.credential.issuer.url().to_string();
+                        if !result.unknown.is_empty() || !result.warnings.is_empty() {
+                            // The Verify screen shows what the time budget cut short and the
+                            // warnings about the issuer document, which are in its details.
+                            let mut partial = result.integrity.clone();
+                            partial.details.extend(
+                                result
+                                    .unknown
+                                    .iter()
+                                    .map(|check| format!("{}: unknown (timeout)", check)),
+                            );
+                            integrity_report = Some(partial);
+                            active_menu_item = MenuItem::Verify;
+                        }
+                        for warning in &result.warnings {
+                            bus.log(format!("Issuer document: {}", warning));
+                        }
+                        trust.record(&issuer, verified);
+                        store.save_trust(&trust)?;
+                        let policy = verification::policy(&trust, &issuer, verified);
+                        let error = policy.error(&issuer, &result);
+                        let outcome = match policy {
+                            verification::Policy::Unknown => {
+                                pending_trust = Some((issuer, policy.outcome(format, None)));
+                                prompt = Prompt::Trust.open();
+                                "Unknown issuer, waiting for a trust decision".to_string()
+                            }
+                            _ => policy.outcome(format, error.as_ref()),
+                        };
+                        let mut report =
+                            verification::report(&result, &outcome, issuer_doc.id().as_str());
+                        report.error = error.map(|error| error.code().to_string());
+                        verification_history.record(report.clone());
+                        store.save_history(&verification_history)?;
+                        history_list_state.select(Some(0));
+                        let outcome = match report.cached_note() {
+                            Some(note) => format!("{} ({})", outcome, note),
+                            None => outcome,
+                        };
+                        last_report = Some(report);
+                        outcome
+                    }
+                    Ok(core::OnNetwork { mismatch, .. }) => format!(
+                        "Not verified: {}",
+                        mismatch
+                            .map(|mismatch| mismatch.to_string())
+                            .unwrap_or_default()
+                    ),
+                    Err(error) => format!("Verification failed: {}", error),
+                };
+                bus.log(format!("Verification finished: {}", status));
+            }
+            Some(Action::TrustIssuer) => {
+                if let Some((issuer, outcome)) = pending_trust.take() {
+                    status = match prompt_input.parse::<trust::Decision>() {
+                        Ok(decision) => {
+                            trust.decide(&issuer, decision);
+                            store.save_trust(&trust)?;
+                            if decision == trust::Decision::Block {
+                                let error = verification::VerificationError::UntrustedIssuer(
+                                    issuer.clone(),
+                                );
+                                if let Some(report) = last_report.as_mut() {
+                                    report.error = Some(error.code().to_string());
+                                }
+                                format!("Credential rejected: {}", error)
+                            } else {
+                                outcome
+                            }
+                        }
+                        Err(error) => {
+                            pending_trust = Some((issuer, outcome));
+                            prompt = Prompt::Trust.open();
+                            error
+                        }
+                    };
+                    if pending_trust.is_none() {
+                        if let Some(report) = last_report.as_mut() {
+                            report.outcome = status.clone();
+                            verification_history.update(report);
+                            store.save_history(&verification_history)?;
+                        }
+                    }
+                    bus.log(format!("Verification finished: {}", status));
+                }
+            }
+            Some(Action::ResolveIssuer) => {
+                tasks_started += 1;
+                running = Some((
+                    tasks_started,
+                    retry::resolve(
+                        service.clone(),
+                        bus.clone(),
+                        retry::Backoff::new(&config),
+                        tasks_started,
+                        issuer_doc.id().clone(),
+                    ),
+                ));
+                status = format!("Resolving {}, Esc cancels", issuer_doc.id());
+            }
+            Some(Action::ResolveFinished) => {
+                let resolved = match finished.take() {
+                    Some(bus::Done::Resolved(resolved)) => resolved,
+                    _ => continue,
+                };
+                status = match resolved {
+                    Ok(core::OnNetwork {
+                        value: Some((document, report)),
+                        ..
+                    }) => {
+                        let (badge, _) = report.integrity.badge();
+                        integrity_report = Some(report);
+                        active_menu_item = MenuItem::Verify;
+                        let status = format!("Resolved {} {}", document.id(), badge);
+                        resolved_doc = Some(document);
+                        status
+                    }
+                    Ok(core::OnNetwork { mismatch, .. }) => format!(
+                        "Not resolved: {}",
+                        mismatch
+                            .map(|mismatch| mismatch.to_string())
+                            .unwrap_or_default()
+                    ),
+                    Err(error) => format!("Resolution failed: {}", error),
+                };
+            }
+            Some(Action::CancelTask) => {
+                if let Some((_, task)) = running.take() {
+                    // The request in flight is dropped with the task, its outcome never arrives.
+                    task.abort();
+                    state.write().await.progress = None;
+                    status = "Cancelled".to_string();
+                    bus.log("Cancelled the running resolution or verification");
+                }
+            }
+            Some(Action::RequestPresentation) => {
+                let request = oob::PresentationRequest::new(
+                    issuer_doc.id().as_str(),
+                    wallet::parse_tags(&prompt_input).into_iter().collect(),
+                );
+                status = format!("Showing presentation request {}", request.id);
+                let shared = sharing::auto_select(
+                    &config.auto_present,
+                    &request,
+                    &state.read().await.wallet,
+                );
+                presentation_request = Some(request);
+                presentation_report = None;
+                if let Some(shared) = shared {
+                    // The holder agreed beforehand, so the request is answered without marking.
+                    bus.log(format!(
+                        "Presenting {} credentials by the auto_present rules",
+                        shared.len()
+                    ));
+                    presentation_selection = shared;
+                    bus.dispatch(Action::PresentSelected);
+                }
+            }
+            Some(Action::ReceivePresentation) => {
+                // Without an open request only presentations sent unasked, like from the LAN.
+                let request: Option<oob::PresentationRequest> = presentation_request.clone();
+                let received: Result<
+                    Option<(Presentation, Vec<serde_json::Value>)>,
+                    Box<dyn std::error::Error>,
+                > = if prompt_input.trim().is_empty() {
+                    match &request {
+                        Some(request) => oob::receive(&config.inbox_dir, request),
+                        None => oob::receive_unsolicited(&config.inbox_dir),
+                    }
+                    .map(|presentation| presentation.map(|presentation| (presentation, Vec::new())))
+                } else {
+                    verify::parse_presentation(prompt_input.trim()).map(Some)
+                };
+                status = match received {
+                    Ok(Some((presentation, proofs))) => {
+                        let report = service.verify_presentation(&presentation, &proofs).await?;
+                        let mut problems: Vec<String> = request
+                            .as_ref()
+                            .map(|request| request.check(&presentation))
+                            .unwrap_or_default();
+                        if !report.verified {
+                            problems.push("the signatures do not verify".to_string());
+                        }
+                        presentation_report = Some(report);
+                        if problems.is_empty() {
+                            presentation_request = None;
+                            match request {
+                                Some(request) => {
+                                    format!("Presentation for request {} verified", request.id)
+                                }
+                                None => "Presentation verified".to_string(),
+                            }
+                        } else {
+                            format!("Presentation rejected: {}", problems.join(", "))
+                        }
+                    }
+                    Ok(None) => "No presentation in the inbox yet".to_string(),
+                    Err(error) => format!("Not a presentation: {}", error),
+                };
+                bus.log(format!("Verification finished: {}", status));
+            }
+            Some(Action::ExportReport) => {
+                status = match opened_report.take().or_else(|| last_report.clone()) {
+                    Some(mut report) => {
+                        // The app identity signs as verifier, so the report can be checked later.
+                        account
+                            .sign(issuer_doc.id(), account::SIGNING_METHOD, &mut report)
+                            .await?;
+                        let (json, text) = report.export(&config.export_dir)?;
+                        format!(
+                            "Exported the report to {} and {}",
+                            json.display(),
+                            text.display()
+                        )
+                    }
+                    None => "Nothing verified yet".to_string(),
+                };
+            }
+            Some(Action::ExportJwks) => {
+                let document: &IotaDocument = resolved_doc.as_ref().unwrap_or(&issuer_doc);
+                let dir = Path::new(&config.export_dir);
+                fs::create_dir_all(dir)?;
+                let path = dir.join("jwks.json");
+                fs::write(&path, jwk::key_set(document.methods()).to_json_pretty()?)?;
+                status = format!(
+                    "Exported the keys of {} to {}",
+                    document.id(),
+                    path.display()
+                );
+            }
+            Some(Action::PinVersion) => {
+                let message_id: &str = prompt_input.trim();
+                status = match (resolved_doc.as_ref(), integrity_report.as_ref()) {
+                    (Some(document), Some(report)) => {
+                        let did = document.id().to_string();
+                        if message_id.is_empty() {
+                            service.pin_version(&did, None);
+                            format!("Credentials of {} verify against its latest document", did)
+                        } else if let Some(index) = integrity::version_of(report, message_id) {
+                            service.pin_version(&did, Some(message_id));
+                            format!(
+                                "Credentials of {} verify against version {} of {} ({})",
+                                did,
+                                index + 1,
+                                report.versions.len(),
+                                message_id
+                            )
+                        } else {
+                            format!("{} is no integration message of {}", message_id, did)
+                        }
+                    }
+                    _ => "Resolve the issuer first, its versions are pinned".to_string(),
+                };
+                bus.log(status.clone());
+            }
+            Some(Action::ExportResolution) => {
+                status = match (resolved_doc.as_ref(), integrity_report.as_ref()) {
+                    (Some(document), Some(report)) => {
+                        let dir = Path::new(&config.export_dir);
+                        fs::create_dir_all(dir)?;
+                        let path = dir.join("did-resolution.json");
+                        fs::write(
+                            &path,
+                            serde_json::to_string_pretty(&resolution::result(
+                                document, report, None,
+                            ))?,
+                        )?;
+                        format!(
+                            "Exported the resolution result of {} to {}",
+                            document.id(),
+                            path.display()
+                        )
+                    }
+                    _ => "Resolve a DID first".to_string(),
+                };
+            }
+            Some(Action::AddMethod) => {
+                if let Err(error) = backup::create(&config) {
+                    status = format!("Nothing changed, backing up the snapshot failed: {}", error);
+                    continue;
+                }
+                status = match jwk::parse_method(&subject_doc, &prompt_input) {
+                    Ok((public, fragment)) => {
+                        for warning in lint::document(&subject_doc, 1) {
+                            bus.log(format!("Publishing {}: {}", subject_doc.id(), warning));
+                        }
+                        let receipt = match confirmed {
+                            Some(write) => {
+                                subject_doc = write.document;
+                                service.publish_document(&mut subject_doc).await?
+                            }
+                            None => {
+                                service
+                                    .add_method(&mut subject_doc, &subject_key, &fragment, &public)
+                                    .await?
+                            }
+                        };
+                        store.save_receipt(receipts::PublishReceipt::new(
+                            &subject_doc,
+                            &receipt,
+                            receipts::Operation::Update,
+                        )?)?;
+                        published = store.receipts();
+                        bus.log(format!("Published #{} on {}", fragment, subject_doc.id()));
+                        format!("Added verification method #{}", fragment)
+                    }
+                    Err(error) => format!("Reading the JWK failed: {}", error),
+                };
+            }
+            Some(Action::CompareNodes) => {
+                let did = match prompt_input.trim() {
+                    "" => resolved_doc
+                        .as_ref()
+                        .map(|document| document.id().clone())
+                        .ok_or_else(|| "Resolve a DID first or type one".to_string()),
+                    typed => IotaDID::parse(typed).map_err(|error| error.to_string()),
+                };
+                status = match did {
+                    Ok(did) => {
+                        // Every configured node, also disabled ones, to find the one that diverges.
+                        let urls: Vec<String> = nodes
+                            .lock()
+                            .unwrap()
+                            .iter()
+                            .map(|node| node.url.clone())
+                            .collect();
+                        let results = compare::resolve_all(
+                            &urls,
+                            &did,
+                            Duration::from_secs(config.verification_timeout),
+                        )
+                        .await;
+                        let findings = compare::divergence(&results);
+                        for finding in &findings {
+                            bus.log(format!("Nodes diverge on {}: {}", did, finding));
+                        }
+                        node_comparison = Some(compare::summary(&did, &results));
+                        if findings.is_empty() {
+                            format!("Compared {} on {} nodes", did, urls.len())
+                        } else {
+                            format!("The nodes diverge on {} in {} ways", did, findings.len())
+                        }
+                    }
+                    Err(error) => error,
+                };
+            }
+            Some(Action::TogglePin) => {
+                let mut app = state.write().await;
+                status = match pin_target.take() {
+                    Some(home::Pin::Credential(id)) => match app.wallet.toggle_pin(&id) {
+                        Some(true) => "Pinned the credential to the Home tab".to_string(),
+                        Some(false) => "Unpinned the credential from the Home tab".to_string(),
+                        None => continue,
+                    },
+                    Some(home::Pin::Identity(did)) => {
+                        if app.wallet.toggle_identity_pin(&did) {
+                            format!("Pinned {} to the Home tab", did)
+                        } else {
+                            format!("Unpinned {} from the Home tab", did)
+                        }
+                    }
+                    None => "Select a credential or resolve a DID to pin it".to_string(),
+                };
+                store.save_wallet(&app.wallet)?;
+                let count = home::pins(&app.wallet).len();
+                if home_list_state.selected().map_or(true, |i| i >= count) {
+                    home_list_state.select(count.checked_sub(1));
+                }
+            }
+            Some(Action::ResolveIdentity) => {
+                let did = match resolve_did.take().map(|did| IotaDID::parse(&did)) {
+                    Some(Ok(did)) => did,
+                    Some(Err(error)) => {
+                        status = format!("Not a DID: {}", error);
+                        continue;
+                    }
+                    None => continue,
+                };
+                tasks_started += 1;
+                running = Some((
+                    tasks_started,
+                    retry::resolve(
+                        service.clone(),
+                        bus.clone(),
+                        retry::Backoff::new(&config),
+                        tasks_started,
+                        did.clone(),
+                    ),
+                ));
+                status = format!("Resolving {}, Esc cancels", did);
+            }
+            Some(Action::ToggleConsole) => {
+                if matches!(active_menu_item, MenuItem::Console) {
+                    active_menu_item = console_return;
+                } else {
+                    console_return = active_menu_item;
+                    active_menu_item = MenuItem::Console;
+                    prompt = Prompt::Console.open();
+                }
+            }
+            Some(Action::RunConsole) => match console::parse(&prompt_input) {
+                Ok(console::Command::Resolve(did)) => {
+                    bus.log(format!("console> resolve {}", did));
+                    let resolved = match IotaDID::parse(&did) {
+                        Ok(did) => service.resolve(&did).await,
+                        Err(error) => Err(error.into()),
+                    };
+                    match resolved {
+                        Ok(core::OnNetwork {
+                            value: Some((document, report)),
+                            ..
+                        }) => {
+                            bus.log(format!("{} {}", report.integrity.badge().0, report.did));
+                            for detail in report.details {
+                                bus.log(detail);
+                            }
+                            bus.log(document.to_json()?);
+                        }
+                        Ok(core::OnNetwork { mismatch, .. }) => bus.log(format!(
+                            "Not resolved: {}",
+                            mismatch
+                                .map(|mismatch| mismatch.to_string())
+                                .unwrap_or_default()
+                        )),
+                        Err(error) => bus.log(format!("Resolving failed: {}", error)),
+                    }
+                }
+                Ok(console::Command::PublishJson(path)) => {
+                    bus.log(format!("console> publish-json {}", path));
+                    // Goes through the profile and Tangle write confirmation like any publish.
+                    console_file = Some(path);
+                    bus.dispatch(Action::PublishJson);
+                }
+                Ok(console::Command::DumpState) => {
+                    bus.log("console> dump-state");
+                    let app = state.read().await;
+                    let lines = vec![
+                        format!("issuer {}", app.issuer),
+                        format!("holder {}", app.holder),
+                        format!("signer {}", signer.document().id()),
+                        format!(
+                            "wallet: {} credentials, {} with a known status",
+                            app.wallet.entries.len(),
+                            app.statuses.len()
+                        ),
+                        format!(
+                            "receipts: {}, {} inclusions tracked",
+                            published.len(),
+                            app.inclusions.len()
+                        ),
+                        format!(
+                            "queue: {} open credentials, {} verification reports",
+                            issue_queue.open().count(),
+                            verification_history.reports.len()
+                        ),
+                        format!("clock offset {}s", clock::offset()),
+                    ];
+                    drop(app);
+                    for line in lines {
+                        bus.log(line);
+                    }
+                }
+                Err(error) => status = error,
+            },
+            Some(Action::PublishJson) => {
+                if let Err(error) = backup::create(&config) {
+                    status = format!("Nothing changed, backing up the snapshot failed: {}", error);
+                    continue;
+                }
+                let path = match console_file.take() {
+                    Some(path) => path,
+                    None => continue,
+                };
+                let mut document = match confirmed {
+                    Some(write) => write.document,
+                    None => match console::read_document(&path) {
+                        Ok(document) => document,
+                        Err(error) => {
+                            bus.log(format!("Reading {} failed: {}", path, error));
+                            continue;
+                        }
+                    },
+                };
+                match service.publish_document(&mut document).await {
+                    Ok(receipt) => {
+                        let operation = if document.previous_message_id().is_null() {
+                            receipts::Operation::Create
+                        } else {
+                            receipts::Operation::Update
+                        };
+                        store.save_receipt(receipts::PublishReceipt::new(
+                            &document, &receipt, operation,
+                        )?)?;
+                        published = store.receipts();
+                        bus.log(format!(
+                            "Published {} from {} in {}",
+                            document.id(),
+                            path,
+                            receipt.message_id()
+                        ));
+                    }
+                    Err(error) => bus.log(format!("Publishing {} failed: {}", path, error)),
+                }
+            }
+            Some(Action::ExportCredential) => {
+                fs::write("credential.json", &credential_str)?;
+                fs::write(
+                    "credential.asc",
+                    armor::encode(armor::Kind::Credential, &credential_str),
+                )?;
+                status = match Credential::from_json(&credential_str) {
+                    Ok(credential) => {
+                        fs::write("credential.html", branding::certificate(&credential))?;
+                        "Exported credential to credential.json, credential.asc and credential.html"
+                            .to_string()
+                    }
+                    Err(_) => {
+                        "Exported credential to credential.json and credential.asc".to_string()
+                    }
+                };
+            }
+            Some(Action::BackupSnapshot) => {
+                status = match backup::create(&config) {
+                    Ok(Some(backup)) => format!("Backed up snapshot to {}", backup.path.display()),
+                    Ok(None) => "There is no snapshot to back up".to_string(),
+                    Err(error) => format!("Backing up the snapshot failed: {}", error),
+                };
+            }
+            Some(Action::ExportBundle) => {
+                let scenario = bundle::Scenario {
+                    issuer: issuer_doc.clone(),
+                    holder: subject_doc.clone(),
+                    templates: [&issue_form, &request_form]
+                        .iter()
+                        .map(|form| bundle::Template {
+                            credential_type: form.credential_type.clone(),
+                            contexts: form.contexts.clone(),
+                            claims: form.claims.clone(),
+                        })
+                        .collect(),
+                    issued: store.load_issued(),
+                    wallet: store.load_wallet(),
+                    presentations: bundle::presentations(&config.inbox_dir),
+                    history: store.load_history(),
+                };
+                restore_status = match bundle::export(&config.export_dir, &scenario) {
+                    Ok(path) => {
+                        bus.log(format!("Exported the scenario to {}", path.display()));
+                        format!("Exported the scenario to {}", path.display())
+                    }
+                    Err(error) => format!("Exporting the scenario failed: {}", error),
+                };
+                active_menu_item = MenuItem::Restore;
+            }
+            Some(Action::ImportBundle) => {
+                let path = prompt_input.trim().to_string();
+                let (manifest, scenario) = match bundle::import(Path::new(&path)) {
+                    Ok(imported) => imported,
+                    Err(error) => {
+                        restore_status = format!("{} is not a scenario bundle: {}", path, error);
+                        continue;
+                    }
+                };
+                for (form, template) in [&mut issue_form, &mut request_form]
+                    .iter_mut()
+                    .zip(&scenario.templates)
+                {
+                    form.credential_type = template.credential_type.clone();
+                    if template.contexts.is_some() {
+                        form.contexts = template.contexts.clone();
+                    }
+                    form.claims = template.claims.clone();
+                }
+                issued = scenario.issued;
+                store.save_issued(&issued)?;
+                revocation::publish(&config, &issued, signer.document().id().as_str())?;
+                verification_history = scenario.history;
+                store.save_history(&verification_history)?;
+                bundle::restore_presentations(&config.inbox_dir, &scenario.presentations)?;
+                let mut app = state.write().await;
+                app.wallet = scenario.wallet;
+                app.statuses.clear();
+                store.save_wallet(&app.wallet)?;
+                wallet_list_state.select(Some(0));
+                presentation_selection.clear();
+                bus.log(format!(
+                    "Imported the scenario of {} from {}",
+                    manifest.created, path
+                ));
+                // Keys are not bundled, credentials of other identities only verify against their DIDs.
+                restore_status = if manifest.issuer == issuer_doc.id().as_str() {
+                    format!("Imported the scenario of {}", manifest.created)
+                } else {
+                    format!(
+                        "Imported the scenario of {}, it was issued by {} instead of this issuer",
+                        manifest.created, manifest.issuer
+                    )
+                };
+            }
+            Some(Action::SendRequest) => {
+                let credential_request = request::create(
+                    &subject_doc,
+                    &subject_key,
+                    &request_form.credential_type,
+                    submitted_claims.clone(),
+                )?;
+                request_status = match request::send(
+                    &config.inbox_dir,
+                    &credential_request,
+                    &subject_doc,
+                    &subject_key,
+                ) {
+                    Ok(path) => format!("Sent request to {}", path.display()),
+                    Err(error) => format!("Sending the request failed: {}", error),
+                };
+            }
+            Some(Action::AcceptRequest) => {
+                let selected = incoming_list_state.selected().and_then(|i| incoming.get(i));
+                if let Some(selected) = selected.cloned() {
+                    if selected.verified {
+                        let mut request = selected.request.clone();
+                        if let Err(error) = claim_hooks.run(
+                            config.hooks_for(&request.credential_type),
+                            &mut request.claims,
+                        ) {
+                            status = error;
+                            continue;
+                        }
+                        let id = ids::generate(
+                            config.id_strategy_for(&request.credential_type),
+                            &mut issued,
+                            &request.holder,
+                            &request.credential_type,
+                            &serde_json::to_string(&request.claims)?,
+                        );
+                        // A request names no contexts, the registry ones of its type are used.
+                        let mut credential: Credential = issue::issue_requested(
+                            signer.document(),
+                            &id,
+                            &config.contexts_for(&request.credential_type),
+                            &request,
+                        )?;
+                        branding::attach(&mut credential, config.branding.as_ref());
+                        issue::set_validity(&mut credential, config.validity_days);
+                        refresh::attach(&mut credential, &config)?;
+                        revocation::attach(&mut credential, &config)?;
+                        for warning in lint::credential(&credential) {
+                            bus.log(format!("Requested credential: {}", warning));
+                        }
+                        service.issue_credential(&*signer, &mut credential).await?;
+                        issued.record(&credential);
+                        store.save_issued(&issued)?;
+                        status = match request::accept(
+                            &config.inbox_dir,
+                            &peers,
+                            &selected,
+                            &credential,
+                        ) {
+                            Ok(path) => format!("Delivered credential to {}", path.display()),
+                            Err(error) => format!("Delivering failed: {}", error),
+                        };
+                        match pending_requests(&nodes, &config.inbox_dir).await {
+                            Ok(received) => incoming = received,
+                            Err(error) => {
+                                status =
+                                    format!("{}, reading the requests failed: {}", status, error)
+                            }
+                        }
+                    } else {
+                        status = "Requests with an invalid holder signature can only be rejected"
+                            .to_string();
+                    }
+                }
+            }
+            Some(Action::RejectRequest) => {
+                let selected = incoming_list_state.selected().and_then(|i| incoming.get(i));
+                if let Some(selected) = selected {
+                    status = match request::reject(&config.inbox_dir, selected, &prompt_input) {
+                        Ok(_) => format!("Rejected request {}", selected.id),
+                        Err(error) => format!("Rejecting failed: {}", error),
+                    };
+                }
+                match pending_requests(&nodes, &config.inbox_dir).await {
+                    Ok(received) => incoming = received,
+                    Err(error) => status = format!("Reading the requests failed: {}", error),
+                }
+            }
+            Some(Action::ShowWallet) => active_menu_item = MenuItem::Wallet,
+            Some(Action::ShowLog) => active_menu_item = MenuItem::Log,
+            Some(Action::ShowSettings) => active_menu_item = MenuItem::Settings,
+            Some(Action::ShowDashboard) => {
+                active_menu_item = MenuItem::Dashboard;
+                // Credentials count as revoked once their signing method is gone from the issuer.
+                status = match service.resolve(signer.document().id()).await {
+                    Ok(core::OnNetwork {
+                        value: Some((document, _)),
+                        ..
+                    }) => match issued.mark_revoked(&document) {
+                        0 => String::new(),
+                        revoked => format!("{} issued credentials were revoked", revoked),
+                    },
+                    Ok(core::OnNetwork { mismatch, .. }) => format!(
+                        "Revocations not checked: {}",
+                        mismatch
+                            .map(|mismatch| mismatch.to_string())
+                            .unwrap_or_default()
+                    ),
+                    Err(error) => format!("Revocations not checked: {}", error),
+                };
+                store.save_issued(&issued)?;
+                revocation::publish(&config, &issued, signer.document().id().as_str())?;
+            }
+            Some(Action::ShowHistory) => {
+                published = store.receipts();
+                active_menu_item = MenuItem::History;
+            }
+            Some(Action::ShowKeys) => active_menu_item = MenuItem::Keys,
+            Some(Action::CheckReceipts) => {
+                status = match nodes::route(&nodes).into_iter().next() {
+                    Some(node) => {
+                        for receipt in published.iter().filter(|r| r.republished_as.is_none()) {
+                            let inclusion =
+                                receipts::check(&node, receipt, config.confirm_timeout).await;
+                            state
+                                .write()
+                                .await
+                                .inclusions
+                                .insert(receipt.message_id.clone(), inclusion);
+                        }
+                        format!("Checked {} published messages on {}", published.len(), node)
+                    }
+                    None => "No node is enabled".to_string(),
+                };
+            }
+            Some(Action::RepublishReceipt) => {
+                if let Err(error) = backup::create(&config) {
+                    status = format!("Nothing changed, backing up the snapshot failed: {}", error);
+                    continue;
+                }
+                let selected = published_list_state
+                    .selected()
+                    .filter(|i| *i < published.len());
+                if let Some(i) = selected {
+                    let inclusion = state
+                        .read()
+                        .await
+                        .inclusions
+                        .get(&published[i].message_id)
+                        .cloned();
+                    if published[i].republished_as.is_some()
+                        || !inclusion.map_or(false, |inclusion| inclusion.needs_republish())
+                    {
+                        status = "Only stale or conflicting messages are republished".to_string();
+                        continue;
+                    }
+                    let client = nodes::client(&nodes).await?;
+                    status = match receipts::republish(&client, &published[i]).await {
+                        Ok(republished) => {
+                            let message_id = republished.message_id.clone();
+                            published[i].republished_as = Some(message_id.clone());
+                            published.push(republished);
+                            store.save_receipts(&published)?;
+                            bus.log(format!(
+                                "Republished {} in {}",
+                                published[i].did, message_id
+                            ));
+                            format!("Republished {}", published[i].did)
+                        }
+                        Err(error) => format!("Republishing failed: {}", error),
+                    };
+                }
+            }
+            Some(Action::ShowChain) => {
+                // Explore the last resolved DID, or the issuer if nothing was resolved yet.
+                let did = resolved_doc.as_ref().unwrap_or(&issuer_doc).id().clone();
+                let loaded = match nodes::client(&nodes).await {
+                    Ok(client) => explorer::load(&client, &did).await,
+                    Err(error) => Err(error),
+                };
+                match loaded {
+                    Ok(messages) => {
+                        chain_list_state.select(messages.len().checked_sub(1));
+                        chain_messages = messages;
+                        chain_did = did.to_string();
+                    }
+                    Err(error) => {
+                        // The receipts still point to the last publish without a node.
+                        let last = receipts::for_did(&published, did.as_str())
+                            .into_iter()
+                            .find_map(|receipt| receipt.explorer_url.clone());
+                        status = match last {
+                            Some(url) => format!(
+                                "Reading the messages of {} failed: {}, its last publish is {}",
+                                did, error, url
+                            ),
+                            None => format!("Reading the messages of {} failed: {}", did, error),
+                        }
+                    }
+                }
+                active_menu_item = MenuItem::Chain;
+            }
+            Some(Action::AcceptOffer) => {
+                let offered: String = match offer.take() {
+                    Some(offered) => offered,
+                    None => continue,
+                };
+                status = match service.verify_credential(&offered).await {
+                    Ok(core::OnNetwork {
+                        value:
+                            Some(verification::Verified {
+                                credential,
+                                verified: true,
+                                ..
+                            }),
+                        ..
+                    }) => {
+                        let mut app = state.write().await;
+                        let title = credential.types.iter().last().cloned().unwrap_or_default();
+                        let id = handle::digest(&credential.to_json()?);
+                        if app.wallet.receive(credential, status::Status::Valid)?
+                            == wallet::Arrival::Added
+                        {
+                            if let Some(older) = app.wallet.link_predecessor(&id) {
+                                pending_supersessions.push((id.clone(), older));
+                            }
+                            if config.subject_consent {
+                                if let Some(entry) = app.wallet.get_mut(&id) {
+                                    entry.acceptance = Some(consent::accept(
+                                        &subject_doc,
+                                        &subject_key,
+                                        &entry.credential,
+                                    )?);
+                                }
+                            }
+                            app.statuses.insert(id, status::Status::Valid);
+                            store.save_wallet(&app.wallet)?;
+                            active_menu_item = MenuItem::Wallet;
+                            format!("Accepted offered {} into the wallet", title)
+                        } else {
+                            format!("Offered {} is already in the wallet", title)
+                        }
+                    }
+                    Ok(core::OnNetwork { value: Some(_), .. }) => {
+                        "Offered credential is NOT valid, not accepted".to_string()
+                    }
+                    Ok(core::OnNetwork { mismatch, .. }) => format!(
+                        "Offered credential not verified: {}",
+                        mismatch
+                            .map(|mismatch| mismatch.to_string())
+                            .unwrap_or_default()
+                    ),
+                    Err(error) => format!("Offer is not a credential: {}", error),
+                };
+            }
+            Some(Action::CollectCredentials) => {
+                let mut arriving: Vec<Credential> =
+                    request::responses(&config.inbox_dir, subject_doc.id().as_str())
+                        .into_iter()
+                        .filter_map(|response| match response {
+                            request::Response::Issued(credential) => Some(credential),
+                            _ => None,
+                        })
+                        .collect();
+                arriving.extend(peers.receive(&config.inbox_dir, subject_doc.id().as_str()));
+                if !credential_str.is_empty() {
+                    arriving.push(Credential::from_json(&credential_str)?);
+                }
+                // Verify before storing, without holding the state while the issuers resolve.
+                let client = match nodes::client(&nodes).await {
+                    Ok(client) => client,
+                    Err(error) => {
+                        status = format!("Nothing collected, no node to verify with: {}", error);
+                        continue;
+                    }
+                };
+                let mut checked: Vec<(Credential, status::Status)> = Vec::new();
+                for credential in arriving {
+                    let checked_status = status::check(&client, &credential).await;
+                    checked.push((credential, checked_status));
+                }
+                let mut app = state.write().await;
+                let (mut added, mut unverified, mut rejected) = (0, 0, 0);
+                for (credential, checked_status) in checked {
+                    let id = handle::digest(&credential.to_json()?);
+                    let title = credential.types.iter().last().cloned().unwrap_or_default();
+                    match app.wallet.receive(credential, checked_status)? {
+                        wallet::Arrival::Added => added += 1,
+                        wallet::Arrival::Unverified => unverified += 1,
+                        wallet::Arrival::Held => continue,
+                        wallet::Arrival::Rejected(reason) => {
+                            rejected += 1;
+                            bus.log(format!("Refused {}, it is {:?}", title, reason));
+                            continue;
+                        }
+                    }
+                    if let Some(older) = app.wallet.link_predecessor(&id) {
+                        pending_supersessions.push((id.clone(), older));
+                    }
+                    // The holder counter-signs what it accepts into the wallet.
+                    if config.subject_consent {
+                        if let Some(entry) = app.wallet.get_mut(&id) {
+                            entry.acceptance = Some(consent::accept(
+                                &subject_doc,
+                                &subject_key,
+                                &entry.credential,
+                            )?);
+                        }
+                    }
+                    app.statuses.insert(id, checked_status);
+                }
+                store.save_wallet(&app.wallet)?;
+                status = format!(
+                    "Added {} credentials to the wallet, {} unverified, {} refused as invalid or revoked",
+                    added + unverified,
+                    unverified,
+                    rejected
+                );
+                active_menu_item = MenuItem::Wallet;
+            }
+            Some(Action::TagCredential) | Some(Action::FileCredential) => {
+                let selected = wallet_list_state
+                    .selected()
+                    .and_then(|i| wallet_entries.get(i));
+                let mut app = state.write().await;
+                if let Some(entry) = selected.and_then(|selected| app.wallet.get_mut(&selected.id))
+                {
+                    if action == Some(Action::TagCredential) {
+                        entry.tags.extend(wallet::parse_tags(&prompt_input));
+                    } else {
+                        entry.folder =
+                            Some(prompt_input.trim().to_string()).filter(|f| !f.is_empty());
+                    }
+                    store.save_wallet(&app.wallet)?;
+                }
+            }
+            Some(Action::CustomizeCredential) => {
+                let appearance = match wallet::Appearance::parse(&prompt_input) {
+                    Ok(appearance) => appearance,
+                    Err(error) => {
+                        status = error;
+                        continue;
+                    }
+                };
+                let selected = wallet_list_state
+                    .selected()
+                    .and_then(|i| wallet_entries.get(i));
+                let mut app = state.write().await;
+                if let Some(entry) = selected.and_then(|selected| app.wallet.get_mut(&selected.id))
+                {
+                    entry.appearance = appearance;
+                    status = format!("{} is listed as {}", entry.title(), entry.name());
+                    store.save_wallet(&app.wallet)?;
+                }
+            }
+            Some(Action::DeriveAgeOver) => {
+                let source: Option<Credential> = wallet_list_state
+                    .selected()
+                    .and_then(|i| wallet_entries.get(i))
+                    .map(|entry| entry.credential.clone());
+                let threshold: u32 = match prompt_input.trim() {
+                    "" => 18,
+                    threshold => match threshold.parse() {
+                        Ok(threshold) => threshold,
+                        Err(_) => {
+                            status = format!("{} is not an age", threshold);
+                            continue;
+                        }
+                    },
+                };
+                let source: Credential = match source {
+                    Some(source) => source,
+                    None => continue,
+                };
+                // Only a birthdate that still verifies may back the derived claim.
+                let checked = service.verify_credential(&source.to_json()?).await?;
+                if !checked.value.map_or(false, |checked| checked.verified) {
+                    status =
+                        "The birthdate credential does not verify, nothing derived".to_string();
+                    continue;
+                }
+                // The original issuer signs if it is this identity, otherwise it acts as derivation service.
+                let signed_by = if source.issuer.url().as_str() == signer.document().id().as_str() {
+                    "the original issuer"
+                } else {
+                    "the derivation service"
+                };
+                status = match derive::age_over(signer.document(), &source, threshold) {
+                    Ok(mut credential) => {
+                        service.issue_credential(&*signer, &mut credential).await?;
+                        issued.record(&credential);
+                        store.save_issued(&issued)?;
+                        let id = handle::digest(&credential.to_json()?);
+                        let mut app = state.write().await;
+                        if app.wallet.add(credential)? {
+                            if let Some(older) = app.wallet.link_predecessor(&id) {
+                                pending_supersessions.push((id, older));
+                            }
+                        }
+                        store.save_wallet(&app.wallet)?;
+                        format!(
+                            "Derived an over {} credential signed by {}",
+                            threshold, signed_by
+                        )
+                    }
+                    Err(error) => format!("Nothing derived: {}", error),
+                };
+            }
+            Some(Action::SelfAttest) => {
+                let claims: Object = match claims::parse(&prompt_input) {
+                    Ok(claims) if !claims.is_empty() => claims,
+                    Ok(_) => {
+                        status = "Nothing to attest, enter at least one claim".to_string();
+                        continue;
+                    }
+                    Err(error) => {
+                        status = error;
+                        continue;
+                    }
+                };
+                // The holder signs with its own key, as issuer and subject at once.
+                let mut credential: Credential =
+                    issue::self_attest(&subject_doc, &ids::uuid(), claims)?;
+                issue::set_validity(&mut credential, config.validity_days);
+                let signed: String = proof::sign(
+                    &subject_doc,
+                    &subject_key,
+                    credential,
+                    ProofFormat::Embedded,
+                )?;
+                let mut app = state.write().await;
+                app.wallet.add(Credential::from_json(&signed)?)?;
+                store.save_wallet(&app.wallet)?;
+                status = format!("Self-attested a credential as {}", subject_doc.id());
+                bus.log(status.clone());
+            }
+            Some(Action::PresentSelected) => {
+                let credentials: Vec<Credential> = state
+                    .read()
+                    .await
+                    .wallet
+                    .entries
+                    .iter()
+                    .filter(|entry| presentation_selection.contains(&entry.id))
+                    .map(|entry| entry.credential.clone())
+                    .collect();
+                if credentials.is_empty() {
+                    status = "Mark credentials with space to present them".to_string();
+                } else {
+                    let presentation = present::present(
+                        &subject_doc,
+                        &subject_key,
+                        credentials,
+                        presentation_request.as_ref(),
+                    )?;
+                    issued.record_presentation(&presentation);
+                    store.save_issued(&issued)?;
+                    if !ephemeral {
+                        // Armored for pasting into chat or email, where JSON gets mangled.
+                        let dir = Path::new(&config.export_dir);
+                        fs::create_dir_all(dir)?;
+                        let path = dir.join("presentation.asc");
+                        fs::write(
+                            &path,
+                            armor::encode(armor::Kind::Presentation, &presentation.to_json()?),
+                        )?;
+                        bus.log(format!(
+                            "Wrote the armored presentation to {}",
+                            path.display()
+                        ));
+                    }
+                    if let Some(request) = &presentation_request {
+                        let path = oob::respond(&config.inbox_dir, request, &presentation)?;
+                        bus.log(format!(
+                            "Answered presentation request {} via {}",
+                            request.id,
+                            path.display()
+                        ));
+                    }
+                    if let Some(target) = &transfer_target {
+                        let id = presentation_request
+                            .as_ref()
+                            .map(|request| request.id.as_str());
+                        bus.log(
+                            match discovery::send_presentation(target, id, &presentation).await {
+                                Ok(()) => format!("Sent the presentation to {}", target.name),
+                                Err(error) => format!(
+                                    "Sending the presentation to {} failed: {}",
+                                    target.name, error
+                                ),
+                            },
+                        );
+                    }
+                    let checked = service.verify_presentation(&presentation, &[]).await;
+                    presentation_selection.clear();
+                    match checked {
+                        Ok(report) => {
+                            status = format!(
+                                "Presented {} credentials from {} issuers, verified: {}",
+                                report.credentials.len(),
+                                report.by_issuer().len(),
+                                report.verified
+                            );
+                            bus.log(format!("Verification finished: {}", status));
+                            presentation_report = Some(report);
+                            active_menu_item = MenuItem::Verify;
+                        }
+                        Err(error) => {
+                            status =
+                                format!("Presented, verifying the presentation failed: {}", error)
+                        }
+                    }
+                }
+            }
+            Some(Action::ArchiveCredential) | Some(Action::DeleteCredential) => {
+                let selected: Option<String> = wallet_list_state
+                    .selected()
+                    .and_then(|i| wallet_entries.get(i))
+                    .map(|entry| entry.id.clone());
+                let id: String = match selected {
+                    Some(id) => id,
+                    None => continue,
+                };
+                let mut app = state.write().await;
+                status = if action == Some(Action::DeleteCredential) {
+                    match app.wallet.delete(&id) {
+                        Some(entry) => {
+                            app.statuses.remove(&id);
+                            bus.log(format!("Deleted {} from the wallet", entry.title()));
+                            format!("Deleted {} permanently", entry.title())
+                        }
+                        None => continue,
+                    }
+                } else {
+                    match app.wallet.toggle_archive(&id) {
+                        Some(true) => "Archived the credential, Z shows the archive".to_string(),
+                        Some(false) => "Restored the credential from the archive".to_string(),
+                        None => continue,
+                    }
+                };
+                store.save_wallet(&app.wallet)?;
+                presentation_selection.remove(&id);
+                wallet_list_state.select(Some(0));
+            }
+            Some(Action::SupersedeCredential) => {
+                if pending_supersessions.is_empty() {
+                    continue;
+                }
+                match prompt_input.trim() {
+                    "r" => {
+                        let (_, older) = pending_supersessions.remove(0);
+                        let mut app = state.write().await;
+                        if let Some(entry) = app.wallet.get_mut(&older) {
+                            entry.archived.get_or_insert_with(Timestamp::now_utc);
+                            status =
+                                format!("Replaced the older {}, it is archived", entry.title());
+                        }
+                        store.save_wallet(&app.wallet)?;
+                        presentation_selection.remove(&older);
+                        wallet_list_state.select(Some(0));
+                    }
+                    "k" => {
+                        pending_supersessions.remove(0);
+                        status = "Kept both credentials".to_string();
+                    }
+                    other => status = format!("{} is neither r nor k", other),
+                }
+            }
+            Some(Action::ResolveDuplicate) => {
+                if pending_duplicates.is_empty() {
+                    continue;
+                }
+                let answer: String = prompt_input.trim().to_string();
+                if !["m", "s", "k"].contains(&answer.as_str()) {
+                    status = format!("{} is none of m, s or k", answer);
+                    continue;
+                }
+                let (held, imported) = pending_duplicates.remove(0);
+                let title = imported.types.iter().last().cloned().unwrap_or_default();
+                let mut app = state.write().await;
+                status = match answer.as_str() {
+                    "m" if app.wallet.merge(&held, imported)? => {
+                        presentation_selection.remove(&held);
+                        format!("Merged the imported {}, the held one is archived", title)
+                    }
+                    "m" => format!("Kept the held {}, it is the newer one", title),
+                    "k" => {
+                        app.wallet.add(imported)?;
+                        format!("Kept both {} credentials", title)
+                    }
+                    _ => format!("Skipped the imported {}", title),
+                };
+                store.save_wallet(&app.wallet)?;
+                wallet_list_state.select(Some(0));
+            }
+            Some(Action::ExportWalletCredential) => {
+                let selected: Option<String> = wallet_list_state
+                    .selected()
+                    .and_then(|i| wallet_entries.get(i))
+                    .map(|entry| entry.id.clone());
+                let app = state.read().await;
+                status = match selected.map(|id| app.wallet.export_entry(&id, &config.export_dir)) {
+                    Some(Ok(Some(path))) => format!(
+                        "Exported the credential to {}, armored to {}",
+                        path.display(),
+                        path.with_extension("asc").display()
+                    ),
+                    Some(Err(error)) => format!("Exporting the credential failed: {}", error),
+                    _ => "Select a credential to export".to_string(),
+                };
+            }
+            Some(Action::PickLanPeer) => {
+                let input = prompt_input.trim();
+                let picked = input
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|number| number.checked_sub(1))
+                    .and_then(|index| lan_instances.get(index));
+                status = match picked {
+                    _ if input.is_empty() => {
+                        transfer_target = None;
+                        "No LAN instance picked".to_string()
+                    }
+                    Some(instance) => {
+                        transfer_target = Some(instance.clone());
+                        format!(
+                            "T sends credentials to {}, presentations go there too",
+                            instance.name
+                        )
+                    }
+                    None => format!("{} is no listed instance", input),
+                };
+            }
+            Some(Action::TransferCredential) => {
+                let selected: Option<wallet::Entry> = wallet_list_state
+                    .selected()
+                    .and_then(|i| wallet_entries.get(i))
+                    .cloned();
+                status = match (selected, &transfer_target) {
+                    (Some(entry), Some(target)) => {
+                        match discovery::send_credential(target, &entry.credential).await {
+                            Ok(()) => {
+                                bus.log(format!("Sent {} to {}", entry.title(), target.url));
+                                format!("Sent {} to {}", entry.title(), target.name)
+                            }
+                            Err(error) => format!("Sending to {} failed: {}", target.name, error),
+                        }
+                    }
+                    (None, _) => "Select a credential to send".to_string(),
+                    (_, None) => "Pick a LAN instance with L first".to_string(),
+                };
+            }
+            Some(Action::RefreshCredential) => {
+                let selected: Option<wallet::Entry> = wallet_list_state
+                    .selected()
+                    .and_then(|i| wallet_entries.get(i))
+                    .cloned();
+                let entry = match selected {
+                    Some(entry) => entry,
+                    None => continue,
+                };
+                let renewed =
+                    match refresh::request(&entry.credential, &subject_doc, &subject_key).await {
+                        Ok(renewed) => renewed,
+                        Err(error) => {
+                            status = format!("Refreshing {} failed: {}", entry.title(), error);
+                            continue;
+                        }
+                    };
+                let client = nodes::client(&nodes).await?;
+                let checked_status = status::check(&client, &renewed).await;
+                if checked_status != status::Status::Valid {
+                    status = format!(
+                        "The renewed {} is {:?}, kept the old one",
+                        entry.title(),
+                        checked_status
+                    );
+                    continue;
+                }
+                let mut app = state.write().await;
+                if let Some(id) = app.wallet.swap(&entry.id, renewed)? {
+                    app.statuses.remove(&entry.id);
+                    app.statuses.insert(id, checked_status);
+                    store.save_wallet(&app.wallet)?;
+                    status = format!("Renewed {}", entry.title());
+                    bus.log(status.clone());
+                }
+            }
+            Some(Action::PairDevice) => {
+                let label = prompt_input.trim().to_string();
+                match peers.invite(&label, subject_doc.id().as_str()) {
+                    Ok(link) => {
+                        peers.save(&config.peers_path)?;
+                        bus.log(format!("Invited {} to pair", label));
+                        pairing_link = Some(link);
+                    }
+                    Err(error) => status = format!("Pairing failed: {}", error),
+                }
+            }
+            Some(Action::AcceptPairing) => {
+                let invitation = match pending_invitation.take() {
+                    Some(invitation) => invitation,
+                    None => continue,
+                };
+                status = match peers.accept(prompt_input.trim(), &invitation) {
+                    Ok(peer) => format!(
+                        "Paired with {}, credentials for {} now travel encrypted",
+                        peer.label, peer.holder
+                    ),
+                    Err(error) => format!("Not a pairing invitation: {}", error),
+                };
+                peers.save(&config.peers_path)?;
+            }
+            Some(Action::ExportFolder) => {
+                let selected = wallet_list_state
+                    .selected()
+                    .and_then(|i| wallet_entries.get(i));
+                status = match selected.and_then(|entry| entry.folder.as_ref()) {
+                    Some(folder) => {
+                        let app = state.read().await;
+                        let path = app.wallet.export_folder(folder, &config.export_dir)?;
+                        format!("Exported folder {} to {}", folder, path.display())
+                    }
+                    None => "Select a credential in a folder to export that folder".to_string(),
+                };
+            }
+            Some(Action::ExportWallet) => {
+                // Exports what the wallet shows, the tag filter and the archive view narrow it.
+                status = match portable::export(
+                    &config.export_dir,
+                    subject_doc.id().as_str(),
+                    wallet_filter.as_deref(),
+                    &wallet_entries,
+                ) {
+                    Ok(path) => format!(
+                        "Exported {} credentials to {}",
+                        wallet_entries.len(),
+                        path.display()
+                    ),
+                    Err(error) => format!("Exporting the wallet failed: {}", error),
+                };
+            }
+            Some(Action::ImportWallet) => {
+                let path = prompt_input.trim().to_string();
+                let (manifest, credentials) = match portable::import(Path::new(&path)) {
+                    Ok(imported) => imported,
+                    Err(error) => {
+                        status = format!("{} is not a wallet export: {}", path, error);
+                        continue;
+                    }
+                };
+                let mut outcomes: Vec<portable::Outcome> = Vec::new();
+                for credential in credentials {
+                    let title = credential.types.iter().last().cloned().unwrap_or_default();
+                    let checked = service.verify_credential(&credential.to_json()?).await;
+                    let (verified, error) = match checked {
+                        Ok(core::OnNetwork {
+                            value: Some(result),
+                            ..
+                        }) => (result.verified, result.error.map(|error| error.to_string())),
+                        Ok(core::OnNetwork {
+                            mismatch: Some(mismatch),
+                            ..
+                        }) => (false, Some(mismatch.to_string())),
+                        Ok(_) => (false, None),
+                        Err(error) => (false, Some(error.to_string())),
+                    };
+                    // Credentials that do not verify are kept too, the holder sees why in the log.
+                    // Exact copies are skipped, near duplicates wait for the holder to decide.
+                    let mut app = state.write().await;
+                    let (added, duplicate) = match app.wallet.duplicate_of(&credential)? {
+                        Some(wallet::Duplicate::Exact) => (false, false),
+                        Some(wallet::Duplicate::Near(held)) => {
+                            pending_duplicates.push((held, credential));
+                            (false, true)
+                        }
+                        None => (app.wallet.add(credential)?, false),
+                    };
+                    store.save_wallet(&app.wallet)?;
+                    let outcome = portable::Outcome {
+                        title,
+                        verified,
+                        error,
+                        added,
+                        duplicate,
+                    };
+                    bus.log(format!("Imported {}", outcome.line()));
+                    outcomes.push(outcome);
+                }
+                let failed = outcomes.iter().filter(|outcome| !outcome.verified).count();
+                let held = outcomes
+                    .iter()
+                    .filter(|outcome| !outcome.added && !outcome.duplicate)
+                    .count();
+                status = format!(
+                    "Imported {} credentials{}, {} verified, {} do not, {} already held, {} near duplicates",
+                    outcomes.len(),
+                    manifest
+                        .map(|manifest| format!(" exported by {}", manifest.holder))
+                        .unwrap_or_default(),
+                    outcomes.len() - failed,
+                    failed,
+                    held,
+                    pending_duplicates.len()
+                );
+            }
+            Some(Action::DeleteTag) => {
+                status = match wallet_filter.take() {
+                    Some(tag) => {
+                        let mut app = state.write().await;
+                        let count = app.wallet.delete_tag(&tag);
+                        store.save_wallet(&app.wallet)?;
+                        format!("Removed #{} from {} credentials", tag, count)
+                    }
+                    None => "Filter by a tag to delete it".to_string(),
+                };
+            }
+            Some(Action::ExportKey) => {
+                status = match key_format.take() {
+                    Some(format) if lock::matches(&prompt_input, &store::password()) => {
+                        let dir = Path::new(&config.export_dir);
+                        fs::create_dir_all(dir)?;
+                        let path = dir.join(format!("holder-key.{}", format.extension()));
+                        if path.exists() {
+                            format!(
+                                "{} exists, move it away to export the key again",
+                                path.display()
+                            )
+                        } else {
+                            keys::write(&path, &subject_key, format)?;
+                            bus.log(format!("Exported holder private key to {}", path.display()));
+                            format!("Exported the holder private key to {}", path.display())
+                        }
+                    }
+                    Some(_) => "Wrong password, the key was not exported".to_string(),
+                    None => "Choose an export format first".to_string(),
+                };
+                prompt_input.zeroize();
+            }
+            Some(Action::ImportKey) => {
+                status = match keys::read(&prompt_input) {
+                    Ok(key) => {
+                        // The DID is derived from the key, the published document has the rest.
+                        let did = IotaDocument::new(&key)?.id().clone();
+                        match service.resolve(&did).await {
+                            Ok(core::OnNetwork {
+                                value: Some((document, _)),
+                                ..
+                            }) => {
+                                subject_doc = document;
+                                subject_key = key;
+                                holder_label = None;
+                                state.write().await.holder = subject_doc.id().to_string();
+                                format!("Imported holder {}", subject_doc.id())
+                            }
+                            Ok(core::OnNetwork { mismatch, .. }) => format!(
+                                "Not resolved {}: {}",
+                                did,
+                                mismatch
+                                    .map(|mismatch| mismatch.to_string())
+                                    .unwrap_or_default()
+                            ),
+                            Err(error) => format!("Resolving {} failed: {}", did, error),
+                        }
+                    }
+                    Err(error) => format!("Importing the key failed: {}", error),
+                };
+                prompt_input.zeroize();
+            }
+            Some(Action::PublishKey) => {
+                let imported = keys::read(&prompt_input);
+                prompt_input.zeroize();
+                let key: KeyPair = match imported {
+                    Ok(key) => key,
+                    Err(error) => {
+                        status = format!("Reading the key failed: {}", error);
+                        continue;
+                    }
+                };
+                // The DID follows from the key, only publish if nobody did yet.
+                let did = IotaDocument::new(&key)?.id().clone();
+                let client = nodes::client(&nodes).await?;
+                if client.read_document(&did).await.is_ok() {
+                    status = format!("{} is already published, o imports its key", did);
+                    continue;
+                }
+                if let Err(error) = backup::create(&config) {
+                    status = format!("Nothing changed, backing up the snapshot failed: {}", error);
+                    continue;
+                }
+                let (document, key, receipt) = match confirmed {
+                    Some(write) => service.publish_identity(write.document, key).await?,
+                    None => service.create_identity(key).await?,
+                };
+                store.save_receipt(receipts::PublishReceipt::new(
+                    &document,
+                    &receipt,
+                    receipts::Operation::Create,
+                )?)?;
+                published = store.receipts();
+                bus.log(format!(
+                    "Published {} for an existing key in {}",
+                    document.id(),
+                    receipt.message_id()
+                ));
+                status = format!("Published and switched to holder {}", document.id());
+                subject_doc = document;
+                subject_key = key;
+                holder_label = None;
+                state.write().await.holder = subject_doc.id().to_string();
+            }
+            Some(Action::RestoreBackup) => {
+                let selected = backup_list_state.selected().and_then(|i| backups.get(i));
+                if let Some(selected) = selected.cloned() {
+                    // Close the account so it doesn't write the old snapshot back, the server
+                    // holds it through its own signer too.
+                    let serving: bool = refresher.write().unwrap().take().is_some();
+                    drop(signer);
+                    drop(account);
+                    let restored = backup::restore(&config, &selected);
+                    // Reopened either way, a failed restore leaves the current snapshot in place.
+                    account = Arc::new(account::open(&*store).await?);
+                    let client = nodes::client(&nodes).await?;
+                    signer = signer::open(&config, account.clone(), &issuer_doc, &client).await?;
+                    if serving {
+                        *refresher.write().unwrap() = Some(Arc::from(
+                            signer::open(&config, account.clone(), &issuer_doc, &client).await?,
+                        ));
+                    }
+                    store.secure()?;
+                    restore_status = match restored {
+                        Ok(()) => format!("Restored backup from {}", selected.created_at()),
+                        Err(error) => format!("Restoring the backup failed: {}", error),
+                    };
+                    backups = backup::list(&config)?;
+                }
+            }
+        }
+
+        // Every signature lands in the issuance log, one check here covers all actions signing.
+        let key_usage = usage::per_method(
+            &issued,
+            &config.rotation_due,
+            signer.document(),
+            &subject_doc,
+        );
+        if usage::overdue(&key_usage) > overdue_signatures {
+            for warning in usage::warnings(&key_usage) {
+                bus.log(warning);
+            }
+            status = "A key due for rotation still signs, see the Keys tab (k)".to_string();
+        }
+        overdue_signatures = usage::overdue(&key_usage);
+    }
+
+    Ok(())
+}
+
+/// The requests in the inbox, their holder signatures checked against the Tangle.
+async fn pending_requests(
+    nodes: &nodes::Nodes,
+    inbox: &str,
+) -> Result<Vec<request::Incoming>, Box<dyn std::error::Error>> {
+    let client = nodes::client(nodes).await?;
+    Ok(request::receive(&client, inbox).await?)
+}
+
+/// Signs the open queue items and drops them into the holder inbox, recording the outcome per item.
+/// Returns the last delivered credential and its QR payload.
+async fn deliver_queue(
+    config: &config::Config,
+    signer: &dyn signer::Signer,
+    client: &Client,
+    peers: &pairing::Peers,
+    issue_queue: &mut queue::IssueQueue,
+    issued: &mut issued::IssuanceLog,
+) -> Option<(String, String)> {
+    let mut last = None;
+    for item in issue_queue.open() {
+        // Items that failed after signing keep their proof and are only delivered again.
+        if item.credential.proof.is_none() {
+            if let Err(error) = signer.sign(&mut item.credential).await {
+                item.status = queue::ItemStatus::Failed(error.to_string());
+                continue;
+            }
+            issued.record(&item.credential);
+        }
+        item.status = queue::ItemStatus::Signed;
+        let credential: String = item.credential.to_string();
+        let id: String = handle::digest(&credential);
+        let delivered = request::deliver(&config.inbox_dir, peers, &id[..16], &item.credential);
+        item.status = match delivered {
+            Ok(_) => match handle::share(config, client, &credential).await {
+                Ok(payload) => {
+                    last = Some((credential, payload));
+                    queue::ItemStatus::Delivered
+                }
+                Err(error) => queue::ItemStatus::Failed(error.to_string()),
+            },
+            Err(error) => queue::ItemStatus::Failed(error.to_string()),
+        };
+    }
+    last
+}
+
+/// Issues the degree to the subject once their enrollment credential checks out.
+async fn issue_degree_credential(
+    service: &core::Service,
+    signer: &dyn signer::Signer,
+    id: &str,
+    subject: &IotaDocument,
+    display: Option<&branding::Display>,
+) -> Result<Option<Credential>, Box<dyn std::error::Error>> {
+    let issuer: &IotaDocument = signer.document();
+    // The subject first needs an enrollment credential, which they present to the issuer.
+    let mut enrollment: Credential = issue::issue_enrollment(issuer, subject)?;
+    service.issue_credential(signer, &mut enrollment).await?;
+
+    // The issuer only issues the degree if the enrollment credential checks out.
+    if !service.verify_prerequisite(&enrollment, subject).await? {
+        return Ok(None);
+    }
+
+    // Create an unsigned Credential with claims about `subject` specified by `issuer`.
+    let mut credential: Credential = issue::issue_degree(issuer, id, subject, &enrollment)?;
+    branding::attach(&mut credential, display);
+    // Sign the Credential with the issuer key behind the configured signer.
+    service.issue_credential(signer, &mut credential).await?;
+
+    Ok(Some(credential))
+}
+
+fn render_settings<'a>(
+    config: &config::Config,
+    profile: &profile::Profile,
+    store: &dyn store::IdentityStore,
+    signer: &dyn signer::Signer,
+) -> Paragraph<'a> {
+    let snapshot = Path::new(&config.snapshot_path);
+    let permissions = paths::mode(snapshot).unwrap_or_else(|| "not created yet".to_string());
+    let rows = vec![
+        ("Profile", profile.name.clone()),
+        ("Storage", store.name().to_string()),
+        ("Identities", store.location()),
+        ("Signer", signer.name().to_string()),
+        ("Snapshot permissions", permissions),
+        ("Data directory", config.data_dir.clone()),
+        ("Backups", config.backup_dir.clone()),
+        ("Wallet", config.wallet_path.clone()),
+        ("Inbox", config.inbox_dir.clone()),
+        ("Exports", config.export_dir.clone()),
+        ("Nodes", config.nodes.join(", ")),
+        (
+            "Clock",
+            clock::label()
+                .map(|offset| format!("simulated, {}", offset))
+                .unwrap_or_else(|| "real time".to_string()),
+        ),
+        (
+            "Other networks",
+            if config.auto_switch_network {
+                "resolved on their own network"
+            } else {
+                "reported as mismatch"
+            }
+            .to_string(),
+        ),
+    ];
+    let lines: Vec<Spans> = rows
+        .into_iter()
+        .map(|(name, value)| {
+            Spans::from(vec![
+                Span::styled(format!("{:<22}", name), Style::default().fg(Color::Gray)),
+                Span::raw(value),
+            ])
+        })
+        .collect();
+
+    Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title(format!(
+                "Settings (from {}, o shifts the clock)",
+                config::CONFIG_FILE
+            ))
+            .border_type(BorderType::Plain),
+    )
+}
+
+fn render_issue<'a>(
+    did: &'a str,
+    credential: &'a str,
+    level: EcLevel,
+    form: &claims::ClaimsForm,
+) -> Paragraph<'a> {
+    let mut lines = form.lines("signs the credential for the current holder");
+    lines.push(Spans::from(vec![Span::raw("")]));
+    if credential.is_empty() {
+        lines.push(Spans::from(vec![Span::raw("No credential issued yet.")]));
+        return Paragraph::new(lines).alignment(Alignment::Center).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title("Issue")
+                .border_type(BorderType::Plain),
+        );
+    }
+    let preflight = qr::Preflight::new(credential, level);
+    let mut text = String::new();
+    if let Some(warning) = preflight.warning() {
+        text.push_str(&format!("Warning: {}\n", warning));
+        for suggestion in preflight.suggestions(credential) {
+            text.push_str(&format!("- {}\n", suggestion));
+        }
+        text.push('\n');
+    }
+    if preflight.fits() {
+        if let Ok(image) = qr::render(credential, level) {
+            text.push_str(&image);
+        }
+    }
+    text.push_str(&format!("\nissued by {}", did));
+    lines.extend(tui::text::Text::from(text).lines);
+
+    let issue = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title("Issue")
+                .border_type(BorderType::Plain),
+        )
+        .wrap(Wrap { trim: true });
+    issue
+}
+
+fn render_verify<'a>(
+    report: Option<&integrity::Report>,
+    document: Option<&IotaDocument>,
+    presentation: Option<&verify::PresentationReport>,
+    request: Option<&oob::PresentationRequest>,
+    level: EcLevel,
+    redacted: bool,
+    encoding: jwk::Encoding,
+) -> Paragraph<'a> {
+    let mut lines = vec![
+        Spans::from(vec![Span::raw("")]),
+        Spans::from(vec![Span::raw("Verify")]),
+        Spans::from(vec![Span::raw("")]),
+    ];
+    if let Some(request) = request {
+        let types = if request.credential_types.is_empty() {
+            "any credential".to_string()
+        } else {
+            request.credential_types.join(", ")
+        };
+        lines.push(Spans::from(vec![Span::raw(format!(
+            "Presentation request {} for {}",
+            request.id, types
+        ))]));
+        if let Ok(image) = request
+            .to_json()
+            .map_err(|error| error.to_string())
+            .and_then(|json| qr::render(&json, level).map_err(|error| error.to_string()))
+        {
+            lines.extend(tui::text::Text::from(image).lines);
+        }
+        lines.push(Spans::from(vec![Span::raw(
+            "Waiting for the holder: e pastes a presentation, f checks the inbox",
+        )]));
+        lines.push(Spans::from(vec![Span::raw("")]));
+    }
+    if let Some(presentation) = presentation {
+        lines.extend(presentation.lines());
+        lines.push(Spans::from(vec![Span::raw("")]));
+    }
+    if let Some(report) = report {
+        let (badge, color) = report.integrity.badge();
+        lines.push(Spans::from(vec![
+            Span::styled(badge, Style::default().fg(color)),
+            Span::raw(format!(
+                " {}",
+                if redacted {
+                    redact::dids(&report.did)
+                } else {
+                    report.did.clone()
+                }
+            )),
+        ]));
+        for detail in &report.details {
+            lines.push(Spans::from(vec![Span::raw(detail.clone())]));
+        }
+    }
+    if let Some(document) = document {
+        lines.push(Spans::from(vec![Span::raw("")]));
+        lines.push(Spans::from(vec![Span::raw(format!(
+            "Verification methods as {} (k shows {}, j exports the JWK set):",
+            encoding.label(),
+            encoding.next().label()
+        ))]));
+        for method in document.methods() {
+            let key = match jwk::encode(method, encoding) {
+                Ok(key) => key,
+                Err(error) => error.to_string(),
+            };
+            lines.push(Spans::from(vec![Span::raw(key)]));
+        }
+    }
+    lines.push(Spans::from(vec![Span::raw("")]));
+    lines.push(Spans::from(vec![Span::raw("Press q to quit.")]));
+    let verify = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title("Verify")
+            .border_type(BorderType::Plain),
+    );
+    verify
+}
+
+fn render_nodes<'a>(nodes: &[nodes::Node], routed: &[String]) -> List<'a> {
+    let items: Vec<ListItem> = nodes
+        .iter()
+        .map(|node| {
+            let enabled = if node.enabled { "[x]" } else { "[ ]" };
+            let route = match (node.pinned, routed.contains(&node.url)) {
+                (true, true) => " pinned",
+                (false, true) if routed.len() == 1 => " fastest",
+                _ => "",
+            };
+            let (status, color) = match &node.health {
+                None => ("checking".to_string(), Color::Gray),
+                Some(health) if !health.healthy => ("unhealthy".to_string(), Color::Red),
+                Some(health) => (
+                    format!(
+                        "{} ms, milestone {}{}",
+                        health.latency.as_millis(),
+                        health.milestone,
+                        if health.synced { "" } else { " (syncing)" }
+                    ),
+                    if health.synced {
+                        Color::Green
+                    } else {
+                        Color::Yellow
+                    },
+                ),
+            };
+            ListItem::new(Spans::from(vec![
+                Span::raw(format!("{} {} ", enabled, node.url)),
+                Span::styled(status, Style::default().fg(color)),
+                Span::styled(route, Style::default().fg(Color::LightBlue)),
+            ]))
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title("Nodes (space toggles a node, x pins it instead of the fastest, C compares a DID across them)")
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+}
+
+fn render_restore<'a>(backups: &[backup::Backup], status: &str) -> List<'a> {
+    let items: Vec<ListItem> = backups
+        .iter()
+        .map(|backup| {
+            ListItem::new(Spans::from(vec![
+                Span::raw(format!("{}  ", backup.created_at())),
+                Span::styled(
+                    backup.path.display().to_string(),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]))
+        })
+        .collect();
+
+    let title = if status.is_empty() {
+        "Restore (enter rolls back to the selected backup, x exports the scenario, m imports one)"
+            .to_string()
+    } else {
+        status.to_string()
+    };
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(title)
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+}
@@ -0,0 +1,114 @@
+use std::error::Error;
+
+const LINE_LENGTH: usize = 64;
+/// Initial value and generator of the CRC-24 OpenPGP armor uses (RFC 4880, 6.1).
+const CRC24_INIT: u32 = 0x00B7_04CE;
+const CRC24_POLY: u32 = 0x0186_4CFB;
+
+/// What the armor carries, named in its BEGIN and END lines.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Credential,
+    Presentation,
+}
+
+impl Kind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Kind::Credential => "VERIFIABLE CREDENTIAL",
+            Kind::Presentation => "VERIFIABLE PRESENTATION",
+        }
+    }
+
+    fn begin(&self) -> String {
+        format!("-----BEGIN {}-----", self.label())
+    }
+
+    fn end(&self) -> String {
+        format!("-----END {}-----", self.label())
+    }
+}
+
+fn crc24(data: &[u8]) -> u32 {
+    let mut crc: u32 = CRC24_INIT;
+    for byte in data {
+        crc ^= (*byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x0100_0000 != 0 {
+                crc ^= CRC24_POLY;
+            }
+        }
+    }
+    crc & 0x00FF_FFFF
+}
+
+fn checksum(data: &[u8]) -> String {
+    format!("={}", base64::encode(&crc24(data).to_be_bytes()[1..]))
+}
+
+/// The JSON or JWS as base64 between BEGIN and END lines, with a CRC-24 checksum line like
+/// OpenPGP armor, so it survives chat and email clients rewrapping or reformatting it.
+pub fn encode(kind: Kind, content: &str) -> String {
+    let data: &[u8] = content.trim().as_bytes();
+    let body: Vec<String> = base64::encode(data)
+        .as_bytes()
+        .chunks(LINE_LENGTH)
+        .map(|line| String::from_utf8_lossy(line).into_owned())
+        .collect();
+    format!(
+        "{}\n\n{}\n{}\n{}\n",
+        kind.begin(),
+        body.join("\n"),
+        checksum(data),
+        kind.end()
+    )
+}
+
+/// Whether the text holds an armored credential or presentation, also amid a chat message.
+pub fn is_armored(input: &str) -> bool {
+    [Kind::Credential, Kind::Presentation]
+        .iter()
+        .any(|kind| input.contains(&kind.begin()))
+}
+
+/// The content of the first armor in the text. Quoting (`> `) and indentation added on the way
+/// are ignored, a missing or wrong checksum fails.
+pub fn decode(input: &str) -> Result<(Kind, String), Box<dyn Error>> {
+    let (kind, start) = [Kind::Credential, Kind::Presentation]
+        .iter()
+        .filter_map(|kind| input.find(&kind.begin()).map(|start| (*kind, start)))
+        .min_by_key(|(_, start)| *start)
+        .ok_or("no armored credential or presentation")?;
+    let armored: &str = &input[start + kind.begin().len()..];
+    let end: usize = armored
+        .find(&kind.end())
+        .ok_or_else(|| format!("the armor lacks its {} line", kind.end()))?;
+    let mut body = String::new();
+    let mut check: Option<String> = None;
+    for line in armored[..end].lines() {
+        let line: &str = line.trim_start_matches(|c: char| c == '>' || c.is_whitespace());
+        let line: &str = line.trim_end();
+        if line.starts_with('=') && line.len() == 5 {
+            check = Some(line.to_string());
+        } else if !line.contains(':') {
+            // Lines with a colon are armor headers like `Comment: ...`, base64 has none.
+            body.push_str(line);
+        }
+    }
+    let data: Vec<u8> = base64::decode(&body)?;
+    match check {
+        Some(check) if check == checksum(&data) => Ok((kind, String::from_utf8(data)?)),
+        Some(_) => Err("the armor checksum does not match, the text was altered".into()),
+        None => Err("the armor has no checksum line".into()),
+    }
+}
+
+/// The content of armored input, other input as it is.
+pub fn dearmor(input: &str) -> Result<String, Box<dyn Error>> {
+    if is_armored(input) {
+        Ok(decode(input)?.1)
+    } else {
+        Ok(input.to_string())
+    }
+}
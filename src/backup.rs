@@ -0,0 +1,119 @@
+use crate::config::Config;
+use crate::paths;
+use identity::core::Timestamp;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Debug)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub created: i64,
+    /// Nanoseconds within the second, so backups of the same second sort and never collide.
+    pub nanos: u32,
+}
+
+impl Backup {
+    pub fn created_at(&self) -> String {
+        Timestamp::from_unix(self.created).to_string()
+    }
+}
+
+/// Copies the snapshot to a timestamped backup and drops the oldest backups beyond the retention count.
+pub fn create(config: &Config) -> io::Result<Option<Backup>> {
+    create_keeping(config, None)
+}
+
+/// Like `create`, but never drops the backup at `keep`, the one being restored.
+fn create_keeping(config: &Config, keep: Option<&Path>) -> io::Result<Option<Backup>> {
+    if config.backup_retention == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "backup_retention is 0, every backup would be dropped right after it is written",
+        ));
+    }
+    let snapshot = Path::new(&config.snapshot_path);
+    if !snapshot.exists() {
+        return Ok(None);
+    }
+    paths::restrict_dir(Path::new(&config.backup_dir))?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let (created, mut nanos) = (now.as_secs() as i64, now.subsec_nanos());
+    let mut path = file_name(config, created, nanos);
+    while path.exists() {
+        nanos += 1;
+        path = file_name(config, created, nanos);
+    }
+    fs::copy(snapshot, &path)?;
+    paths::restrict_file(&path)?;
+
+    for old in list(config)?
+        .iter()
+        .filter(|old| Some(old.path.as_path()) != keep)
+        .skip(config.backup_retention)
+    {
+        fs::remove_file(&old.path)?;
+    }
+
+    Ok(Some(Backup {
+        path,
+        created,
+        nanos,
+    }))
+}
+
+fn file_name(config: &Config, created: i64, nanos: u32) -> PathBuf {
+    Path::new(&config.backup_dir).join(format!("{}-{}-{:09}.hodl", stem(config), created, nanos))
+}
+
+/// Lists the backups of the snapshot, newest first.
+pub fn list(config: &Config) -> io::Result<Vec<Backup>> {
+    let prefix = format!("{}-", stem(config));
+    let entries = match fs::read_dir(&config.backup_dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+
+    let mut backups: Vec<Backup> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let suffix: &str = path.file_stem()?.to_str()?.strip_prefix(&prefix)?;
+            // Backups named before the nanoseconds were added have the seconds only.
+            let (created, nanos) = suffix.split_once('-').unwrap_or((suffix, "0"));
+            let (created, nanos) = (created.parse().ok()?, nanos.parse().ok()?);
+            Some(Backup {
+                path,
+                created,
+                nanos,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| (b.created, b.nanos).cmp(&(a.created, a.nanos)));
+    Ok(backups)
+}
+
+/// Replaces the snapshot with the backup. The current snapshot is backed up first.
+/// The backup is copied next to the snapshot and renamed over it, so the snapshot is never
+/// half written.
+pub fn restore(config: &Config, backup: &Backup) -> io::Result<()> {
+    create_keeping(config, Some(&backup.path))?;
+    let snapshot = Path::new(&config.snapshot_path);
+    let restoring = snapshot.with_extension("restoring");
+    fs::copy(&backup.path, &restoring)?;
+    paths::restrict_file(&restoring)?;
+    fs::rename(&restoring, snapshot)
+}
+
+fn stem(config: &Config) -> String {
+    Path::new(&config.snapshot_path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("snapshot")
+        .to_string()
+}
@@ -0,0 +1,90 @@
+use crate::core::Service;
+use crate::keys::{self, KeyFormat};
+use crate::paths;
+use crate::receipts::{Operation, PublishReceipt};
+use crate::seed;
+use crate::store::IdentityStore;
+use identity::iota::TangleRef;
+use serde_json::json;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Semaphore;
+
+/// Identities published at once unless `--parallel` says otherwise.
+pub const DEFAULT_PARALLEL: usize = 4;
+
+/// Creates and publishes `count` identities, at most `parallel` at a time, and writes each
+/// DID with its private key as JWK to `<export_dir>/identities/participant-<n>.json`.
+/// Progress goes to stderr, returns how many identities were created.
+pub async fn create_dids(
+    service: &Service,
+    store: Arc<dyn IdentityStore>,
+    export_dir: &str,
+    count: usize,
+    parallel: usize,
+    seed: Option<&str>,
+) -> Result<usize, Box<dyn Error>> {
+    let dir: PathBuf = Path::new(export_dir).join("identities");
+    paths::restrict_dir(&dir)?;
+    let permits = Arc::new(Semaphore::new(parallel.max(1)));
+    let finished = Arc::new(AtomicUsize::new(0));
+    let started = Instant::now();
+
+    let mut tasks = Vec::new();
+    for n in 1..=count {
+        let label = format!("participant-{}", n);
+        // Seeded key pairs give every workshop run the same participant DIDs.
+        let keypair = seed::keypair(seed, &label)?;
+        let service = service.clone();
+        let permits = permits.clone();
+        let finished = finished.clone();
+        tasks.push(tokio::spawn(async move {
+            let _permit = permits
+                .acquire_owned()
+                .await
+                .map_err(|error| error.to_string())?;
+            let created = service
+                .create_identity(keypair)
+                .await
+                .map_err(|error| format!("{}: {}", label, error));
+            let done = finished.fetch_add(1, Ordering::SeqCst) + 1;
+            match &created {
+                Ok((document, _, _)) => {
+                    eprintln!("[{}/{}] {} {}", done, count, label, document.id())
+                }
+                Err(error) => eprintln!("[{}/{}] failed {}", done, count, error),
+            }
+            created.map(|created| (label, created))
+        }));
+    }
+
+    let mut created = 0;
+    for task in tasks {
+        let (label, (document, keypair, receipt)) = match task.await? {
+            Ok(identity) => identity,
+            Err(_) => continue,
+        };
+        store.save_receipt(PublishReceipt::new(&document, &receipt, Operation::Create)?)?;
+        let path = dir.join(format!("{}.json", label));
+        let identity = json!({
+            "did": document.id().to_string(),
+            "messageId": document.message_id().to_string(),
+            "privateKeyJwk": serde_json::from_str::<serde_json::Value>(&keys::export(&keypair, KeyFormat::Jwk)?)?,
+        });
+        fs::write(&path, serde_json::to_string_pretty(&identity)?)?;
+        paths::restrict_file(&path)?;
+        created += 1;
+    }
+    eprintln!(
+        "Created {} of {} identities in {:.1}s, written to {}",
+        created,
+        count,
+        started.elapsed().as_secs_f64(),
+        dir.display()
+    );
+    Ok(created)
+}
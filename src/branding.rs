@@ -0,0 +1,243 @@
+use crate::dates;
+use crate::redact;
+use identity::core::{FromJson, Object, Value};
+use identity::credential::Credential;
+use serde::{Deserialize, Serialize};
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Paragraph, Wrap},
+};
+
+/// Property of the credential the display metadata is kept in.
+const DISPLAY: &str = "display";
+
+/// Issuer display metadata in the shape of the OpenID4VCI credential `display` object.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Display {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo: Option<Logo>,
+    /// Hex color like `#12107c`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_color: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Logo {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt_text: Option<String>,
+}
+
+pub fn example() -> Display {
+    Display {
+        name: "Example University".to_string(),
+        logo: Some(Logo {
+            url: "https://example.edu/logo.png".to_string(),
+            alt_text: Some("Example University crest".to_string()),
+        }),
+        background_color: Some("#12107c".to_string()),
+        text_color: Some("#ffffff".to_string()),
+    }
+}
+
+/// Adds the display metadata to the unsigned credential, so the proof covers it.
+pub fn attach(credential: &mut Credential, display: Option<&Display>) {
+    if let Some(value) = display.and_then(|display| serde_json::to_value(display).ok()) {
+        credential.properties.insert(DISPLAY.to_string(), value);
+    }
+}
+
+/// The display metadata the issuer attached, if any.
+pub fn of(credential: &Credential) -> Option<Display> {
+    credential
+        .properties
+        .get(DISPLAY)
+        .and_then(|value| Display::from_json_value(value.clone()).ok())
+}
+
+/// Parses `#rrggbb` into a terminal color.
+pub fn color(hex: &str) -> Option<Color> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn title(credential: &Credential) -> String {
+    credential.types.iter().last().cloned().unwrap_or_default()
+}
+
+/// Flattens the subject claims into `key: value` rows, nested objects with dotted keys.
+pub fn claims(credential: &Credential) -> Vec<(String, String)> {
+    fn flatten(prefix: &str, object: &Object, rows: &mut Vec<(String, String)>) {
+        for (key, value) in object.iter() {
+            let key = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                Value::Object(nested) => flatten(&key, &nested.clone().into_iter().collect(), rows),
+                Value::String(text) => rows.push((key, text.clone())),
+                other => rows.push((key, other.to_string())),
+            }
+        }
+    }
+    let mut rows = Vec::new();
+    for subject in credential.credential_subject.iter() {
+        flatten("", &subject.properties, &mut rows);
+    }
+    rows
+}
+
+/// The credential as a card in the colors of the issuer with the claim `rows` its detail renderer
+/// laid out, with masked claim values if `redacted`.
+pub fn card<'a>(
+    credential: &Credential,
+    rows: Vec<(String, String)>,
+    redacted: bool,
+) -> Paragraph<'a> {
+    let display = of(credential);
+    let background = display
+        .as_ref()
+        .and_then(|display| display.background_color.as_deref())
+        .and_then(color)
+        .unwrap_or(Color::Reset);
+    let foreground = display
+        .as_ref()
+        .and_then(|display| display.text_color.as_deref())
+        .and_then(color)
+        .unwrap_or(Color::White);
+    let style = Style::default().bg(background).fg(foreground);
+
+    let issuer = display
+        .as_ref()
+        .map(|display| display.name.clone())
+        .unwrap_or_else(|| credential.issuer.url().to_string());
+    let issuer = if redacted {
+        redact::dids(&issuer)
+    } else {
+        issuer
+    };
+    let mut lines = vec![
+        Spans::from(vec![Span::styled(
+            issuer,
+            style.add_modifier(Modifier::BOLD),
+        )]),
+        Spans::from(vec![Span::styled(title(credential), style)]),
+        Spans::from(vec![Span::raw("")]),
+    ];
+    let mut rows = rows;
+    rows.push((
+        "Issued".to_string(),
+        dates::with_relative(credential.issuance_date),
+    ));
+    if let Some(expires) = credential.expiration_date {
+        rows.push(("Expires".to_string(), dates::with_relative(expires)));
+    }
+    for (key, value) in rows {
+        let value = if redacted {
+            redact::MASK.to_string()
+        } else {
+            value
+        };
+        lines.push(Spans::from(vec![
+            Span::styled(format!("{}: ", key), style.add_modifier(Modifier::DIM)),
+            Span::styled(value, style),
+        ]));
+    }
+    if let Some(logo) = display.and_then(|display| display.logo) {
+        lines.push(Spans::from(vec![Span::raw("")]));
+        lines.push(Spans::from(vec![Span::styled(
+            format!("logo: {}", logo.alt_text.unwrap_or(logo.url)),
+            style.add_modifier(Modifier::ITALIC),
+        )]));
+    }
+
+    Paragraph::new(lines)
+        .style(style)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(style)
+                .title("Card")
+                .border_type(BorderType::Rounded),
+        )
+        .wrap(Wrap { trim: true })
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A printable HTML certificate of the credential styled like the card.
+pub fn certificate(credential: &Credential) -> String {
+    let display = of(credential);
+    let background = display
+        .as_ref()
+        .and_then(|display| display.background_color.clone())
+        .unwrap_or_else(|| "#ffffff".to_string());
+    let foreground = display
+        .as_ref()
+        .and_then(|display| display.text_color.clone())
+        .unwrap_or_else(|| "#000000".to_string());
+    let issuer = display
+        .as_ref()
+        .map(|display| display.name.clone())
+        .unwrap_or_else(|| credential.issuer.url().to_string());
+    let logo = display
+        .and_then(|display| display.logo)
+        .map(|logo| {
+            format!(
+                "<img src=\"{}\" alt=\"{}\" height=\"64\">",
+                escape(&logo.url),
+                escape(logo.alt_text.as_deref().unwrap_or_default())
+            )
+        })
+        .unwrap_or_default();
+    let rows: String = claims(credential)
+        .into_iter()
+        .map(|(key, value)| {
+            format!(
+                "<tr><th>{}</th><td>{}</td></tr>\n",
+                escape(&key),
+                escape(&value)
+            )
+        })
+        .collect();
+
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head><meta charset=\"utf-8\"><title>{title}</title></head>
+<body style=\"font-family: sans-serif\">
+<div style=\"background: {background}; color: {foreground}; border-radius: 12px; padding: 24px; max-width: 640px\">
+{logo}
+<h1>{issuer}</h1>
+<h2>{title}</h2>
+<table>
+{rows}</table>
+<p><small>Issued {issued} by {did}</small></p>
+</div>
+</body>
+</html>
+",
+        title = escape(&title(credential)),
+        background = escape(&background),
+        foreground = escape(&foreground),
+        logo = logo,
+        issuer = escape(&issuer),
+        rows = rows,
+        issued = dates::date(credential.issuance_date),
+        did = escape(credential.issuer.url().as_str()),
+    )
+}
@@ -0,0 +1,190 @@
+use crate::handle;
+use crate::history::VerificationHistory;
+use crate::issued::IssuanceLog;
+use crate::oob;
+use crate::wallet::Wallet;
+use identity::core::{FromJson, Timestamp, ToJson};
+use identity::iota::IotaDocument;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Layout version of the bundle, bumped when files are renamed or change meaning.
+const FORMAT: u32 = 1;
+const MANIFEST: &str = "manifest.json";
+const PRESENTATIONS: &str = "presentations/";
+
+/// Credential type, contexts and claims of a form, to start a checkpoint with the same input.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Template {
+    pub credential_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contexts: Option<String>,
+    pub claims: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Manifest {
+    pub format: u32,
+    pub created: Timestamp,
+    pub issuer: String,
+    pub holder: String,
+    /// SHA-256 of every other file in the bundle by name.
+    pub files: BTreeMap<String, String>,
+}
+
+/// Everything a tutorial checkpoint is made of. Private keys stay out of it,
+/// the DID documents only tell which identities the artifacts belong to.
+pub struct Scenario {
+    pub issuer: IotaDocument,
+    pub holder: IotaDocument,
+    /// The Issue form first, then the Apply form.
+    pub templates: Vec<Template>,
+    pub issued: IssuanceLog,
+    pub wallet: Wallet,
+    /// Presentations waiting in the inbox by file name.
+    pub presentations: BTreeMap<String, String>,
+    pub history: VerificationHistory,
+}
+
+impl Scenario {
+    fn files(&self) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+        let mut files = BTreeMap::new();
+        files.insert("issuer.json".to_string(), self.issuer.to_json_pretty()?);
+        files.insert("holder.json".to_string(), self.holder.to_json_pretty()?);
+        files.insert(
+            "templates.json".to_string(),
+            serde_json::to_string_pretty(&self.templates)?,
+        );
+        files.insert(
+            "issued.json".to_string(),
+            serde_json::to_string_pretty(&self.issued)?,
+        );
+        files.insert(
+            "wallet.json".to_string(),
+            serde_json::to_string_pretty(&self.wallet)?,
+        );
+        files.insert(
+            "verifications.json".to_string(),
+            serde_json::to_string_pretty(&self.history)?,
+        );
+        for (name, presentation) in &self.presentations {
+            files.insert(format!("{}{}", PRESENTATIONS, name), presentation.clone());
+        }
+        Ok(files)
+    }
+}
+
+/// Presentations delivered to the inbox and not checked yet.
+pub fn presentations(inbox: &str) -> BTreeMap<String, String> {
+    fs::read_dir(oob::responses_dir(inbox))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_str()?.to_string();
+                    Some((name, fs::read_to_string(entry.path()).ok()?))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Puts the presentations of a bundle back into the inbox.
+pub fn restore_presentations(
+    inbox: &str,
+    presentations: &BTreeMap<String, String>,
+) -> Result<(), Box<dyn Error>> {
+    let dir = oob::responses_dir(inbox);
+    fs::create_dir_all(&dir)?;
+    for (name, presentation) in presentations {
+        fs::write(dir.join(name), presentation)?;
+    }
+    Ok(())
+}
+
+/// Writes the scenario as `scenario-<unix time>.zip` into `dir`.
+pub fn export(dir: &str, scenario: &Scenario) -> Result<PathBuf, Box<dyn Error>> {
+    let files = scenario.files()?;
+    let created = Timestamp::now_utc();
+    let manifest = Manifest {
+        format: FORMAT,
+        created,
+        issuer: scenario.issuer.id().to_string(),
+        holder: scenario.holder.id().to_string(),
+        files: files
+            .iter()
+            .map(|(name, content)| (name.clone(), handle::hash(content)))
+            .collect(),
+    };
+
+    fs::create_dir_all(dir)?;
+    let path = Path::new(dir).join(format!("scenario-{}.zip", created.to_unix()));
+    let mut zip = ZipWriter::new(fs::File::create(&path)?);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(MANIFEST, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    for (name, content) in &files {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(path)
+}
+
+/// Reads a bundle, refusing it if a file is missing or does not match the manifest.
+pub fn import(path: &Path) -> Result<(Manifest, Scenario), Box<dyn Error>> {
+    let mut zip = ZipArchive::new(fs::File::open(path)?)?;
+    let mut read = |name: &str| -> Result<String, Box<dyn Error>> {
+        let mut content = String::new();
+        zip.by_name(name)
+            .map_err(|_| format!("the bundle has no {}", name))?
+            .read_to_string(&mut content)?;
+        Ok(content)
+    };
+
+    let manifest: Manifest = serde_json::from_str(&read(MANIFEST)?)?;
+    if manifest.format != FORMAT {
+        return Err(format!("bundle format {} is not supported", manifest.format).into());
+    }
+    let mut files: BTreeMap<String, String> = BTreeMap::new();
+    for (name, hash) in &manifest.files {
+        let content = read(name)?;
+        if &handle::hash(&content) != hash {
+            return Err(format!("{} does not match the manifest", name).into());
+        }
+        files.insert(name.clone(), content);
+    }
+    let file = |name: &str| -> Result<&String, Box<dyn Error>> {
+        files
+            .get(name)
+            .ok_or_else(|| format!("the manifest lists no {}", name).into())
+    };
+
+    let scenario = Scenario {
+        issuer: IotaDocument::from_json(file("issuer.json")?)?,
+        holder: IotaDocument::from_json(file("holder.json")?)?,
+        templates: serde_json::from_str(file("templates.json")?)?,
+        issued: serde_json::from_str(file("issued.json")?)?,
+        wallet: serde_json::from_str(file("wallet.json")?)?,
+        history: serde_json::from_str(file("verifications.json")?)?,
+        presentations: files
+            .iter()
+            .filter_map(|(name, content)| {
+                let name = name.strip_prefix(PRESENTATIONS)?;
+                // Only plain file names, a bundle must not write outside the inbox.
+                if name.is_empty() || name.contains('/') || name.contains('\\') || name == ".." {
+                    return None;
+                }
+                Some((name.to_string(), content.clone()))
+            })
+            .collect(),
+    };
+    Ok((manifest, scenario))
+}
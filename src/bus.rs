@@ -0,0 +1,78 @@
+use crate::actions::Action;
+use crate::core::OnNetwork;
+use crate::integrity::Report;
+use crate::receipts::Inclusion;
+use crate::status::Status;
+use crate::verification::Verified;
+use crossterm::event::KeyEvent;
+use identity::credential::Credential;
+use identity::iota::IotaDocument;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+/// Everything the render loop reacts to.
+pub enum Event {
+    Input(KeyEvent),
+    Tick,
+    Message(Message),
+    /// An action following up on another one, checked like one from a key press.
+    Action(Action),
+    /// A background resolution or verification finished, tagged with the number of its task.
+    Done(u64, Done),
+}
+
+/// Outcome of a resolution or verification run in the background.
+pub enum Done {
+    Verified(Result<OnNetwork<Verified>, String>),
+    Resolved(Result<OnNetwork<(IotaDocument, Report)>, String>),
+}
+
+/// Updates background tasks hand to the render loop, which applies them to the app state.
+#[derive(Clone, Debug)]
+pub enum Message {
+    Log(String),
+    Status {
+        id: String,
+        status: Status,
+    },
+    Inclusion {
+        message_id: String,
+        inclusion: Inclusion,
+    },
+    /// What a background task is doing, like its attempt counter, `None` once it is done.
+    Progress(Option<String>),
+    /// A credential the server renewed at `/refresh`, for the issuance log.
+    Renewed(Credential),
+}
+
+/// Sending half of the bus, cheap to clone into every task.
+#[derive(Clone)]
+pub struct Bus {
+    sender: UnboundedSender<Event>,
+}
+
+impl Bus {
+    /// The bus and the receiving half the render loop awaits next to the terminal input.
+    pub fn new() -> (Bus, UnboundedReceiver<Event>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (Bus { sender }, receiver)
+    }
+
+    pub fn publish(&self, message: Message) {
+        // The receiver only goes away when the app quits.
+        let _ = self.sender.send(Event::Message(message));
+    }
+
+    pub fn log(&self, message: impl Into<String>) {
+        self.publish(Message::Log(message.into()));
+    }
+
+    /// Runs the action once the render loop is done with the current one.
+    pub fn dispatch(&self, action: Action) {
+        let _ = self.sender.send(Event::Action(action));
+    }
+
+    /// Hands the outcome of the background task to the render loop.
+    pub fn finish(&self, task: u64, done: Done) {
+        let _ = self.sender.send(Event::Done(task, done));
+    }
+}
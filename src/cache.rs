@@ -0,0 +1,59 @@
+use crate::verification::Verified;
+use identity::core::Timestamp;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+struct Cached {
+    stored: Instant,
+    /// When the result was checked on the Tangle.
+    checked: Timestamp,
+    verified: Verified,
+}
+
+/// Verification results by canonical credential hash, kept for a short time so opening
+/// the same credential again does not resolve its issuer again.
+#[derive(Clone)]
+pub struct VerificationCache {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<String, Cached>>>,
+}
+
+impl VerificationCache {
+    /// A cache keeping results for `ttl` seconds, 0 disables it.
+    pub fn new(ttl: u64) -> Self {
+        VerificationCache {
+            ttl: Duration::from_secs(ttl),
+            entries: Default::default(),
+        }
+    }
+
+    /// The result for the hash if it is fresh, with `cached` set to when it was checked.
+    pub fn get(&self, hash: &str) -> Option<Verified> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, cached| cached.stored.elapsed() < self.ttl);
+        entries.get(hash).map(|cached| Verified {
+            cached: Some(cached.checked),
+            ..cached.verified.clone()
+        })
+    }
+
+    /// Forgets all results, once they could come out differently.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    pub fn insert(&self, hash: &str, verified: &Verified) {
+        if self.ttl.as_secs() == 0 {
+            return;
+        }
+        self.entries.lock().unwrap().insert(
+            hash.to_string(),
+            Cached {
+                stored: Instant::now(),
+                checked: Timestamp::now_utc(),
+                verified: verified.clone(),
+            },
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::handle;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Prefix of a reference standing in for an artifact in a record file.
+const PREFIX: &str = "sha256:";
+/// File listing the references of each record file, next to the artifacts.
+const REFS: &str = "refs.json";
+
+/// Where a record file holds artifacts.
+pub struct Field {
+    /// JSON pointer of the array of records, empty if the file is the array.
+    pub records: &'static str,
+    /// Field of a record holding the artifact, `None` if each record is one.
+    pub field: Option<&'static str>,
+    /// Whether the artifact is kept as text, like the JSON of a published document.
+    pub text: bool,
+}
+
+/// The credentials of the wallet entries.
+pub const WALLET: [Field; 1] = [Field {
+    records: "/entries",
+    field: Some("credential"),
+    text: false,
+}];
+/// The documents of the publish receipts.
+pub const RECEIPTS: [Field; 1] = [Field {
+    records: "",
+    field: Some("document"),
+    text: true,
+}];
+/// The verification reports.
+pub const HISTORY: [Field; 1] = [Field {
+    records: "/reports",
+    field: None,
+    text: false,
+}];
+
+/// Artifacts stored once under the SHA-256 of their content. An artifact is counted once for
+/// every reference in a record file and removed when no record file references it anymore.
+pub struct Cas {
+    dir: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl Cas {
+    pub fn new(dir: PathBuf) -> Self {
+        Cas {
+            dir,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// The artifact a reference stands for, checked against its hash.
+    pub fn get(&self, reference: &str) -> Result<String, Box<dyn Error>> {
+        let hash = reference
+            .strip_prefix(PREFIX)
+            .ok_or_else(|| format!("{} is no artifact reference", reference))?;
+        let content = fs::read_to_string(self.dir.join(hash))?;
+        if handle::hash(&content) != hash {
+            return Err(format!("the artifact {} was altered", hash).into());
+        }
+        Ok(content)
+    }
+
+    /// Reads a record file and puts the artifacts back in place of their references.
+    /// Files written before the store still hold the artifacts and are read as they are.
+    pub fn read<T: DeserializeOwned>(&self, path: &Path, fields: &[Field]) -> Option<T> {
+        let mut value: Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+        for field in fields {
+            for slot in slots(&mut value, field) {
+                let reference = match slot.as_str().filter(|s| s.starts_with(PREFIX)) {
+                    Some(reference) => reference.to_string(),
+                    None => continue,
+                };
+                let content = self.get(&reference).ok()?;
+                *slot = if field.text {
+                    Value::String(content)
+                } else {
+                    serde_json::from_str(&content).ok()?
+                };
+            }
+        }
+        serde_json::from_value(value).ok()
+    }
+
+    /// Stores the artifacts of the records, writes the record file with their references and
+    /// removes the artifacts no record file references anymore.
+    pub fn write<T: Serialize>(
+        &self,
+        path: &Path,
+        records: &T,
+        fields: &[Field],
+    ) -> Result<(), Box<dyn Error>> {
+        let _lock = self.lock.lock().unwrap();
+        fs::create_dir_all(&self.dir)?;
+        let mut value: Value = serde_json::to_value(records)?;
+        let mut references: Vec<String> = Vec::new();
+        for field in fields {
+            for slot in slots(&mut value, field) {
+                let content: String = match &*slot {
+                    Value::String(text) if field.text => text.clone(),
+                    artifact => serde_json::to_string(artifact)?,
+                };
+                let hash: String = handle::hash(&content);
+                let artifact = self.dir.join(&hash);
+                if !artifact.exists() {
+                    fs::write(&artifact, &content)?;
+                }
+                let reference = format!("{}{}", PREFIX, hash);
+                *slot = Value::String(reference.clone());
+                references.push(reference);
+            }
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&value)?)?;
+        self.collect(path, references)
+    }
+
+    /// Records the references of the file and removes artifacts without any reference.
+    fn collect(&self, path: &Path, references: Vec<String>) -> Result<(), Box<dyn Error>> {
+        let refs_path = self.dir.join(REFS);
+        let mut refs: BTreeMap<String, Vec<String>> = fs::read_to_string(&refs_path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        refs.insert(path.display().to_string(), references);
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for reference in refs.values().flatten() {
+            *counts.entry(&reference[PREFIX.len()..]).or_default() += 1;
+        }
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name != REFS && !counts.contains_key(name.as_str()) {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        fs::write(refs_path, serde_json::to_string_pretty(&refs)?)?;
+        Ok(())
+    }
+}
+
+/// The values of the field in every record, skipping records without it.
+fn slots<'a>(value: &'a mut Value, field: &Field) -> Vec<&'a mut Value> {
+    let records = match value.pointer_mut(field.records) {
+        Some(Value::Array(records)) => records,
+        _ => return Vec::new(),
+    };
+    records
+        .iter_mut()
+        .filter_map(|record| match field.field {
+            Some(name) => record.get_mut(name),
+            None => Some(record),
+        })
+        .filter(|slot| !slot.is_null())
+        .collect()
+}
@@ -0,0 +1,287 @@
+use crate::templates::{self, TypeTemplate};
+use crossterm::event::{KeyCode, KeyEvent};
+use identity::core::{Object, Timestamp, Value};
+use std::collections::BTreeMap;
+use tui::{
+    style::{Color, Style},
+    text::{Span, Spans},
+};
+
+/// Parses typed claims written as `key:type=value` pairs separated by commas.
+///
+/// Supported types are `string` (the default), `number`, `bool`, `date` (`YYYY-MM-DD` or RFC 3339)
+/// and `object` (a JSON object). Dotted keys like `degree.name` build nested objects.
+pub fn parse(input: &str) -> Result<Object, String> {
+    let mut claims = Object::new();
+    for pair in input.split(',').filter(|pair| !pair.trim().is_empty()) {
+        let mut parts = pair.splitn(2, '=');
+        let key = parts.next().unwrap_or_default().trim();
+        let raw = parts
+            .next()
+            .ok_or_else(|| format!("claim `{}` has no value", key))?
+            .trim();
+
+        let (path, type_) = match key.split_once(':') {
+            Some((path, type_)) => (path.trim(), type_.trim()),
+            None => (key, "string"),
+        };
+        if path.is_empty() {
+            return Err(format!("claim `{}` has no name", pair.trim()));
+        }
+        let value = parse_value(path, type_, raw)?;
+        insert(&mut claims, path, value)?;
+    }
+    Ok(claims)
+}
+
+fn parse_value(path: &str, type_: &str, raw: &str) -> Result<Value, String> {
+    match type_ {
+        "string" => Ok(Value::String(raw.to_string())),
+        "number" => raw
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| raw.parse::<f64>().map(Value::from))
+            .map_err(|_| format!("`{}` is not a number: {}", path, raw)),
+        "bool" => raw
+            .parse::<bool>()
+            .map(Value::Bool)
+            .map_err(|_| format!("`{}` is not true or false: {}", path, raw)),
+        "date" => {
+            let timestamp = if raw.len() == 10 {
+                format!("{}T00:00:00Z", raw)
+            } else {
+                raw.to_string()
+            };
+            Timestamp::parse(&timestamp)
+                .map(|timestamp| Value::String(timestamp.to_string()))
+                .map_err(|_| format!("`{}` is not a date: {}", path, raw))
+        }
+        "object" => match serde_json::from_str(raw) {
+            Ok(Value::Object(object)) => Ok(Value::Object(object)),
+            _ => Err(format!("`{}` is not a JSON object: {}", path, raw)),
+        },
+        other => Err(format!("`{}` has the unknown type {}", path, other)),
+    }
+}
+
+fn insert(claims: &mut Object, path: &str, value: Value) -> Result<(), String> {
+    match path.split_once('.') {
+        None => {
+            claims.insert(path.to_string(), value);
+            Ok(())
+        }
+        Some((head, rest)) => {
+            let entry = claims
+                .entry(head.to_string())
+                .or_insert_with(|| Value::Object(Default::default()));
+            match entry {
+                Value::Object(nested) => {
+                    let mut object: Object = nested.clone().into_iter().collect();
+                    insert(&mut object, rest, value)?;
+                    *nested = object.into_iter().collect();
+                    Ok(())
+                }
+                _ => Err(format!("`{}` is not an object", head)),
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Type,
+    Contexts,
+    Claims,
+}
+
+impl Default for Field {
+    fn default() -> Self {
+        Field::Type
+    }
+}
+
+/// A credential type and typed claims edited in place, the type completed from the registry.
+#[derive(Default)]
+pub struct ClaimsForm {
+    pub editing: bool,
+    pub credential_type: String,
+    /// Comma separated contexts, `None` if the form does not ask for them.
+    pub contexts: Option<String>,
+    pub claims: String,
+    pub error: Option<String>,
+    types: BTreeMap<String, TypeTemplate>,
+    focused: Field,
+}
+
+impl ClaimsForm {
+    pub fn new(credential_type: &str, claims: &str) -> Self {
+        ClaimsForm {
+            credential_type: credential_type.to_string(),
+            claims: claims.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Suggests types from the registry while typing the credential type.
+    pub fn with_types(mut self, types: &BTreeMap<String, TypeTemplate>) -> Self {
+        self.types = types.clone();
+        self
+    }
+
+    /// Asks for the contexts too and checks them against the registry on submit.
+    pub fn with_contexts(mut self, contexts: &str) -> Self {
+        self.contexts = Some(contexts.to_string());
+        self
+    }
+
+    /// The contexts given in the form.
+    pub fn context_list(&self) -> Vec<String> {
+        self.contexts
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|context| !context.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn suggestions(&self) -> Vec<&str> {
+        if self.focused != Field::Type {
+            return Vec::new();
+        }
+        templates::suggest(&self.types, &self.credential_type)
+    }
+
+    /// Takes the first suggested type, adds its missing contexts and, if there are no claims
+    /// yet, its required claims with empty values.
+    fn complete(&mut self) {
+        let name = match self.suggestions().first() {
+            Some(name) => name.to_string(),
+            None => return,
+        };
+        let template = self.types[&name].clone();
+        self.credential_type = name;
+        let present = self.context_list();
+        if let Some(contexts) = self.contexts.as_mut() {
+            for context in template
+                .contexts
+                .iter()
+                .filter(|context| !present.contains(*context))
+            {
+                if !contexts.trim().is_empty() {
+                    contexts.push_str(", ");
+                }
+                contexts.push_str(context);
+            }
+        }
+        if self.claims.trim().is_empty() {
+            self.claims = template.skeleton();
+        }
+    }
+
+    fn next_field(&self) -> Field {
+        match self.focused {
+            Field::Type if self.contexts.is_some() => Field::Contexts,
+            Field::Type | Field::Contexts => Field::Claims,
+            Field::Claims => Field::Type,
+        }
+    }
+
+    /// Checks the claims, and with contexts the registry requirements of the type.
+    fn submit(&self) -> Result<Object, String> {
+        let claims = parse(&self.claims)?;
+        if self.contexts.is_some() {
+            templates::check(
+                &self.types,
+                &self.credential_type,
+                &self.context_list(),
+                &claims,
+            )?;
+        }
+        Ok(claims)
+    }
+
+    /// Handles a key while editing, returns the parsed claims when the form is submitted.
+    /// Invalid claims or missing contexts keep the form open and show the error.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Object> {
+        let field = match self.focused {
+            Field::Type => &mut self.credential_type,
+            Field::Contexts => self.contexts.get_or_insert_with(String::new),
+            Field::Claims => &mut self.claims,
+        };
+        match key.code {
+            KeyCode::Esc => self.editing = false,
+            KeyCode::Tab => self.focused = self.next_field(),
+            KeyCode::Right => self.complete(),
+            KeyCode::Backspace => {
+                field.pop();
+            }
+            KeyCode::Char(c) => field.push(c),
+            KeyCode::Enter => match self.submit() {
+                Ok(claims) => {
+                    self.error = None;
+                    self.editing = false;
+                    return Some(claims);
+                }
+                Err(error) => self.error = Some(error),
+            },
+            _ => {}
+        }
+        None
+    }
+
+    pub fn lines<'a>(&self, submit: &str) -> Vec<Spans<'a>> {
+        let field_style = |focused: bool| {
+            if self.editing && focused {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::White)
+            }
+        };
+        let hint = if self.editing {
+            format!("Tab switches fields, Enter {}, Esc cancels.", submit)
+        } else {
+            "Press e to edit.".to_string()
+        };
+
+        let mut lines = vec![Spans::from(vec![
+            Span::raw("Credential type: "),
+            Span::styled(
+                self.credential_type.clone(),
+                field_style(self.focused == Field::Type),
+            ),
+        ])];
+        let suggestions = self.suggestions();
+        if self.editing && !suggestions.is_empty() {
+            lines.push(Spans::from(vec![Span::styled(
+                format!("  {} (→ completes)", suggestions.join(", ")),
+                Style::default().fg(Color::DarkGray),
+            )]));
+        }
+        if let Some(contexts) = &self.contexts {
+            lines.push(Spans::from(vec![
+                Span::raw("Contexts: "),
+                Span::styled(
+                    contexts.clone(),
+                    field_style(self.focused == Field::Contexts),
+                ),
+            ]));
+        }
+        lines.push(Spans::from(vec![
+            Span::raw("Claims (key[:string|number|bool|date|object]=value, ...): "),
+            Span::styled(
+                self.claims.clone(),
+                field_style(self.focused == Field::Claims),
+            ),
+        ]));
+        lines.push(Spans::from(vec![Span::raw(hint)]));
+        if let Some(error) = &self.error {
+            lines.push(Spans::from(vec![Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red),
+            )]));
+        }
+        lines
+    }
+}
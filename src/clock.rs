@@ -0,0 +1,114 @@
+use identity::core::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Seconds the app's "now" is ahead of (or behind) the real time.
+static OFFSET: AtomicI64 = AtomicI64::new(0);
+
+const UNITS: [(char, i64); 4] = [('d', 86_400), ('h', 3_600), ('m', 60), ('s', 1)];
+
+/// Parses offsets like `+30d`, `-2h` or `90m`, a number alone counts seconds and
+/// an empty offset resets the clock.
+pub fn parse_offset(offset: &str) -> Result<i64, String> {
+    let offset = offset.trim();
+    if offset.is_empty() {
+        return Ok(0);
+    }
+    let (number, unit) = match offset.chars().last() {
+        Some(last) if last.is_ascii_alphabetic() => (&offset[..offset.len() - 1], last),
+        _ => (offset, 's'),
+    };
+    let seconds = UNITS
+        .iter()
+        .find(|(name, _)| *name == unit)
+        .map(|(_, seconds)| *seconds)
+        .ok_or_else(|| format!("unknown unit {} in {}, use d, h, m or s", unit, offset))?;
+    number
+        .trim_start_matches('+')
+        .parse::<i64>()
+        .map(|number| number * seconds)
+        .map_err(|_| format!("{} is no offset like +30d or -2h", offset))
+}
+
+pub fn set_offset(seconds: i64) {
+    OFFSET.store(seconds, Ordering::SeqCst);
+}
+
+pub fn offset() -> i64 {
+    OFFSET.load(Ordering::SeqCst)
+}
+
+/// The time the app works with, the real time shifted by the simulated offset.
+pub fn now() -> Timestamp {
+    Timestamp::from_unix(Timestamp::now_utc().to_unix() + offset())
+}
+
+/// `+30d 4h` style description of the offset, `None` while the clock is real.
+pub fn label() -> Option<String> {
+    let offset = offset();
+    if offset == 0 {
+        return None;
+    }
+    let mut rest = offset.abs();
+    let mut parts = Vec::new();
+    for (unit, seconds) in UNITS.iter() {
+        if rest >= *seconds {
+            parts.push(format!("{}{}", rest / seconds, unit));
+            rest %= seconds;
+        }
+    }
+    let sign = if offset > 0 { '+' } else { '-' };
+    Some(format!("{}{}", sign, parts.join(" ")))
+}
+
+/// How far the creation time of a proof may lie from the app's now, so drifting clocks of
+/// issuer and verifier do not fail verification.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Skew {
+    /// Seconds a proof may be older than now, 0 accepts proofs of any age.
+    pub past: u64,
+    /// Seconds a proof may be created ahead of now, for issuers whose clock runs fast.
+    pub future: u64,
+}
+
+impl Default for Skew {
+    fn default() -> Self {
+        Skew {
+            past: 0,
+            future: 300,
+        }
+    }
+}
+
+impl Skew {
+    /// `Ok(None)` if the proof was created before now, a warning if it lies ahead within the
+    /// tolerance and an error if it is outside the bounds.
+    pub fn check(&self, created: Timestamp) -> Result<Option<String>, String> {
+        let ahead = created.to_unix() - now().to_unix();
+        if ahead > self.future as i64 {
+            return Err(format!(
+                "the proof was created {}s ahead of the verifier clock, {}s are tolerated",
+                ahead, self.future
+            ));
+        }
+        if self.past > 0 && -ahead > self.past as i64 {
+            return Err(format!(
+                "the proof was created {}s ago, at most {}s are accepted",
+                -ahead, self.past
+            ));
+        }
+        if ahead > 0 {
+            return Ok(Some(format!(
+                "The proof was created {}s ahead of the verifier clock, within the tolerated skew",
+                ahead
+            )));
+        }
+        Ok(None)
+    }
+
+    /// Latest creation time the tolerance accepts.
+    pub fn latest(&self) -> Timestamp {
+        Timestamp::from_unix(now().to_unix() + self.future as i64)
+    }
+}
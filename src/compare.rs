@@ -0,0 +1,148 @@
+use crate::handle;
+use crate::integrity::{self, Integrity, Report};
+use crate::nodes;
+use futures::future::join_all;
+use identity::core::ToJson;
+use identity::iota::{IotaDID, IotaDocument, TangleRef};
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+
+/// What one node resolved the DID to.
+#[derive(Clone, Debug)]
+pub struct Resolved {
+    /// Integration message of the latest document.
+    pub message_id: String,
+    pub versions: usize,
+    pub integrity: Integrity,
+    /// Hash of the latest document, telling documents apart that claim the same message.
+    pub hash: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct NodeResult {
+    pub url: String,
+    pub resolved: Result<Resolved, String>,
+    pub elapsed: Duration,
+}
+
+async fn resolve_on(url: &str, did: &IotaDID) -> Result<Resolved, String> {
+    let client = nodes::single(url)
+        .await
+        .map_err(|error| error.to_string())?;
+    let (document, report): (IotaDocument, Report) = integrity::resolve(&client, did)
+        .await
+        .map_err(|error| error.to_string())?;
+    Ok(Resolved {
+        message_id: document.message_id().to_string(),
+        versions: report.versions.len(),
+        integrity: report.integrity,
+        hash: handle::digest(&document.to_json().map_err(|error| error.to_string())?),
+    })
+}
+
+/// Resolves the DID from every node at once, each with its own client and `budget`.
+pub async fn resolve_all(urls: &[String], did: &IotaDID, budget: Duration) -> Vec<NodeResult> {
+    join_all(urls.iter().map(|url| async move {
+        let started = Instant::now();
+        let resolved = match timeout(budget, resolve_on(url, did)).await {
+            Ok(resolved) => resolved,
+            Err(_) => Err(format!("no answer within {}s", budget.as_secs())),
+        };
+        NodeResult {
+            url: url.clone(),
+            resolved,
+            elapsed: started.elapsed(),
+        }
+    }))
+    .await
+}
+
+/// Where the nodes disagree, empty if all of them resolved the same document.
+/// The node with the most versions is taken as the synced one.
+pub fn divergence(results: &[NodeResult]) -> Vec<String> {
+    let resolved: Vec<(&str, &Resolved)> = results
+        .iter()
+        .filter_map(|result| Some((result.url.as_str(), result.resolved.as_ref().ok()?)))
+        .collect();
+    let mut findings: Vec<String> = results
+        .iter()
+        .filter_map(|result| match &result.resolved {
+            Err(error) if !resolved.is_empty() => Some(format!(
+                "{} fails while other nodes resolve: {}",
+                result.url, error
+            )),
+            _ => None,
+        })
+        .collect();
+    let (_, latest) = match resolved
+        .iter()
+        .max_by_key(|(_, resolved)| resolved.versions)
+    {
+        Some(latest) => *latest,
+        None => return findings,
+    };
+    for (url, resolved) in &resolved {
+        if resolved.versions < latest.versions {
+            findings.push(format!(
+                "{} is at version {} of {} (message {}), probably not synced",
+                url, resolved.versions, latest.versions, resolved.message_id
+            ));
+        } else if resolved.message_id != latest.message_id {
+            findings.push(format!(
+                "{} resolves message {} instead of {}, the chain forked between the nodes",
+                url, resolved.message_id, latest.message_id
+            ));
+        } else if resolved.hash != latest.hash {
+            findings.push(format!(
+                "{} returns another document for message {}",
+                url, resolved.message_id
+            ));
+        }
+        if resolved.integrity != latest.integrity {
+            findings.push(format!(
+                "{} finds the document {} instead of {}",
+                url,
+                resolved.integrity.badge().0,
+                latest.integrity.badge().0
+            ));
+        }
+    }
+    findings
+}
+
+/// One line per node and the divergence found, for the log, the TUI and the command line.
+pub fn summary(did: &IotaDID, results: &[NodeResult]) -> String {
+    let mut lines: Vec<String> = vec![format!("Resolving {} on {} nodes", did, results.len())];
+    for result in results {
+        lines.push(match &result.resolved {
+            Ok(resolved) => format!(
+                "{} ({} ms): {} versions, latest message {}, {}",
+                result.url,
+                result.elapsed.as_millis(),
+                resolved.versions,
+                resolved.message_id,
+                resolved.integrity.badge().0
+            ),
+            Err(error) => format!(
+                "{} ({} ms): failed, {}",
+                result.url,
+                result.elapsed.as_millis(),
+                error
+            ),
+        });
+    }
+    lines.push(String::new());
+    let findings: Vec<String> = divergence(results);
+    if results.iter().all(|result| result.resolved.is_err()) {
+        lines.push("No node resolved the DID".to_string());
+    } else if findings.is_empty() {
+        lines.push("All nodes agree".to_string());
+    } else {
+        lines.extend(
+            findings
+                .into_iter()
+                .map(|finding| format!("Diverges: {}", finding)),
+        );
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,182 @@
+use crate::backup;
+use crate::clock::Skew;
+use crate::config::Config;
+use crate::core::Service;
+use crate::did;
+use crate::ids;
+use crate::issue;
+use crate::ledger::Ledger;
+use crate::nodes;
+use crate::proof;
+use crate::quickstart::{artifact, note, step};
+use crate::seed;
+use crate::verification;
+use identity::core::{Timestamp, Url};
+use identity::credential::Credential;
+use identity::iota::{Client, Receipt};
+use identity::prelude::*;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Walks through the compromise of the issuer signing key without the TUI: rotating the key
+/// revokes everything it signed, before and after the cutoff, and the holder gets a new credential.
+/// Returns whether every verifier decision came out as expected.
+pub async fn run(config: &Config, seed: Option<&str>) -> std::result::Result<bool, Box<dyn Error>> {
+    let service = Service::new(config, nodes::from_urls(&config.nodes));
+    let client: Client = service.client().await?;
+
+    step(1, "Create the issuer with a signing key for credentials");
+    let (mut issuer_doc, issuer_key, _): (IotaDocument, KeyPair, Receipt) = service
+        .create_identity(seed::keypair(seed, "compromise-issuer")?)
+        .await?;
+    let old_key: KeyPair = seed::keypair(seed, "compromise-signing-1")?;
+    let receipt: Receipt = did::add_method(
+        &client,
+        &mut issuer_doc,
+        &issuer_key,
+        "signing-1",
+        old_key.public().as_ref(),
+    )
+    .await?;
+    note(&format!(
+        "Issuer {} signs credentials with #signing-1, published in {}",
+        issuer_doc.id(),
+        receipt.message_url()?
+    ));
+    let old_method: String = issuer_doc.id().join("#signing-1")?.to_string();
+
+    step(
+        2,
+        "Create the holder and issue a credential with #signing-1",
+    );
+    let (holder_doc, _, _): (IotaDocument, KeyPair, Receipt) = service
+        .create_identity(seed::keypair(seed, "compromise-holder")?)
+        .await?;
+    let credential: Credential = issue::issue_enrollment(&issuer_doc, &holder_doc)?;
+    let issued: String = proof::sign_jws(&issuer_doc, &old_method, &old_key, &credential)?;
+    artifact("Credential signed with #signing-1", &issued);
+    let mut expected: bool = check(
+        &client,
+        config.verification_timeout,
+        &config.proof_skew,
+        "The holder credential",
+        &issued,
+        true,
+    )
+    .await?;
+
+    step(
+        3,
+        "The key of #signing-1 leaks and the attacker signs with it",
+    );
+    let mut forged: Credential = issue::issue_enrollment(&issuer_doc, &holder_doc)?;
+    forged.id = Some(Url::parse(ids::uuid())?);
+    let forged: String = proof::sign_jws(&issuer_doc, &old_method, &old_key, &forged)?;
+    artifact("Forged credential", &forged);
+    expected &= check(
+        &client,
+        config.verification_timeout,
+        &config.proof_skew,
+        "The forged credential",
+        &forged,
+        true,
+    )
+    .await?;
+    note("Verifiers can't tell it from a real one as long as #signing-1 is in the document");
+
+    step(4, "Rotate: replace #signing-1 with #signing-2");
+    backup::create(config)?;
+    let new_key: KeyPair = seed::keypair(seed, "compromise-signing-2")?;
+    let cutoff: Timestamp = Timestamp::now_utc();
+    let receipt: Receipt = did::rotate_method(
+        &client,
+        &mut issuer_doc,
+        &issuer_key,
+        "signing-1",
+        "signing-2",
+        new_key.public().as_ref(),
+    )
+    .await?;
+    note(&format!(
+        "Rotated at {} in {}, the authentication key stays with the issuer",
+        cutoff,
+        receipt.message_url()?
+    ));
+
+    step(5, "Verifiers reject everything #signing-1 signed");
+    expected &= check(
+        &client,
+        config.verification_timeout,
+        &config.proof_skew,
+        "The holder credential",
+        &issued,
+        false,
+    )
+    .await?;
+    expected &= check(
+        &client,
+        config.verification_timeout,
+        &config.proof_skew,
+        "The forged credential",
+        &forged,
+        false,
+    )
+    .await?;
+    let late: String = proof::sign_jws(&issuer_doc, &old_method, &old_key, &credential)?;
+    expected &= check(
+        &client,
+        config.verification_timeout,
+        &config.proof_skew,
+        "A credential signed after the cutoff",
+        &late,
+        false,
+    )
+    .await?;
+
+    step(6, "Re-issue the holder credential with #signing-2");
+    let new_method: String = issuer_doc.id().join("#signing-2")?.to_string();
+    let reissued: String = proof::sign_jws(&issuer_doc, &new_method, &new_key, &credential)?;
+    artifact("Credential signed with #signing-2", &reissued);
+    expected &= check(
+        &client,
+        config.verification_timeout,
+        &config.proof_skew,
+        "The re-issued credential",
+        &reissued,
+        true,
+    )
+    .await?;
+
+    note(&format!(
+        "Every verifier decision came out as expected: {}",
+        expected
+    ));
+    Ok(expected)
+}
+
+/// Verifies the credential as a verifier would, within `budget` seconds, and reports whether
+/// the outcome is `expected`.
+async fn check(
+    client: &Client,
+    budget: u64,
+    skew: &Skew,
+    name: &str,
+    input: &str,
+    expected: bool,
+) -> std::result::Result<bool, Box<dyn Error>> {
+    let deadline = Instant::now() + Duration::from_secs(budget);
+    let ledger = Ledger::Tangle(client.clone());
+    let verified = verification::verify(&ledger, input, deadline, skew, &BTreeMap::new()).await?;
+    match &verified.error {
+        None => note(&format!("{} verifies", name)),
+        Some(error) => note(&format!(
+            "{} is rejected: {} ({})",
+            name,
+            error,
+            error.code()
+        )),
+    }
+    Ok(verified.verified == expected)
+}
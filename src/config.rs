@@ -0,0 +1,287 @@
+use crate::branding::{self, Display};
+use crate::clock::Skew;
+use crate::hooks;
+use crate::ids::IdStrategy;
+use crate::paths;
+use crate::profile::{self, Profile};
+use crate::proof::ProofFormat;
+use crate::sharing;
+use crate::templates::{self, TypeTemplate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+pub const CONFIG_FILE: &str = "config.json";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Tangle nodes the client may use.
+    pub nodes: Vec<String>,
+    /// Seconds between two node health checks, at least 1.
+    pub node_poll_interval: u64,
+    /// Seconds after which an unconfirmed DID message is offered for republishing.
+    pub confirm_timeout: u64,
+    /// Show the payload of every Tangle write and publish it only once it is confirmed.
+    pub confirm_tangle_writes: bool,
+    /// Resolve DIDs of another network on that network instead of only reporting the mismatch.
+    pub auto_switch_network: bool,
+    /// Backend identities, wallet and receipts are persisted with.
+    pub storage: Storage,
+    /// Where the key signing issued credentials lives.
+    pub signer: SignerKind,
+    /// Directory the file backend and the publish receipts live in.
+    pub data_dir: String,
+    /// Stronghold snapshot holding the issuer identity.
+    pub snapshot_path: String,
+    /// Directory the snapshot is backed up to before identity changes.
+    pub backup_dir: String,
+    /// Number of snapshot backups to keep.
+    pub backup_retention: usize,
+    /// Proof representation used when signing with a key pair.
+    pub proof_format: ProofFormat,
+    /// QR error correction level, one of L, M, Q or H.
+    pub qr_ec_level: String,
+    /// Start in presentation mode, masking claim values and shortening DIDs on screen.
+    pub presentation_mode: bool,
+    /// The holder counter-signs an acceptance of every credential it stores in the wallet.
+    pub subject_consent: bool,
+    /// Credentials only verify with a valid acceptance signed by their subject.
+    pub verify_consent: bool,
+    /// Credential types the holder presents to listed verifiers without marking them each time.
+    pub auto_present: Vec<sharing::Rule>,
+    /// Issuer display metadata attached to issued credentials, `null` leaves it out.
+    pub branding: Option<Display>,
+    /// Claim hooks run before signing, by credential type.
+    pub claim_hooks: BTreeMap<String, Vec<String>>,
+    /// Known credential types with the contexts and claims they require.
+    pub credential_types: BTreeMap<String, TypeTemplate>,
+    /// How ids of issued credentials are made unless `credential_ids` names the type.
+    pub credential_id: IdStrategy,
+    /// Id strategy by credential type.
+    pub credential_ids: BTreeMap<String, IdStrategy>,
+    /// Days credentials issued from the forms stay valid, 0 issues them without expiration.
+    pub validity_days: u64,
+    /// Debug setting shifting the app's "now", like `+30d` or `-2h`, to demo expiration.
+    pub clock_offset: String,
+    /// Locale dates are shown in, like `en-US`, `en-GB`, `de-DE` or `fr-FR`, ISO dates otherwise.
+    pub locale: String,
+    /// Timezone dates are shown in, `UTC` or an offset like `+02:00`.
+    pub timezone: String,
+    /// Issue from the form to a pairwise pseudonym of the holder instead of its DID.
+    pub pseudonymous_subjects: bool,
+    /// File the pseudonyms are mapped to the real holders in, kept locally.
+    pub pseudonyms_path: String,
+    /// Expert mode, the unsigned credential JSON can be edited in the preview before signing.
+    pub expert_mode: bool,
+    /// Where issued credentials are published so the QR only carries a handle.
+    pub credential_store: CredentialStore,
+    /// Directory the HTTP store serves credentials from.
+    pub store_dir: String,
+    /// Address the HTTP store listens on.
+    pub store_bind: String,
+    /// Public base URL of the HTTP store.
+    pub store_url: String,
+    /// Issue credentials with a `refreshService` at `<store_url>/refresh`, needs the HTTP store.
+    pub refresh_service: bool,
+    /// Directory messages between holder and issuer are dropped into.
+    pub inbox_dir: String,
+    /// File the holder wallet is persisted in.
+    pub wallet_path: String,
+    /// File the paired devices and their channel keys are kept in.
+    pub peers_path: String,
+    /// Reopen the tab, holder and selections of the last run on start.
+    pub restore_session: bool,
+    /// File the on-screen state is kept in between runs.
+    pub session_path: String,
+    /// Directory wallet exports are written to.
+    pub export_dir: String,
+    /// Seconds between two status checks of the wallet credentials.
+    pub status_poll_interval: u64,
+    /// Seconds a verification result is reused for the same credential, 0 disables the cache.
+    pub verification_cache_ttl: u64,
+    /// Seconds a verification may take, checks still running by then are reported as unknown.
+    pub verification_timeout: u64,
+    /// Attempts at a resolution or verification whose nodes or issuer can't be reached.
+    pub retry_attempts: u32,
+    /// Milliseconds before the second attempt, doubling before each further one.
+    pub retry_backoff_ms: u64,
+    /// Issuer document versions to verify against instead of the latest, integration message id by DID.
+    pub pinned_versions: BTreeMap<String, String>,
+    /// Dates from which verification methods should no longer sign, `YYYY-MM-DD` by method id.
+    pub rotation_due: BTreeMap<String, String>,
+    /// Tolerated distance of a proof creation time from now, in seconds before and after.
+    pub proof_skew: Skew,
+    /// Hash of the PIN locking wallet and signing screens, printed by `--hash-pin <pin>`.
+    pub pin_hash: Option<String>,
+    /// Seconds of inactivity after which the screens lock again.
+    pub lock_after: u64,
+    /// Seconds an unlocked session lasts however active it is, 0 for no limit.
+    pub session_timeout: u64,
+    /// Lock the screens with the Stronghold password if no `pin_hash` is configured.
+    pub lock_with_password: bool,
+    /// Profiles sharing this machine and their capabilities.
+    pub profiles: Vec<Profile>,
+    /// Profile used unless `--profile <name>` is given.
+    pub profile: String,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Storage {
+    Stronghold,
+    File,
+    /// Nothing leaves memory, used by `--ephemeral`.
+    Memory,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignerKind {
+    Stronghold,
+    SoftHsm,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStore {
+    Off,
+    Http,
+    Tangle,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            nodes: vec![
+                "https://chrysalis-nodes.iota.org".to_string(),
+                "https://chrysalis-nodes.iota.cafe".to_string(),
+            ],
+            node_poll_interval: 10,
+            confirm_timeout: 120,
+            confirm_tangle_writes: true,
+            auto_switch_network: false,
+            storage: Storage::Stronghold,
+            signer: SignerKind::Stronghold,
+            data_dir: paths::default_path("data"),
+            snapshot_path: paths::default_path("example-strong.hodl"),
+            backup_dir: paths::default_path("backups"),
+            backup_retention: 5,
+            proof_format: ProofFormat::Embedded,
+            qr_ec_level: "M".to_string(),
+            presentation_mode: false,
+            subject_consent: false,
+            verify_consent: false,
+            auto_present: Vec::new(),
+            branding: Some(branding::example()),
+            claim_hooks: hooks::defaults(),
+            credential_types: templates::defaults(),
+            credential_id: IdStrategy::default(),
+            credential_ids: BTreeMap::new(),
+            validity_days: 0,
+            clock_offset: String::new(),
+            locale: "iso".to_string(),
+            timezone: "UTC".to_string(),
+            pseudonymous_subjects: false,
+            pseudonyms_path: paths::default_path("pseudonyms.json"),
+            expert_mode: false,
+            credential_store: CredentialStore::Off,
+            store_dir: "store".to_string(),
+            store_bind: "127.0.0.1:8080".to_string(),
+            store_url: "http://127.0.0.1:8080".to_string(),
+            refresh_service: false,
+            inbox_dir: "inbox".to_string(),
+            wallet_path: "wallet.json".to_string(),
+            peers_path: paths::default_path("peers.json"),
+            restore_session: true,
+            session_path: paths::default_path("session.json"),
+            export_dir: "export".to_string(),
+            status_poll_interval: 60,
+            verification_cache_ttl: 120,
+            verification_timeout: 15,
+            retry_attempts: 3,
+            retry_backoff_ms: 500,
+            pinned_versions: BTreeMap::new(),
+            rotation_due: BTreeMap::new(),
+            proof_skew: Skew::default(),
+            pin_hash: None,
+            lock_after: 300,
+            session_timeout: 1800,
+            lock_with_password: false,
+            profiles: profile::defaults(),
+            profile: "issuer".to_string(),
+        }
+    }
+}
+
+/// Loads the config from `config.json`, falling back to the defaults if there is none.
+pub fn load() -> Config {
+    fs::read_to_string(CONFIG_FILE)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+impl Config {
+    /// The config for `--ephemeral`: identities, wallet and receipts only live in memory and
+    /// every other file goes into `dir`, which is removed on quit.
+    pub fn ephemeral(self, dir: &Path) -> Config {
+        let path = |name: &str| dir.join(name).to_string_lossy().into_owned();
+        Config {
+            storage: Storage::Memory,
+            signer: SignerKind::Stronghold,
+            restore_session: false,
+            data_dir: path("data"),
+            snapshot_path: path("snapshot.hodl"),
+            backup_dir: path("backups"),
+            pseudonyms_path: path("pseudonyms.json"),
+            store_dir: path("store"),
+            inbox_dir: path("inbox"),
+            wallet_path: path("wallet.json"),
+            peers_path: path("peers.json"),
+            session_path: path("session.json"),
+            export_dir: path("export"),
+            ..self
+        }
+    }
+
+    /// Time between two node health checks, receipt checks and inbox scans. A
+    /// `node_poll_interval` of 0 counts as 1 second rather than polling without a pause.
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.node_poll_interval.max(1))
+    }
+
+    /// Claim hooks configured for the credential type, none if it has no entry.
+    pub fn hooks_for(&self, credential_type: &str) -> &[String] {
+        self.claim_hooks
+            .get(credential_type)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Contexts the registry requires for the credential type, none if it is unknown.
+    pub fn contexts_for(&self, credential_type: &str) -> Vec<String> {
+        self.credential_types
+            .get(credential_type)
+            .map(|template| template.contexts.clone())
+            .unwrap_or_default()
+    }
+
+    /// Id strategy configured for the credential type, the default one if it has no entry.
+    pub fn id_strategy_for(&self, credential_type: &str) -> &IdStrategy {
+        self.credential_ids
+            .get(credential_type)
+            .unwrap_or(&self.credential_id)
+    }
+
+    /// Returns the profile with the given name, or the configured one.
+    pub fn profile(&self, name: Option<&str>) -> Option<Profile> {
+        let name = name.unwrap_or(&self.profile);
+        self.profiles
+            .iter()
+            .find(|profile| profile.name == name)
+            .cloned()
+    }
+}
@@ -0,0 +1,115 @@
+use crate::actions::Action;
+use crate::console;
+use crate::did;
+use crate::integrity;
+use crate::receipts::PublishReceipt;
+use identity::core::{FromJson, ToJson};
+use identity::crypto::KeyPair;
+use identity::iota::{IotaDocument, TangleRef};
+use std::error::Error;
+
+/// A Tangle write prepared up to its signature, held until it is confirmed or cancelled.
+pub struct TangleWrite {
+    pub action: Action,
+    /// The signed document exactly as it is published.
+    pub document: IotaDocument,
+    /// Key of a new identity, it controls the DID once the document is published.
+    pub key: Option<KeyPair>,
+    /// What publishing changes, one line each.
+    pub consequences: Vec<String>,
+}
+
+impl TangleWrite {
+    /// Publishing a new identity for the key, with `CreateIdentity` or `PublishKey`.
+    pub fn new_identity(action: Action, key: KeyPair) -> Result<Self, Box<dyn Error>> {
+        let document: IotaDocument = did::new_document(&key)?;
+        let consequences = vec![
+            format!("Publishes the new DID {}", document.id()),
+            "Switches the holder to the new DID".to_string(),
+            "The DID follows from the key, whoever holds the key controls it".to_string(),
+        ];
+        Ok(TangleWrite {
+            action,
+            document,
+            key: Some(key),
+            consequences,
+        })
+    }
+
+    /// Publishing the document with a verification method for `public` added.
+    pub fn add_method(
+        document: &IotaDocument,
+        key: &KeyPair,
+        fragment: &str,
+        public: &[u8],
+    ) -> Result<Self, Box<dyn Error>> {
+        let consequences = vec![
+            format!(
+                "Updates {} with the verification method #{}",
+                document.id(),
+                fragment
+            ),
+            format!("Chains the update to message {}", document.message_id()),
+            "Whoever holds the new key signs for the DID from then on".to_string(),
+        ];
+        Ok(TangleWrite {
+            action: Action::AddMethod,
+            document: did::with_method(document, key, fragment, public)?,
+            key: None,
+            consequences,
+        })
+    }
+
+    /// Publishing the document of a stale or conflicting message again.
+    pub fn republish(receipt: &PublishReceipt) -> Result<Self, Box<dyn Error>> {
+        let json = receipt
+            .document
+            .as_ref()
+            .ok_or("the receipt does not keep the published document")?;
+        let consequences = vec![
+            format!("Publishes the document of {} in a new message", receipt.did),
+            format!("Message {} is no longer waited for", receipt.message_id),
+        ];
+        Ok(TangleWrite {
+            action: Action::RepublishReceipt,
+            document: IotaDocument::from_json(json)?,
+            key: None,
+            consequences,
+        })
+    }
+
+    /// Publishing a document from a file as it is, from the developer console.
+    pub fn publish_json(path: &str) -> Result<Self, Box<dyn Error>> {
+        let document: IotaDocument = console::read_document(path)?;
+        let mut consequences = vec![format!(
+            "Publishes the document of {} read from {}",
+            document.id(),
+            path
+        )];
+        // Nothing is re-signed, the console publishes broken documents too.
+        consequences.extend(integrity::check(&document).details);
+        Ok(TangleWrite {
+            action: Action::PublishJson,
+            document,
+            key: None,
+            consequences,
+        })
+    }
+
+    /// The consequences followed by the payload, for the confirmation modal.
+    pub fn text(&self) -> String {
+        let mut text: String = self
+            .consequences
+            .iter()
+            .map(|consequence| format!("- {}\n", consequence))
+            .collect();
+        text.push_str("- Tangle messages are permanent, later updates only supersede them\n\n");
+        text.push_str(
+            &self
+                .document
+                .to_json_pretty()
+                .unwrap_or_else(|error| error.to_string()),
+        );
+        text
+    }
+}
@@ -0,0 +1,89 @@
+use crate::clock;
+use crate::handle;
+use crate::integrity::Integrity;
+use crate::ledger::Ledger;
+use identity::core::{Timestamp, ToJson};
+use identity::credential::Credential;
+use identity::crypto::{KeyPair, SetSignature, Signature, TrySignature, TrySignatureMut};
+use identity::iota::{IotaDID, IotaDocument};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// The subject counter-signing that it accepted a credential. The issuer proof covers the
+/// whole credential, so the acceptance is kept next to it instead of inside it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Acceptance {
+    /// Canonical hash of the accepted credential.
+    pub credential: String,
+    pub subject: String,
+    pub accepted: Timestamp,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<Signature>,
+}
+
+impl TrySignature for Acceptance {
+    fn signature(&self) -> Option<&Signature> {
+        self.proof.as_ref()
+    }
+}
+
+impl TrySignatureMut for Acceptance {
+    fn signature_mut(&mut self) -> Option<&mut Signature> {
+        self.proof.as_mut()
+    }
+}
+
+impl SetSignature for Acceptance {
+    fn set_signature(&mut self, signature: Signature) {
+        self.proof = Some(signature);
+    }
+}
+
+/// The credential with the issuer proof and the acceptance with the subject proof, as exported
+/// from the wallet and verified like a credential.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Consented {
+    pub credential: Credential,
+    pub subject_acceptance: Acceptance,
+}
+
+/// Signs the acceptance of the credential with the key of the subject.
+pub fn accept(
+    subject: &IotaDocument,
+    key: &KeyPair,
+    credential: &Credential,
+) -> Result<Acceptance, Box<dyn Error>> {
+    let mut acceptance = Acceptance {
+        credential: handle::digest(&credential.to_json()?),
+        subject: subject.id().to_string(),
+        accepted: clock::now(),
+        proof: None,
+    };
+    subject.sign_data(&mut acceptance, key.private())?;
+    Ok(acceptance)
+}
+
+/// Checks that the acceptance is about this credential, comes from its subject
+/// and carries a valid signature of the subject.
+pub async fn check(
+    ledger: &Ledger,
+    credential: &Credential,
+    acceptance: &Acceptance,
+) -> Result<bool, Box<dyn Error>> {
+    if acceptance.credential != handle::digest(&credential.to_json()?) {
+        return Ok(false);
+    }
+    let is_subject = credential
+        .credential_subject
+        .iter()
+        .any(|subject| subject.id.as_ref().map(|id| id.as_str()) == Some(&acceptance.subject));
+    if !is_subject {
+        return Ok(false);
+    }
+    let (subject, report) = ledger
+        .resolve(&IotaDID::parse(&acceptance.subject)?)
+        .await?;
+    Ok(report.integrity != Integrity::Invalid && subject.verify_data(acceptance).is_ok())
+}
@@ -0,0 +1,75 @@
+use crate::events::{self, LogEntry};
+use identity::core::FromJson;
+use identity::iota::IotaDocument;
+use std::error::Error;
+use std::fs;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Paragraph},
+    Frame,
+};
+
+/// What the console runs.
+pub const USAGE: &str = "resolve <did>, publish-json <file>, dump-state";
+
+/// A raw operation typed into the developer console.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    /// Resolves the DID and logs its document and integrity.
+    Resolve(String),
+    /// Publishes a DID document read from a file as it is.
+    PublishJson(String),
+    /// Logs the identities, wallet and receipts the app holds.
+    DumpState,
+}
+
+pub fn parse(input: &str) -> Result<Command, String> {
+    let mut words = input.split_whitespace();
+    let command = words.next().unwrap_or_default();
+    let argument = words.next();
+    match (command, argument) {
+        ("resolve", Some(did)) => Ok(Command::Resolve(did.to_string())),
+        ("publish-json", Some(path)) => Ok(Command::PublishJson(path.to_string())),
+        ("dump-state", None) => Ok(Command::DumpState),
+        ("", _) => Err(format!("Type a command: {}", USAGE)),
+        _ => Err(format!(
+            "{} is no console command, use {}",
+            input.trim(),
+            USAGE
+        )),
+    }
+}
+
+/// The document in the file, published unchanged, so it has to be signed already.
+pub fn read_document(path: &str) -> Result<IotaDocument, Box<dyn Error>> {
+    Ok(IotaDocument::from_json(&fs::read_to_string(path)?)?)
+}
+
+/// The usage above the event log the commands write to.
+pub fn render<B: Backend>(f: &mut Frame<B>, area: Rect, log: &[LogEntry]) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(4), Constraint::Min(2)])
+        .split(area);
+    let usage = Paragraph::new(vec![
+        Spans::from(vec![Span::styled(
+            USAGE,
+            Style::default().fg(Color::LightYellow),
+        )]),
+        Spans::from(vec![Span::raw(
+            "enter types a command, the output goes to the log, F12 leaves",
+        )]),
+    ])
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title("Console")
+            .border_type(BorderType::Plain),
+    );
+    f.render_widget(usage, rows[0]);
+    f.render_widget(events::render(log), rows[1]);
+}
@@ -0,0 +1,285 @@
+use crate::armor;
+use crate::cache::VerificationCache;
+use crate::config::Config;
+use crate::did;
+use crate::handle;
+use crate::integrity::Report;
+use crate::issue;
+use crate::ledger::{Ledger, MemoryTangle};
+use crate::network::{self, Mismatch};
+use crate::nodes::{self, Nodes};
+use crate::signer::Signer;
+use crate::verification::{self, VerificationError, Verified};
+use crate::verify::{self, PresentationReport};
+use identity::core::ToJson;
+use identity::credential::{Credential, Presentation};
+use identity::crypto::KeyPair;
+use identity::iota::{Client, IotaDID, IotaDocument, Receipt};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{timeout_at, Instant};
+
+/// Outcome of an operation on the network of a DID.
+/// `value` is `None` if the DID is on another network and switching is disabled.
+pub struct OnNetwork<T> {
+    pub value: Option<T>,
+    pub mismatch: Option<Mismatch>,
+}
+
+/// Identity and credential operations behind the TUI, the CLI modes and the HTTP server,
+/// so each of them only handles its own input and output.
+#[derive(Clone)]
+pub struct Service {
+    config: Config,
+    nodes: Nodes,
+    /// Publishes and resolves in memory instead of on the nodes, for scenario runs.
+    memory: Option<MemoryTangle>,
+    cache: VerificationCache,
+    /// Issuer document versions verified against, integration message id by DID.
+    pins: Arc<Mutex<BTreeMap<String, String>>>,
+}
+
+impl Service {
+    pub fn new(config: &Config, nodes: Nodes) -> Self {
+        Service {
+            config: config.clone(),
+            nodes,
+            memory: None,
+            cache: VerificationCache::new(config.verification_cache_ttl),
+            pins: Arc::new(Mutex::new(config.pinned_versions.clone())),
+        }
+    }
+
+    /// A service that never talks to a node: identities are published to and resolved from
+    /// memory, gone when the process ends.
+    pub fn in_memory(config: &Config) -> Self {
+        Service {
+            memory: Some(MemoryTangle::default()),
+            ..Service::new(config, nodes::from_urls(&[]))
+        }
+    }
+
+    /// Verifies credentials of the issuer against the document integrated by the message,
+    /// or against the latest document again without one.
+    pub fn pin_version(&self, did: &str, message_id: Option<&str>) {
+        let mut pins = self.pins.lock().unwrap();
+        match message_id {
+            Some(message_id) => pins.insert(did.to_string(), message_id.to_string()),
+            None => pins.remove(did),
+        };
+        // Cached results were checked against another version.
+        self.cache.clear();
+    }
+
+    /// Client for the currently enabled nodes.
+    pub async fn client(&self) -> Result<Client, Box<dyn Error>> {
+        Ok(nodes::client(&self.nodes).await?)
+    }
+
+    /// Creates an identity for the key pair and publishes it.
+    pub async fn create_identity(
+        &self,
+        keypair: KeyPair,
+    ) -> Result<(IotaDocument, KeyPair, Receipt), Box<dyn Error>> {
+        Ok(did::create_did(&self.client().await?, keypair).await?)
+    }
+
+    /// Creates an identity for the key pair and publishes it, in memory if the service has no
+    /// nodes. Returns where the document went, the message URL or the in-memory message id,
+    /// since only the Tangle gives a receipt.
+    pub async fn register_identity(
+        &self,
+        keypair: KeyPair,
+    ) -> Result<(IotaDocument, KeyPair, String), Box<dyn Error>> {
+        match &self.memory {
+            Some(memory) => {
+                let mut document: IotaDocument = did::new_document(&keypair)?;
+                let message_id = memory.publish(&mut document);
+                Ok((document, keypair, format!("memory:{}", message_id)))
+            }
+            None => {
+                let (document, keypair, receipt) = self.create_identity(keypair).await?;
+                Ok((document, keypair, receipt.message_url()?.to_string()))
+            }
+        }
+    }
+
+    /// Publishes the document of a new identity prepared with `did::new_document`.
+    pub async fn publish_identity(
+        &self,
+        mut document: IotaDocument,
+        keypair: KeyPair,
+    ) -> Result<(IotaDocument, KeyPair, Receipt), Box<dyn Error>> {
+        let receipt: Receipt = self.publish_document(&mut document).await?;
+        Ok((document, keypair, receipt))
+    }
+
+    /// Publishes a signed document, linked to the previous message of the identity if any.
+    pub async fn publish_document(
+        &self,
+        document: &mut IotaDocument,
+    ) -> Result<Receipt, Box<dyn Error>> {
+        Ok(did::publish(&self.client().await?, document).await?)
+    }
+
+    /// Adds a verification method for `public` to the document and publishes the update.
+    pub async fn add_method(
+        &self,
+        document: &mut IotaDocument,
+        key: &KeyPair,
+        fragment: &str,
+        public: &[u8],
+    ) -> Result<Receipt, Box<dyn Error>> {
+        Ok(did::add_method(&self.client().await?, document, key, fragment, public).await?)
+    }
+
+    /// Signs the credential, refusing credentials naming another issuer than the signer.
+    pub async fn issue_credential(
+        &self,
+        signer: &dyn Signer,
+        credential: &mut Credential,
+    ) -> Result<(), Box<dyn Error>> {
+        let issuer = signer.document().id().as_str();
+        if credential.issuer.url().as_str() != issuer {
+            return Err(format!(
+                "the credential names {} as issuer, not {}",
+                credential.issuer.url(),
+                issuer
+            )
+            .into());
+        }
+        Ok(signer.sign(credential).await?)
+    }
+
+    /// Verifies a credential given as JSON, compact JWS, armored or as retrieval handle,
+    /// resolving the issuer on its own network if the config allows it.
+    /// A credential verified shortly before gets the cached result.
+    /// Everything has to finish within `verification_timeout`, checks still running by then
+    /// are reported as unknown.
+    pub async fn verify_credential(
+        &self,
+        input: &str,
+    ) -> Result<OnNetwork<Verified>, Box<dyn Error>> {
+        let deadline = Instant::now() + Duration::from_secs(self.config.verification_timeout);
+        let input: String = if handle::is_handle(input) {
+            timeout_at(deadline, handle::fetch(&self.config, input))
+                .await
+                .map_err(|_| format!("fetching {} did not finish in time", input))??
+        } else {
+            armor::dearmor(input)?
+        };
+        let hash: String = handle::digest(&input);
+        if let Some(verified) = self.cache.get(&hash) {
+            return Ok(OnNetwork {
+                value: Some(verified),
+                mismatch: None,
+            });
+        }
+        let issuer: Option<IotaDID> = network::issuer_did(&input);
+        let (ledger, mismatch) = timeout_at(deadline, self.ledger_for(issuer.as_ref()))
+            .await
+            .map_err(|_| "connecting to the nodes did not finish in time")??;
+        if mismatch
+            .as_ref()
+            .map_or(false, |mismatch| !mismatch.switched)
+        {
+            return Ok(OnNetwork {
+                value: None,
+                mismatch,
+            });
+        }
+        let pins: BTreeMap<String, String> = self.pins.lock().unwrap().clone();
+        let mut verified =
+            verification::verify(&ledger, &input, deadline, &self.config.proof_skew, &pins).await?;
+        // Requiring consent, a credential without a valid subject acceptance does not verify.
+        if self.config.verify_consent && verified.consent != Some(true) {
+            verified.verified = false;
+            verified
+                .error
+                .get_or_insert(VerificationError::MissingConsent);
+        }
+        // A partial result is not reused, the next attempt may finish in time or reach the issuer.
+        let unresolved = matches!(verified.error, Some(VerificationError::ResolutionFailed(_)));
+        if verified.unknown.is_empty() && !unresolved {
+            self.cache.insert(&hash, &verified);
+        }
+        Ok(OnNetwork {
+            value: Some(verified),
+            mismatch,
+        })
+    }
+
+    /// Checks that the prerequisite is an enrollment credential about `subject` that verifies.
+    /// The subject's acceptance is no condition here, only the issuer's proof.
+    pub async fn verify_prerequisite(
+        &self,
+        prerequisite: &Credential,
+        subject: &IotaDocument,
+    ) -> Result<bool, Box<dyn Error>> {
+        if !issue::is_prerequisite(prerequisite, subject) {
+            return Ok(false);
+        }
+        let verified = self.verify_credential(&prerequisite.to_json()?).await?;
+        Ok(verified.value.map_or(false, |verified| {
+            verified.verified || verified.error == Some(VerificationError::MissingConsent)
+        }))
+    }
+
+    /// Verifies the presentation and its credentials, and each proof of a proof set against
+    /// the holder document.
+    pub async fn verify_presentation(
+        &self,
+        presentation: &Presentation,
+        proofs: &[Value],
+    ) -> Result<PresentationReport, Box<dyn Error>> {
+        let validation = verify::verify_presentation(&self.client().await?, presentation).await?;
+        let (ledger, _) = self.ledger_for(None).await?;
+        let proofs = verification::check_proofs::<Presentation>(&ledger, proofs).await;
+        Ok(PresentationReport::new(&validation).with_proofs(proofs))
+    }
+
+    /// Resolves the DID document and checks the integrity of its message chain.
+    pub async fn resolve(
+        &self,
+        did: &IotaDID,
+    ) -> Result<OnNetwork<(IotaDocument, Report)>, Box<dyn Error>> {
+        let (ledger, mismatch) = self.ledger_for(Some(did)).await?;
+        if mismatch
+            .as_ref()
+            .map_or(false, |mismatch| !mismatch.switched)
+        {
+            return Ok(OnNetwork {
+                value: None,
+                mismatch,
+            });
+        }
+        Ok(OnNetwork {
+            value: Some(ledger.resolve(did).await?),
+            mismatch,
+        })
+    }
+
+    async fn client_for(
+        &self,
+        did: Option<&IotaDID>,
+    ) -> Result<(Client, Option<Mismatch>), Box<dyn Error>> {
+        Ok(network::client_for(self.client().await?, did, self.config.auto_switch_network).await?)
+    }
+
+    /// The in-memory ledger if the service has one, the nodes of the DID's network otherwise.
+    async fn ledger_for(
+        &self,
+        did: Option<&IotaDID>,
+    ) -> Result<(Ledger, Option<Mismatch>), Box<dyn Error>> {
+        match &self.memory {
+            Some(memory) => Ok((Ledger::Memory(memory.clone()), None)),
+            None => {
+                let (client, mismatch) = self.client_for(did).await?;
+                Ok((Ledger::Tangle(client), mismatch))
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use crate::clock;
+use identity::core::Timestamp;
+use std::sync::atomic::{AtomicI64, AtomicU8, Ordering};
+
+/// Date order and words of the configured locale.
+static STYLE: AtomicU8 = AtomicU8::new(Style::Iso as u8);
+/// Seconds the configured timezone is ahead of UTC.
+static ZONE: AtomicI64 = AtomicI64::new(0);
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+enum Style {
+    /// `2026-10-15 14:30`, the default.
+    Iso,
+    /// `Oct 15, 2026, 2:30 PM`
+    Us,
+    /// `15 Oct 2026, 14:30`
+    Gb,
+    /// `15.10.2026, 14:30`
+    De,
+    /// `15/10/2026 14:30`
+    Fr,
+}
+
+impl Style {
+    fn parse(locale: &str) -> Self {
+        let locale = locale.trim().to_lowercase().replace('_', "-");
+        match locale.as_str() {
+            "en" | "en-us" => Style::Us,
+            "en-gb" => Style::Gb,
+            _ if locale.starts_with("de") => Style::De,
+            _ if locale.starts_with("fr") => Style::Fr,
+            _ => Style::Iso,
+        }
+    }
+
+    fn current() -> Self {
+        match STYLE.load(Ordering::SeqCst) {
+            1 => Style::Us,
+            2 => Style::Gb,
+            3 => Style::De,
+            4 => Style::Fr,
+            _ => Style::Iso,
+        }
+    }
+}
+
+/// Parses timezones like `UTC`, `+02:00` or `-05:30` into seconds ahead of UTC.
+pub fn parse_zone(zone: &str) -> Result<i64, String> {
+    let zone = zone.trim();
+    if zone.is_empty() || zone.eq_ignore_ascii_case("utc") || zone == "Z" {
+        return Ok(0);
+    }
+    let invalid = || format!("{} is no timezone like UTC or +02:00", zone);
+    let (sign, rest) = match zone.chars().next() {
+        Some('+') => (1, &zone[1..]),
+        Some('-') => (-1, &zone[1..]),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i64 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i64 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 14 || minutes > 59 {
+        return Err(invalid());
+    }
+    Ok(sign * (hours * 3_600 + minutes * 60))
+}
+
+/// Sets the locale and timezone dates are rendered in, unknown locales render ISO dates.
+pub fn set(locale: &str, zone: &str) -> Result<(), String> {
+    ZONE.store(parse_zone(zone)?, Ordering::SeqCst);
+    STYLE.store(Style::parse(locale) as u8, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Year, month and day of the days since 1970-01-01.
+fn civil(days: i64) -> (i64, usize, i64) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month as usize, day)
+}
+
+/// Date and minutes of the day of the timestamp in the configured timezone.
+fn local(time: Timestamp) -> ((i64, usize, i64), i64) {
+    let seconds = time.to_unix() + ZONE.load(Ordering::SeqCst);
+    (
+        civil(seconds.div_euclid(86_400)),
+        seconds.rem_euclid(86_400) / 60,
+    )
+}
+
+/// The date in the configured locale and timezone.
+pub fn date(time: Timestamp) -> String {
+    let ((year, month, day), _) = local(time);
+    match Style::current() {
+        Style::Iso => format!("{}-{:02}-{:02}", year, month, day),
+        Style::Us => format!("{} {}, {}", MONTHS[month - 1], day, year),
+        Style::Gb => format!("{} {} {}", day, MONTHS[month - 1], year),
+        Style::De => format!("{:02}.{:02}.{}", day, month, year),
+        Style::Fr => format!("{:02}/{:02}/{}", day, month, year),
+    }
+}
+
+/// Date and time in the configured locale and timezone.
+pub fn date_time(time: Timestamp) -> String {
+    let (_, minutes) = local(time);
+    let (hour, minute) = (minutes / 60, minutes % 60);
+    match Style::current() {
+        Style::Iso => format!("{} {:02}:{:02}", date(time), hour, minute),
+        Style::Us => format!(
+            "{}, {}:{:02} {}",
+            date(time),
+            (hour + 11) % 12 + 1,
+            minute,
+            if hour < 12 { "AM" } else { "PM" }
+        ),
+        Style::Gb | Style::De => format!("{}, {:02}:{:02}", date(time), hour, minute),
+        Style::Fr => format!("{} {:02}:{:02}", date(time), hour, minute),
+    }
+}
+
+/// How far the time lies from the app's now, like `in 3 days` or `2 hours ago`.
+pub fn relative(time: Timestamp) -> String {
+    let seconds = time.to_unix() - clock::now().to_unix();
+    let (count, unit) = match seconds.abs() {
+        s if s >= 86_400 => (s / 86_400, 0),
+        s if s >= 3_600 => (s / 3_600, 1),
+        s if s >= 60 => (s / 60, 2),
+        _ => (0, 3),
+    };
+    let future = seconds > 0;
+    match Style::current() {
+        Style::De => {
+            let unit = match (unit, count == 1) {
+                (0, true) => "Tag",
+                (0, false) => "Tagen",
+                (1, true) => "Stunde",
+                (1, false) => "Stunden",
+                (2, true) => "Minute",
+                (2, false) => "Minuten",
+                _ => return "jetzt".to_string(),
+            };
+            if future {
+                format!("in {} {}", count, unit)
+            } else {
+                format!("vor {} {}", count, unit)
+            }
+        }
+        Style::Fr => {
+            let unit = match unit {
+                0 => "jour",
+                1 => "heure",
+                2 => "minute",
+                _ => return "maintenant".to_string(),
+            };
+            let plural = if count == 1 { "" } else { "s" };
+            if future {
+                format!("dans {} {}{}", count, unit, plural)
+            } else {
+                format!("il y a {} {}{}", count, unit, plural)
+            }
+        }
+        Style::Iso | Style::Us | Style::Gb => {
+            let unit = match unit {
+                0 => "day",
+                1 => "hour",
+                2 => "minute",
+                _ => return "now".to_string(),
+            };
+            let plural = if count == 1 { "" } else { "s" };
+            if future {
+                format!("in {} {}{}", count, unit, plural)
+            } else {
+                format!("{} {}{} ago", count, unit, plural)
+            }
+        }
+    }
+}
+
+/// `Oct 15, 2026 (in 3 days)`
+pub fn with_relative(time: Timestamp) -> String {
+    format!("{} ({})", date(time), relative(time))
+}
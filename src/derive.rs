@@ -0,0 +1,51 @@
+use crate::hooks;
+use identity::core::{Object, Timestamp, Url, Value};
+use identity::credential::{Credential, CredentialBuilder, Subject};
+use identity::iota::IotaDocument;
+use std::error::Error;
+
+/// Type of the derived credential, the threshold is a claim so one type covers all ages.
+pub const AGE_OVER: &str = "AgeOverCredential";
+
+/// The `birthDate` claim of the first subject carrying one.
+pub fn birthdate(credential: &Credential) -> Option<String> {
+    credential.credential_subject.iter().find_map(|subject| {
+        match subject.properties.get("birthDate") {
+            Some(Value::String(birthdate)) => Some(birthdate.clone()),
+            _ => None,
+        }
+    })
+}
+
+/// Derives an unsigned "over X" credential from a credential with a birthdate.
+/// Only the predicate is disclosed, the birthdate itself stays in the source credential.
+pub fn age_over(
+    issuer: &IotaDocument,
+    source: &Credential,
+    threshold: u32,
+) -> Result<Credential, Box<dyn Error>> {
+    let birthdate: String = birthdate(source).ok_or("the credential has no birthDate claim")?;
+    let age: u32 = hooks::age(&birthdate, &Timestamp::now_utc().to_string())?;
+    if age < threshold {
+        return Err(format!("the subject is not over {}", threshold).into());
+    }
+    let subject_id = source
+        .credential_subject
+        .iter()
+        .find_map(|subject| subject.id.clone())
+        .ok_or("the credential has no subject id")?;
+
+    let mut claims = Object::new();
+    claims.insert("ageOver".to_string(), Value::from(threshold));
+    let mut credential: Credential = CredentialBuilder::default()
+        .issuer(Url::parse(issuer.id().as_str())?)
+        .type_(AGE_OVER)
+        .subject(Subject::with_id_and_properties(subject_id, claims))
+        .build()?;
+    if let Some(id) = &source.id {
+        credential
+            .properties
+            .insert("derivedFrom".to_string(), Value::String(id.to_string()));
+    }
+    Ok(credential)
+}
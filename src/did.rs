@@ -1,29 +1,125 @@
+use identity::core::Timestamp;
+use identity::did::{MethodBuilder, MethodData, MethodScope, MethodType};
 use identity::iota::Receipt;
-use identity::iota::{ClientMap, TangleRef};
+use identity::iota::{Client, IotaVerificationMethod, TangleRef};
 use identity::prelude::*;
-pub async fn create_did() -> Result<(IotaDocument, KeyPair, Receipt)> {
-    // Create a client instance to send messages to the Tangle.
-    let client: ClientMap = ClientMap::new();
 
-    // Generate a new Ed25519 public/private key pair.
-    let keypair: KeyPair = KeyPair::new_ed25519()?;
+pub async fn create_did(
+    client: &Client,
+    keypair: KeyPair,
+) -> Result<(IotaDocument, KeyPair, Receipt)> {
+    let mut document: IotaDocument = new_document(&keypair)?;
+    let receipt: Receipt = publish(client, &mut document).await?;
+    Ok((document, keypair, receipt))
+}
 
+/// The signed DID document of a new identity, not published yet.
+pub fn new_document(keypair: &KeyPair) -> Result<IotaDocument> {
     // Create a DID Document (an identity) from the generated key pair.
-    let mut document: IotaDocument = IotaDocument::new(&keypair)?;
+    let mut document: IotaDocument = IotaDocument::new(keypair)?;
 
     // Sign the DID Document with the default authentication key.
     document.sign(keypair.private())?;
 
-    println!("DID Document JSON > {:#}", document);
+    Ok(document)
+}
 
-    // Publish the DID Document to the Tangle.
-    let receipt: Receipt = client.publish_document(&document).await?;
+/// Publishes the signed document to the Tangle and records the message it went into.
+pub async fn publish(client: &Client, document: &mut IotaDocument) -> Result<Receipt> {
+    let receipt: Receipt = client.publish_document(document).await?;
     document.set_message_id(*receipt.message_id());
+    Ok(receipt)
+}
 
-    println!("Publish Receipt > {:#?}", receipt);
+/// Adds an Ed25519 verification method with the given public key and publishes the update.
+pub async fn add_method(
+    client: &Client,
+    document: &mut IotaDocument,
+    key: &KeyPair,
+    fragment: &str,
+    public: &[u8],
+) -> Result<Receipt> {
+    *document = with_method(document, key, fragment, public)?;
+    publish(client, document).await
+}
 
-    // Display the web explorer url that shows the published message.
-    println!("DID Document Transaction > {}", receipt.message_url()?);
+/// Replaces the verification method `old` with a new one for `public` and publishes the update.
+/// Whatever the old key signs no longer verifies against the document from then on.
+pub async fn rotate_method(
+    client: &Client,
+    document: &mut IotaDocument,
+    key: &KeyPair,
+    old: &str,
+    fragment: &str,
+    public: &[u8],
+) -> Result<Receipt> {
+    let mut rotated: IotaDocument = document.clone();
+    rotated.remove_method(&document.id().join(format!("#{}", old))?)?;
+    *document = with_method(&rotated, key, fragment, public)?;
+    publish(client, document).await
+}
 
-    Ok((document, keypair, receipt))
+/// Why the fragment can't name a new method or service of the document: a fragment holds
+/// letters, digits, `-`, `.`, `_` and `~` only and no two entries of a document share one.
+pub fn fragment_error(document: &IotaDocument, fragment: &str) -> Option<String> {
+    if fragment.is_empty() {
+        return Some("the fragment is empty".to_string());
+    }
+    if let Some(invalid) = fragment
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')))
+    {
+        return Some(format!(
+            "#{} contains {:?}, which is no fragment character",
+            fragment, invalid
+        ));
+    }
+    let id = format!("{}#{}", document.id(), fragment);
+    if document
+        .methods()
+        .any(|method| method.id().to_string() == id)
+    {
+        return Some(format!("#{} already names a verification method", fragment));
+    }
+    if document
+        .service()
+        .iter()
+        .any(|service| service.id().to_string() == id)
+    {
+        return Some(format!("#{} already names a service", fragment));
+    }
+    None
+}
+
+/// The first `key-<n>` fragment not taken in the document, for methods named by no `kid`.
+pub fn free_fragment(document: &IotaDocument) -> String {
+    (document.methods().count()..)
+        .map(|n| format!("key-{}", n))
+        .find(|fragment| fragment_error(document, fragment).is_none())
+        .unwrap_or_default()
+}
+
+/// The document with an Ed25519 verification method added, chained and signed for publishing.
+pub fn with_method(
+    document: &IotaDocument,
+    key: &KeyPair,
+    fragment: &str,
+    public: &[u8],
+) -> Result<IotaDocument> {
+    let mut document: IotaDocument = document.clone();
+    let method: IotaVerificationMethod = IotaVerificationMethod::from_builder(
+        MethodBuilder::default()
+            .id(document.id().join(format!("#{}", fragment))?.into())
+            .controller(document.id().clone().into())
+            .key_type(MethodType::Ed25519VerificationKey2018)
+            .key_data(MethodData::new_b58(public)),
+    )?;
+    document.insert_method(MethodScope::VerificationMethod, method);
+
+    // Chain the update to the previous integration message and sign it again.
+    document.set_previous_message_id(*document.message_id());
+    document.set_updated(Timestamp::now_utc());
+    document.sign(key.private())?;
+
+    Ok(document)
 }
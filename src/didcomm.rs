@@ -0,0 +1,278 @@
+use crate::pairing::Peer;
+use chacha20poly1305::aead::{Aead, Payload};
+use chacha20poly1305::Nonce;
+use identity::core::{decode_b64, encode_b64, FromJson, Timestamp, ToJson};
+use identity::credential::Credential;
+use identity::crypto::{Ed25519, Sign, Verify};
+use identity::prelude::*;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use uuid::Uuid;
+
+/// Media types of the three DIDComm v2 envelopes.
+pub const PLAIN: &str = "application/didcomm-plain+json";
+pub const SIGNED: &str = "application/didcomm-signed+json";
+pub const ENCRYPTED: &str = "application/didcomm-encrypted+json";
+
+/// Issue Credential 3.0 messages exchanged between instances.
+pub const OFFER: &str = "https://didcomm.org/issue-credential/3.0/offer-credential";
+pub const REQUEST: &str = "https://didcomm.org/issue-credential/3.0/request-credential";
+pub const ISSUE: &str = "https://didcomm.org/issue-credential/3.0/issue-credential";
+/// Sent back for a rejected request.
+pub const PROBLEM: &str = "https://didcomm.org/report-problem/2.0/problem-report";
+
+/// Attachment format of a W3C credential with a linked data proof.
+const VC_FORMAT: &str = "aries/ld-proof-vc@v1.0";
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// A DIDComm v2 message before it is packed into an envelope.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Message {
+    pub id: String,
+    pub typ: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub to: Vec<String>,
+    pub created_time: i64,
+    pub body: Value,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Attachment {
+    pub id: String,
+    pub media_type: String,
+    pub format: String,
+    pub data: AttachmentData,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AttachmentData {
+    pub json: Value,
+}
+
+impl Message {
+    pub fn new(type_: &str, body: Value) -> Self {
+        Message {
+            id: Uuid::new_v4().to_string(),
+            typ: PLAIN.to_string(),
+            type_: type_.to_string(),
+            from: None,
+            to: Vec::new(),
+            created_time: Timestamp::now_utc().to_unix(),
+            body,
+            attachments: Vec::new(),
+        }
+    }
+
+    pub fn from(mut self, did: &str) -> Self {
+        self.from = Some(did.to_string());
+        self
+    }
+
+    pub fn to(mut self, did: &str) -> Self {
+        self.to.push(did.to_string());
+        self
+    }
+
+    pub fn attach(mut self, format: &str, json: Value) -> Self {
+        self.attachments.push(Attachment {
+            id: Uuid::new_v4().to_string(),
+            media_type: "application/json".to_string(),
+            format: format.to_string(),
+            data: AttachmentData { json },
+        });
+        self
+    }
+
+    /// JSON of the first attachment in the format.
+    pub fn attachment(&self, format: &str) -> Option<&Value> {
+        self.attachments
+            .iter()
+            .find(|attachment| attachment.format == format)
+            .map(|attachment| &attachment.data.json)
+    }
+}
+
+/// An offer or issue message carrying the credential, from its issuer to its subject.
+pub fn credential(type_: &str, credential: &Credential) -> Result<Message, Box<dyn Error>> {
+    let mut message = Message::new(type_, json!({})).from(credential.issuer.url().as_str());
+    if let Some(subject) = credential
+        .credential_subject
+        .iter()
+        .find_map(|subject| subject.id.as_ref())
+    {
+        message = message.to(subject.as_str());
+    }
+    Ok(message.attach(VC_FORMAT, credential.to_json_value()?))
+}
+
+/// The credential an offer or issue message carries.
+pub fn credential_of(message: &Message) -> Option<Credential> {
+    if message.type_ != OFFER && message.type_ != ISSUE {
+        return None;
+    }
+    Credential::from_json_value(message.attachment(VC_FORMAT)?.clone()).ok()
+}
+
+/// The plaintext envelope, for content that carries its own proof.
+pub fn plain(message: &Message) -> Result<String, Box<dyn Error>> {
+    Ok(serde_json::to_string_pretty(message)?)
+}
+
+/// The signed envelope (JWS JSON serialization), signed with the authentication key of the
+/// sender, who becomes `from`.
+pub fn sign(
+    mut message: Message,
+    sender: &IotaDocument,
+    key: &KeyPair,
+) -> Result<String, Box<dyn Error>> {
+    message.from = Some(sender.id().to_string());
+    let protected = encode_b64(&json!({ "typ": SIGNED, "alg": "EdDSA" }).to_json_vec()?);
+    let payload = encode_b64(&serde_json::to_vec(&message)?);
+    let signature = Ed25519::sign(
+        format!("{}.{}", protected, payload).as_bytes(),
+        key.private(),
+    )?;
+    Ok(serde_json::to_string_pretty(&json!({
+        "payload": payload,
+        "signatures": [{
+            "protected": protected,
+            "signature": encode_b64(&signature),
+            "header": { "kid": sender.authentication().id().to_string() },
+        }],
+    }))?)
+}
+
+/// The encrypted envelope (JWE JSON serialization) for a paired device. The pairing secret is
+/// the content key (`dir`), the demo identities hold no key agreement keys to derive one from.
+pub fn encrypt(message: &Message, peer: &Peer) -> Result<String, Box<dyn Error>> {
+    let protected = encode_b64(
+        &json!({ "typ": ENCRYPTED, "alg": "dir", "enc": "C20P", "kid": peer.channel_id() })
+            .to_json_vec()?,
+    );
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let sealed = peer
+        .cipher()?
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &serde_json::to_vec(message)?,
+                aad: protected.as_bytes(),
+            },
+        )
+        .map_err(|_| "encrypting for the paired device failed")?;
+    let (ciphertext, tag) = sealed.split_at(sealed.len() - TAG_LEN);
+    Ok(serde_json::to_string_pretty(&json!({
+        "protected": protected,
+        "iv": encode_b64(&nonce),
+        "ciphertext": encode_b64(ciphertext),
+        "tag": encode_b64(tag),
+    }))?)
+}
+
+/// Signature of a signed envelope, checked once the document of the signer is resolved.
+#[derive(Clone, Debug)]
+pub struct Signed {
+    /// Verification method the signature claims to be made with.
+    pub kid: String,
+    signing_input: String,
+    signature: Vec<u8>,
+}
+
+impl Signed {
+    /// DID of the signer.
+    pub fn did(&self) -> &str {
+        self.kid.split('#').next().unwrap_or_default()
+    }
+
+    /// Whether the document holds the method and its key made the signature.
+    pub fn check(&self, document: &IotaDocument) -> bool {
+        document
+            .resolve(self.kid.as_str())
+            .and_then(|method| method.key_data().try_decode().ok())
+            .map_or(false, |public: Vec<u8>| {
+                Ed25519::verify(self.signing_input.as_bytes(), &self.signature, &public).is_ok()
+            })
+    }
+}
+
+/// A message taken out of its envelope.
+#[derive(Clone, Debug)]
+pub struct Unpacked {
+    pub message: Message,
+    /// The signature to check if it came in a signed envelope.
+    pub signed: Option<Signed>,
+}
+
+/// Takes the message out of a plaintext, signed or encrypted envelope, decrypting with the
+/// pairing secret of one of the peers. Fails on input that is no DIDComm envelope, like the
+/// bare JSON earlier versions exchanged.
+pub fn unpack(input: &str, peers: &[Peer]) -> Result<Unpacked, Box<dyn Error>> {
+    let envelope: Value = serde_json::from_str(input.trim())?;
+    if let Some(ciphertext) = envelope["ciphertext"].as_str() {
+        let protected = envelope["protected"].as_str().unwrap_or_default();
+        let header: Value = serde_json::from_slice(&decode_b64(protected)?)?;
+        let peer = peers
+            .iter()
+            .find(|peer| header["kid"].as_str() == Some(&peer.channel_id()))
+            .ok_or("the envelope is not encrypted for a paired device")?;
+        let iv: Vec<u8> = decode_b64(envelope["iv"].as_str().unwrap_or_default())?;
+        let tag: Vec<u8> = decode_b64(envelope["tag"].as_str().unwrap_or_default())?;
+        // `Nonce::from_slice` panics on another length, anyone can drop a file in the channel.
+        if iv.len() != NONCE_LEN || tag.len() != TAG_LEN {
+            return Err(format!(
+                "the envelope needs a {} byte iv and a {} byte tag",
+                NONCE_LEN, TAG_LEN
+            )
+            .into());
+        }
+        let sealed = [decode_b64(ciphertext)?, tag].concat();
+        let opened = peer
+            .cipher()?
+            .decrypt(
+                Nonce::from_slice(&iv),
+                Payload {
+                    msg: &sealed,
+                    aad: protected.as_bytes(),
+                },
+            )
+            .map_err(|_| "the envelope was not encrypted with the pairing secret")?;
+        return unpack(&String::from_utf8(opened)?, &[]);
+    }
+    if let Some(payload) = envelope["payload"].as_str() {
+        let signature = &envelope["signatures"][0];
+        let protected = signature["protected"].as_str().unwrap_or_default();
+        let message: Message = serde_json::from_slice(&decode_b64(payload)?)?;
+        let signed = Signed {
+            kid: signature["header"]["kid"]
+                .as_str()
+                .ok_or("the signed envelope names no key")?
+                .to_string(),
+            signing_input: format!("{}.{}", protected, payload),
+            signature: decode_b64(signature["signature"].as_str().unwrap_or_default())?,
+        };
+        if message.from.as_deref() != Some(signed.did()) {
+            return Err("the envelope is signed by another DID than the sender".into());
+        }
+        return Ok(Unpacked {
+            message,
+            signed: Some(signed),
+        });
+    }
+    if envelope["typ"].as_str() != Some(PLAIN) {
+        return Err("no DIDComm envelope".into());
+    }
+    Ok(Unpacked {
+        message: serde_json::from_value(envelope)?,
+        signed: None,
+    })
+}
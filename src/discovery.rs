@@ -0,0 +1,149 @@
+use crate::config::Config;
+use crate::didcomm;
+use identity::core::ToJson;
+use identity::credential::{Credential, Presentation};
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+/// mDNS service type the demo instances announce their HTTP store under.
+#[cfg(feature = "discovery")]
+const SERVICE_TYPE: &str = "_iota-identity._tcp.local.";
+
+/// Another demo instance on the LAN, reachable through its HTTP store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instance {
+    pub name: String,
+    /// Base URL of its HTTP store.
+    pub url: String,
+    /// Issuer DID the instance announced.
+    pub did: String,
+}
+
+/// Announces this instance and keeps the list of the others up to date.
+#[cfg_attr(not(feature = "discovery"), allow(dead_code))]
+pub struct Discovery {
+    instances: Arc<Mutex<Vec<Instance>>>,
+    #[cfg(feature = "discovery")]
+    _daemon: mdns_sd::ServiceDaemon,
+}
+
+impl Discovery {
+    /// Starts the discovery if the app was built with the `discovery` feature and runs the
+    /// HTTP store the other instances send to, `None` otherwise.
+    #[cfg(feature = "discovery")]
+    pub fn start(config: &Config, did: &str) -> Result<Option<Self>, Box<dyn Error>> {
+        use crate::config::CredentialStore;
+        use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+        use std::collections::HashMap;
+        use std::thread;
+
+        if config.credential_store != CredentialStore::Http {
+            return Ok(None);
+        }
+        let url = reqwest::Url::parse(&config.store_url)?;
+        let host = url.host_str().ok_or("store_url names no host")?;
+        if host == "localhost" || host.starts_with("127.") {
+            return Err(format!("store_url {} is not reachable from the LAN", url).into());
+        }
+        let port = url.port_or_known_default().unwrap_or(80);
+        let name = format!("iota-identity-{}", &crate::handle::hash(did)[..8]);
+
+        let daemon = ServiceDaemon::new()?;
+        let mut properties = HashMap::new();
+        properties.insert("did".to_string(), did.to_string());
+        properties.insert("url".to_string(), config.store_url.clone());
+        daemon.register(ServiceInfo::new(
+            SERVICE_TYPE,
+            &name,
+            &format!("{}.local.", name),
+            host,
+            port,
+            Some(properties),
+        )?)?;
+
+        let instances: Arc<Mutex<Vec<Instance>>> = Default::default();
+        let receiver = daemon.browse(SERVICE_TYPE)?;
+        let found = instances.clone();
+        let own = format!("{}.{}", name, SERVICE_TYPE);
+        thread::spawn(move || {
+            while let Ok(event) = receiver.recv() {
+                let mut found = found.lock().unwrap();
+                match event {
+                    ServiceEvent::ServiceResolved(info) if info.get_fullname() != own => {
+                        let properties = info.get_properties();
+                        let instance = Instance {
+                            name: info
+                                .get_fullname()
+                                .trim_end_matches(SERVICE_TYPE)
+                                .trim_end_matches('.')
+                                .to_string(),
+                            url: properties.get("url").cloned().unwrap_or_else(|| {
+                                let address = info
+                                    .get_addresses()
+                                    .iter()
+                                    .next()
+                                    .map(|address| address.to_string())
+                                    .unwrap_or_default();
+                                format!("http://{}:{}", address, info.get_port())
+                            }),
+                            did: properties.get("did").cloned().unwrap_or_default(),
+                        };
+                        found.retain(|known| known.name != instance.name);
+                        found.push(instance);
+                    }
+                    ServiceEvent::ServiceRemoved(_, fullname) => {
+                        found.retain(|known| !fullname.starts_with(&known.name));
+                    }
+                    _ => {}
+                }
+            }
+        });
+        Ok(Some(Discovery {
+            instances,
+            _daemon: daemon,
+        }))
+    }
+
+    #[cfg(not(feature = "discovery"))]
+    pub fn start(_config: &Config, _did: &str) -> Result<Option<Self>, Box<dyn Error>> {
+        Ok(None)
+    }
+
+    /// The instances found so far, sorted by name.
+    pub fn instances(&self) -> Vec<Instance> {
+        let mut instances = self.instances.lock().unwrap().clone();
+        instances.sort_by(|a, b| a.name.cmp(&b.name));
+        instances
+    }
+}
+
+/// Drops the credential as offer in a plaintext DIDComm envelope into the inbox of the
+/// instance, its holder collects it with `g`.
+pub async fn send_credential(
+    instance: &Instance,
+    credential: &Credential,
+) -> Result<(), Box<dyn Error>> {
+    let offer = didcomm::credential(didcomm::OFFER, credential)?;
+    post(&format!("{}/inbox", instance.url), didcomm::plain(&offer)?).await
+}
+
+/// Delivers the presentation to the verifying instance, as answer to its request `id` if any.
+pub async fn send_presentation(
+    instance: &Instance,
+    id: Option<&str>,
+    presentation: &Presentation,
+) -> Result<(), Box<dyn Error>> {
+    let url = match id {
+        Some(id) => format!("{}/presentations/{}", instance.url, id),
+        None => format!("{}/presentations", instance.url),
+    };
+    post(&url, presentation.to_json()?).await
+}
+
+async fn post(url: &str, body: String) -> Result<(), Box<dyn Error>> {
+    let response = reqwest::Client::new().post(url).body(body).send().await?;
+    if !response.status().is_success() {
+        return Err(format!("{} refused: {}", url, response.text().await?).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,218 @@
+use crate::palette::centered_rect;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use zeroize::Zeroize;
+
+/// Text input of the prompts with a cursor.
+/// A terminal delivers pasted text as key presses with Enter between the lines, so a
+/// multi-line editor takes Enter as a line break and is submitted with Ctrl-S.
+#[derive(Clone, Debug)]
+pub struct Editor {
+    lines: Vec<String>,
+    row: usize,
+    /// Cursor position in chars within the row.
+    column: usize,
+    multiline: bool,
+}
+
+impl Editor {
+    pub fn new(multiline: bool) -> Self {
+        Editor {
+            lines: vec![String::new()],
+            row: 0,
+            column: 0,
+            multiline,
+        }
+    }
+
+    /// An editor holding the text, with the cursor at its start.
+    pub fn with_text(text: &str, multiline: bool) -> Self {
+        let mut editor = Editor::new(multiline);
+        if !text.is_empty() {
+            editor.lines = text.lines().map(str::to_string).collect();
+        }
+        editor
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        self.multiline
+    }
+
+    /// Handles a key, returns the text when the input is submitted.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<String> {
+        match key.code {
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Some(self.text());
+            }
+            KeyCode::Char(_) if key.modifiers.contains(KeyModifiers::CONTROL) => {}
+            KeyCode::Char(c) => self.insert(c),
+            KeyCode::Tab if self.multiline => {
+                self.insert(' ');
+                self.insert(' ');
+            }
+            KeyCode::Enter if self.multiline => self.break_line(),
+            KeyCode::Enter => return Some(self.text()),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left if self.column > 0 => self.column -= 1,
+            KeyCode::Left if self.row > 0 => {
+                self.row -= 1;
+                self.column = self.row_len();
+            }
+            KeyCode::Right if self.column < self.row_len() => self.column += 1,
+            KeyCode::Right if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.column = 0;
+            }
+            KeyCode::Up => self.move_rows(-1),
+            KeyCode::Down => self.move_rows(1),
+            KeyCode::PageUp => self.move_rows(-10),
+            KeyCode::PageDown => self.move_rows(10),
+            KeyCode::Home => self.column = 0,
+            KeyCode::End => self.column = self.row_len(),
+            _ => {}
+        }
+        None
+    }
+
+    fn row_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    /// Byte offset of the cursor in its row.
+    fn offset(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices()
+            .nth(self.column)
+            .map_or(line.len(), |(i, _)| i)
+    }
+
+    fn insert(&mut self, c: char) {
+        let offset = self.offset();
+        self.lines[self.row].insert(offset, c);
+        self.column += 1;
+    }
+
+    fn break_line(&mut self) {
+        let offset = self.offset();
+        let rest = self.lines[self.row].split_off(offset);
+        self.row += 1;
+        self.lines.insert(self.row, rest);
+        self.column = 0;
+    }
+
+    fn backspace(&mut self) {
+        if self.column > 0 {
+            self.column -= 1;
+            let offset = self.offset();
+            self.lines[self.row].remove(offset);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.column = self.row_len();
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.column < self.row_len() {
+            let offset = self.offset();
+            self.lines[self.row].remove(offset);
+        } else if self.row + 1 < self.lines.len() {
+            let line = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    fn move_rows(&mut self, rows: isize) {
+        let last = self.lines.len() as isize - 1;
+        self.row = (self.row as isize + rows).max(0).min(last) as usize;
+        self.column = self.column.min(self.row_len());
+    }
+
+    /// Shows the editor in a modal, scrolled so the cursor stays visible.
+    pub fn render<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        title: &str,
+        warning: Option<&str>,
+        label: &str,
+    ) {
+        let area = centered_rect(80, 70, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title(title.to_string())
+            .border_type(BorderType::Double);
+        let inner = block.inner(area);
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+
+        let mut header: Vec<Spans> = Vec::new();
+        if let Some(warning) = warning {
+            header.push(Spans::from(Span::styled(
+                warning.to_string(),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+        }
+        header.push(Spans::from(Span::raw(label.to_string())));
+        let hint = if self.multiline {
+            "Ctrl-S confirms, enter adds a line, esc cancels"
+        } else {
+            "enter confirms, esc cancels"
+        };
+        header.push(Spans::from(Span::styled(
+            format!("{} (line {}/{})", hint, self.row + 1, self.lines.len()),
+            Style::default().fg(Color::Gray),
+        )));
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(header.len() as u16 + 1),
+                Constraint::Min(1),
+            ])
+            .split(inner);
+        f.render_widget(Paragraph::new(header).wrap(Wrap { trim: true }), rows[0]);
+
+        let text = rows[1];
+        if text.height == 0 || text.width == 0 {
+            return;
+        }
+        let top = self.row.saturating_sub(text.height as usize - 1);
+        let left = self.column.saturating_sub(text.width as usize - 1);
+        let lines: Vec<Spans> = self
+            .lines
+            .iter()
+            .map(|line| Spans::from(Span::raw(line.clone())))
+            .collect();
+        f.render_widget(
+            Paragraph::new(lines)
+                .style(Style::default().fg(Color::Yellow))
+                .scroll((top as u16, left as u16)),
+            text,
+        );
+        f.set_cursor(
+            text.x + (self.column - left) as u16,
+            text.y + (self.row - top) as u16,
+        );
+    }
+}
+
+impl Drop for Editor {
+    /// Prompts also take passwords and keys, don't leave them behind in memory.
+    fn drop(&mut self) {
+        self.lines.zeroize();
+    }
+}
@@ -0,0 +1,33 @@
+use identity::core::Timestamp;
+use tui::{
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem},
+};
+
+#[derive(Clone, Debug)]
+pub struct LogEntry {
+    pub time: Timestamp,
+    pub message: String,
+}
+
+pub fn render<'a>(entries: &[LogEntry]) -> List<'a> {
+    let items: Vec<ListItem> = entries
+        .iter()
+        .rev()
+        .map(|entry| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{} ", entry.time), Style::default().fg(Color::Gray)),
+                Span::raw(entry.message.clone()),
+            ]))
+        })
+        .collect();
+
+    List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title("Notifications")
+            .border_type(BorderType::Plain),
+    )
+}
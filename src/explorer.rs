@@ -0,0 +1,209 @@
+use crate::receipts::PublishReceipt;
+use identity::core::{Timestamp, ToJson};
+use identity::iota::{Client, DocumentChain, DocumentDiff, IotaDID, TangleRef};
+use identity::prelude::*;
+use std::collections::BTreeSet;
+use tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph, Wrap},
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Kind {
+    Integration,
+    Diff,
+}
+
+/// One message of the DID, with the document state right after it.
+#[derive(Clone, Debug)]
+pub struct Message {
+    pub kind: Kind,
+    pub message_id: String,
+    pub updated: Timestamp,
+    pub state: IotaDocument,
+    pub changes: Vec<String>,
+}
+
+/// Lists the integration messages followed by the diff messages on top of the latest one.
+/// Diffs before the latest integration message are superseded and no longer resolved.
+pub async fn load(client: &Client, did: &IotaDID) -> Result<Vec<Message>> {
+    let chain: DocumentChain = client.read_document_chain(did).await?;
+    let integration: Vec<IotaDocument> = chain
+        .integration_chain()
+        .history()
+        .unwrap_or_default()
+        .iter()
+        .chain(std::iter::once(chain.integration_chain().current()))
+        .cloned()
+        .collect();
+
+    let mut messages: Vec<Message> = Vec::new();
+    let mut previous: Option<IotaDocument> = None;
+    for document in integration {
+        messages.push(Message {
+            kind: Kind::Integration,
+            message_id: document.message_id().to_string(),
+            updated: document.updated(),
+            changes: changes(previous.as_ref(), &document),
+            state: document.clone(),
+        });
+        previous = Some(document);
+    }
+
+    let mut state: IotaDocument = chain.integration_chain().current().clone();
+    for diff in chain.diff().iter() {
+        let diff: &DocumentDiff = diff;
+        let merged: IotaDocument = diff.merge(&state)?;
+        messages.push(Message {
+            kind: Kind::Diff,
+            message_id: diff.message_id().to_string(),
+            updated: merged.updated(),
+            changes: changes(Some(&state), &merged),
+            state: merged.clone(),
+        });
+        state = merged;
+    }
+
+    messages.sort_by(|a, b| a.updated.cmp(&b.updated));
+    Ok(messages)
+}
+
+/// Describes what the message changed compared to the state before it.
+fn changes(previous: Option<&IotaDocument>, current: &IotaDocument) -> Vec<String> {
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return vec!["Created the document".to_string()],
+    };
+
+    let methods = |document: &IotaDocument| -> BTreeSet<String> {
+        document
+            .methods()
+            .map(|method| method.id().to_string())
+            .collect()
+    };
+    let services = |document: &IotaDocument| -> BTreeSet<String> {
+        document
+            .service()
+            .iter()
+            .map(|service| service.id().to_string())
+            .collect()
+    };
+
+    let mut changes = Vec::new();
+    let (before, after) = (methods(previous), methods(current));
+    changes.extend(
+        after
+            .difference(&before)
+            .map(|id| format!("Added method {}", id)),
+    );
+    changes.extend(
+        before
+            .difference(&after)
+            .map(|id| format!("Removed method {}", id)),
+    );
+    let (before, after) = (services(previous), services(current));
+    changes.extend(
+        after
+            .difference(&before)
+            .map(|id| format!("Added service {}", id)),
+    );
+    changes.extend(
+        before
+            .difference(&after)
+            .map(|id| format!("Removed service {}", id)),
+    );
+    if changes.is_empty() {
+        changes.push("No method or service changes".to_string());
+    }
+    changes
+}
+
+pub fn render_list<'a>(messages: &[Message], did: &str) -> List<'a> {
+    let items: Vec<ListItem> = messages
+        .iter()
+        .map(|message| {
+            let (kind, color) = match message.kind {
+                Kind::Integration => ("[integration]", Color::LightBlue),
+                Kind::Diff => ("[diff]", Color::LightMagenta),
+            };
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{} ", kind), Style::default().fg(color)),
+                Span::raw(format!("{} ", message.updated)),
+                Span::styled(
+                    message.message_id.chars().take(16).collect::<String>(),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]))
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(format!("Messages of {}", did))
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+}
+
+/// Details of the message, with its explorer link if this app published it.
+pub fn render_detail<'a>(
+    message: Option<&Message>,
+    receipt: Option<&PublishReceipt>,
+) -> Paragraph<'a> {
+    let mut lines = Vec::new();
+    if let Some(message) = message {
+        lines.push(Spans::from(vec![Span::raw(format!(
+            "Message {}",
+            message.message_id
+        ))]));
+        if let Some(url) = receipt.and_then(|receipt| receipt.explorer_url.as_ref()) {
+            lines.push(Spans::from(vec![Span::styled(
+                url.clone(),
+                Style::default().fg(Color::Gray),
+            )]));
+        }
+        lines.push(Spans::from(vec![Span::raw("")]));
+        for change in &message.changes {
+            lines.push(Spans::from(vec![Span::styled(
+                change.clone(),
+                Style::default().fg(Color::LightCyan),
+            )]));
+        }
+        lines.push(Spans::from(vec![Span::raw("")]));
+        let state = message
+            .state
+            .to_json_pretty()
+            .unwrap_or_else(|error| error.to_string());
+        lines.extend(
+            state
+                .lines()
+                .map(|line| Spans::from(vec![Span::raw(line.to_string())])),
+        );
+    }
+
+    Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title("Changes and document state")
+            .border_type(BorderType::Plain),
+    )
+}
+
+/// Splits the screen into the message list and the detail of the selected message.
+pub fn layout(area: Rect) -> Vec<Rect> {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+        .split(area)
+}
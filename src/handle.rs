@@ -0,0 +1,504 @@
+use crate::bus::{Bus, Message};
+use crate::config::{Config, CredentialStore};
+use crate::core::{OnNetwork, Service};
+use crate::didcomm;
+use crate::issue;
+use crate::jcs;
+use crate::oob;
+use crate::pairing::Peers;
+use crate::refresh;
+use crate::request;
+use crate::resolution;
+use crate::revocation;
+use crate::signer::{self, Signer};
+use crate::store::IdentityStore;
+use crate::verification::{VerificationError, Verified};
+use identity::core::FromJson;
+use identity::credential::{Credential, Presentation};
+use identity::iota::{Client, IotaDID};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::convert::Infallible;
+use std::error::Error;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::task::JoinHandle;
+use warp::http::StatusCode;
+use warp::hyper::body::Bytes;
+use warp::{Filter, Reply};
+
+const TANGLE_SCHEME: &str = "iota-index:";
+
+#[derive(Deserialize)]
+struct Response<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageIds {
+    message_ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    payload: Indexation,
+}
+
+#[derive(Deserialize)]
+struct Indexation {
+    data: String,
+}
+
+pub fn hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Hash of a document used as its anchor. JSON is hashed in canonical form (JCS),
+/// so whitespace and key order of a serialization don't change it, anything else as it is.
+pub fn digest(document: &str) -> String {
+    hash(&jcs::canonical(document).unwrap_or_else(|| document.to_string()))
+}
+
+/// Publishes the credential to the configured store and returns the handle to put in the QR,
+/// or the credential itself if no store is configured.
+pub async fn share(
+    config: &Config,
+    client: &Client,
+    credential: &str,
+) -> Result<String, Box<dyn Error>> {
+    let hash = digest(credential);
+    match config.credential_store {
+        CredentialStore::Off => Ok(credential.to_string()),
+        CredentialStore::Http => {
+            fs::create_dir_all(&config.store_dir)?;
+            fs::write(Path::new(&config.store_dir).join(&hash), credential)?;
+            Ok(format!("{}/credentials/{}", config.store_url, hash))
+        }
+        CredentialStore::Tangle => {
+            client.publish_json(&hash, &credential.to_string()).await?;
+            Ok(format!("{}{}", TANGLE_SCHEME, hash))
+        }
+    }
+}
+
+pub fn is_handle(input: &str) -> bool {
+    input.starts_with(TANGLE_SCHEME)
+        || input.starts_with("http://")
+        || input.starts_with("https://")
+}
+
+/// Fetches the credential behind a handle and checks it against the hash in the handle.
+pub async fn fetch(config: &Config, handle: &str) -> Result<String, Box<dyn Error>> {
+    let (credential, expected) = match handle.strip_prefix(TANGLE_SCHEME) {
+        Some(hash) => (fetch_tangle(config, hash).await?, hash),
+        None => {
+            let hash = handle.rsplit('/').next().unwrap_or_default();
+            (
+                reqwest::get(handle)
+                    .await?
+                    .error_for_status()?
+                    .text()
+                    .await?,
+                hash,
+            )
+        }
+    };
+
+    // Handles shared before canonical hashing carry the hash of the exact bytes.
+    if digest(&credential) != expected && hash(&credential) != expected {
+        return Err(format!("content of {} does not match its hash", handle).into());
+    }
+    Ok(credential)
+}
+
+async fn fetch_tangle(config: &Config, hash: &str) -> Result<String, Box<dyn Error>> {
+    let node = config.nodes.first().ok_or("no node configured")?;
+    let ids: Response<MessageIds> = reqwest::get(format!(
+        "{}/api/v1/messages?index={}",
+        node,
+        hex::encode(hash)
+    ))
+    .await?
+    .json()
+    .await?;
+    let id = ids
+        .data
+        .message_ids
+        .first()
+        .ok_or("no message found for handle")?;
+
+    let message: Response<Message> = reqwest::get(format!("{}/api/v1/messages/{}", node, id))
+        .await?
+        .json()
+        .await?;
+    let credential: String = serde_json::from_slice(&hex::decode(message.data.payload.data)?)?;
+    Ok(credential)
+}
+
+/// Serves the stored credentials at `/credentials/<hash>`, and through the service
+/// verifies credentials posted to `/verify`, resolves DIDs at `/resolve/<did>`, or as DID
+/// Resolution Result at `/1.0/identifiers/<did>` like the Universal Resolver, and renews credentials posted to `/refresh` with the signer, reporting them on the bus. The status list of the issuer is
+/// at `/status`. Other instances drop credentials into the inbox at `/inbox` and presentations
+/// at `/presentations`, or at `/presentations/<id>` to answer a request.
+pub fn spawn_server(
+    config: &Config,
+    service: Service,
+    signer: signer::Slot,
+    store: Arc<dyn IdentityStore>,
+    bus: Bus,
+) -> Result<JoinHandle<()>, Box<dyn Error>> {
+    let dir = config.store_dir.clone();
+    let status_list = Path::new(&config.store_dir).join(revocation::FILE);
+    let validity_days = config.validity_days;
+    let (inbox, peers_path) = (config.inbox_dir.clone(), config.peers_path.clone());
+    let (responses, unsolicited) = (config.inbox_dir.clone(), config.inbox_dir.clone());
+    let address: SocketAddr = config.store_bind.parse()?;
+
+    let credentials = warp::path!("credentials" / String).map(move |hash: String| {
+        if !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+        match fs::read_to_string(Path::new(&dir).join(&hash)) {
+            Ok(credential) => {
+                warp::reply::with_header(credential, "content-type", "application/json")
+                    .into_response()
+            }
+            Err(_) => StatusCode::NOT_FOUND.into_response(),
+        }
+    });
+
+    let status = warp::path!("status").map(move || match fs::read_to_string(&status_list) {
+        Ok(list) => {
+            warp::reply::with_header(list, "content-type", "application/json").into_response()
+        }
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    });
+
+    let deliver = warp::path!("inbox")
+        .and(warp::post())
+        .and(warp::body::bytes())
+        .map(move |body: Bytes| {
+            match inbox_reply(&inbox, &peers_path, &String::from_utf8_lossy(&body)) {
+                Ok(()) => StatusCode::ACCEPTED.into_response(),
+                Err(error) => warp::reply::with_status(error.to_string(), StatusCode::BAD_REQUEST)
+                    .into_response(),
+            }
+        });
+    let present = warp::path!("presentations" / String)
+        .and(warp::post())
+        .and(warp::body::bytes())
+        .map(move |id: String, body: Bytes| {
+            match presentation_reply(&responses, Some(&id), &String::from_utf8_lossy(&body)) {
+                Ok(()) => StatusCode::ACCEPTED.into_response(),
+                Err(error) => warp::reply::with_status(error.to_string(), StatusCode::BAD_REQUEST)
+                    .into_response(),
+            }
+        });
+    let present_unsolicited = warp::path!("presentations")
+        .and(warp::post())
+        .and(warp::body::bytes())
+        .map(move |body: Bytes| {
+            match presentation_reply(&unsolicited, None, &String::from_utf8_lossy(&body)) {
+                Ok(()) => StatusCode::ACCEPTED.into_response(),
+                Err(error) => warp::reply::with_status(error.to_string(), StatusCode::BAD_REQUEST)
+                    .into_response(),
+            }
+        });
+
+    let verifier = service.clone();
+    let verify = warp::path!("verify")
+        .and(warp::post())
+        .and(warp::body::bytes())
+        .and_then(move |body: Bytes| {
+            let service = verifier.clone();
+            async move { Ok::<_, Infallible>(verify_reply(&service, &body).await) }
+        });
+    let refresher = service.clone();
+    let refresh = warp::path!("refresh")
+        .and(warp::post())
+        .and(warp::body::bytes())
+        .and_then(move |body: Bytes| {
+            let service = refresher.clone();
+            let signer: Option<Arc<dyn Signer>> = signer.read().unwrap().clone();
+            let (store, bus) = (store.clone(), bus.clone());
+            async move {
+                Ok::<_, Infallible>(match signer {
+                    Some(signer) => {
+                        refresh_reply(&service, &*signer, validity_days, &*store, &bus, &body).await
+                    }
+                    None => warp::reply::with_status(
+                        "the issuer key is being restored, try again",
+                        StatusCode::SERVICE_UNAVAILABLE,
+                    )
+                    .into_response(),
+                })
+            }
+        });
+    let resolver = service.clone();
+    let resolve = warp::path!("resolve" / String).and_then(move |did: String| {
+        let service = resolver.clone();
+        async move { Ok::<_, Infallible>(resolve_reply(&service, &did).await) }
+    });
+    let identifiers = warp::path!("1.0" / "identifiers" / String).and_then(move |did: String| {
+        let service = service.clone();
+        async move { Ok::<_, Infallible>(resolution_reply(&service, &did).await) }
+    });
+
+    Ok(tokio::spawn(
+        warp::serve(
+            credentials
+                .or(verify)
+                .or(refresh)
+                .or(status)
+                .or(resolve)
+                .or(identifiers)
+                .or(deliver)
+                .or(present)
+                .or(present_unsolicited),
+        )
+        .run(address),
+    ))
+}
+
+async fn verify_reply(service: &Service, body: &[u8]) -> warp::reply::Response {
+    let input = String::from_utf8_lossy(body);
+    match service.verify_credential(input.trim()).await {
+        Ok(OnNetwork {
+            value:
+                Some(Verified {
+                    credential,
+                    verified,
+                    format,
+                    integrity,
+                    consent,
+                    error,
+                    proofs,
+                    unknown,
+                    warnings,
+                    ..
+                }),
+            ..
+        }) => warp::reply::json(&json!({
+            "verified": verified,
+            "format": format!("{:?}", format),
+            "issuer": credential.issuer.url().to_string(),
+            "selfIssued": issue::is_self_issued(&credential),
+            "issuerIntegrity": format!("{:?}", integrity.integrity),
+            "subjectConsent": consent,
+            "error": error.as_ref().map(error_json),
+            "proofs": proofs
+                .iter()
+                .map(|check| json!({
+                    "verificationMethod": check.method,
+                    "proofPurpose": check.purpose,
+                    "verified": check.error.is_none(),
+                    "error": check.error.as_ref().map(error_json),
+                }))
+                .collect::<Vec<_>>(),
+            "unknown": unknown,
+            "warnings": warnings,
+        }))
+        .into_response(),
+        Ok(OnNetwork { mismatch, .. }) => warp::reply::with_status(
+            mismatch
+                .map(|mismatch| mismatch.to_string())
+                .unwrap_or_default(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        )
+        .into_response(),
+        Err(error) => match error.downcast_ref::<VerificationError>() {
+            Some(error) => warp::reply::with_status(
+                warp::reply::json(&json!({ "verified": false, "error": error_json(error) })),
+                StatusCode::BAD_REQUEST,
+            )
+            .into_response(),
+            None => {
+                warp::reply::with_status(error.to_string(), StatusCode::BAD_REQUEST).into_response()
+            }
+        },
+    }
+}
+
+/// Delivers a credential sent by another instance like an issued one, the wallet verifies
+/// it when collecting. It comes offered in a DIDComm envelope, encrypted if the devices are
+/// paired, or bare from earlier versions.
+fn inbox_reply(inbox: &str, peers_path: &str, body: &str) -> Result<(), Box<dyn Error>> {
+    let peers = Peers::load(peers_path);
+    let credential = match didcomm::unpack(body, &peers.peers) {
+        Ok(unpacked) => {
+            didcomm::credential_of(&unpacked.message).ok_or("the message offers no credential")?
+        }
+        // Not bare JSON either, the envelope is what went wrong.
+        Err(error) => Credential::from_json(body.trim()).map_err(|_| error)?,
+    };
+    request::deliver(inbox, &peers, &digest(body), &credential)?;
+    Ok(())
+}
+
+/// Stores the presentation where the request `id` looks for its answer, or with the ones
+/// sent without a request. The verifier checks it when receiving.
+fn presentation_reply(inbox: &str, id: Option<&str>, body: &str) -> Result<(), Box<dyn Error>> {
+    let presentation = Presentation::from_json(body.trim())?;
+    match id {
+        Some(id) if id.chars().all(|c| c.is_ascii_hexdigit()) => {
+            let dir = oob::responses_dir(inbox);
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join(format!("{}.json", id)), body.trim())?;
+        }
+        Some(_) => return Err("invalid request id".into()),
+        None => {
+            oob::keep_unsolicited(inbox, &presentation)?;
+        }
+    }
+    Ok(())
+}
+
+fn error_json(error: &VerificationError) -> serde_json::Value {
+    json!({ "code": error.code(), "message": error.to_string() })
+}
+
+async fn refresh_reply(
+    service: &Service,
+    signer: &dyn Signer,
+    validity_days: u64,
+    store: &dyn IdentityStore,
+    bus: &Bus,
+    body: &[u8],
+) -> warp::reply::Response {
+    let input = String::from_utf8_lossy(body);
+    let log = store.load_issued();
+    match refresh::renew(service, signer, validity_days, &log, input.trim()).await {
+        Ok(credential) => {
+            bus.publish(Message::Renewed(credential.clone()));
+            warp::reply::with_header(credential.to_string(), "content-type", "application/json")
+                .into_response()
+        }
+        Err(error) => {
+            warp::reply::with_status(error.to_string(), StatusCode::BAD_REQUEST).into_response()
+        }
+    }
+}
+
+/// The DID Resolution Result of the DID, errors with their registered codes.
+async fn resolution_reply(service: &Service, did: &str) -> warp::reply::Response {
+    let (result, status) = match IotaDID::parse(did) {
+        Err(error) => (
+            resolution::failure("invalidDid", &error.to_string()),
+            StatusCode::BAD_REQUEST,
+        ),
+        Ok(did) => {
+            let started = Instant::now();
+            match service.resolve(&did).await {
+                Ok(OnNetwork {
+                    value: Some((document, report)),
+                    ..
+                }) => (
+                    resolution::result(&document, &report, Some(started.elapsed())),
+                    StatusCode::OK,
+                ),
+                Ok(OnNetwork { mismatch, .. }) => (
+                    resolution::failure(
+                        "methodNotSupported",
+                        &mismatch
+                            .map(|mismatch| mismatch.to_string())
+                            .unwrap_or_default(),
+                    ),
+                    StatusCode::NOT_IMPLEMENTED,
+                ),
+                Err(error) => (
+                    resolution::failure("notFound", &error.to_string()),
+                    StatusCode::NOT_FOUND,
+                ),
+            }
+        }
+    };
+    warp::reply::with_status(
+        warp::reply::with_header(result.to_string(), "content-type", resolution::CONTENT_TYPE),
+        status,
+    )
+    .into_response()
+}
+
+async fn resolve_reply(service: &Service, did: &str) -> warp::reply::Response {
+    let did: IotaDID = match IotaDID::parse(did) {
+        Ok(did) => did,
+        Err(_) => return StatusCode::BAD_REQUEST.into_response(),
+    };
+    match service.resolve(&did).await {
+        Ok(OnNetwork {
+            value: Some((document, report)),
+            ..
+        }) => warp::reply::json(&json!({
+            "document": document,
+            "integrity": report.integrity.badge().0,
+            "details": report.details,
+        }))
+        .into_response(),
+        Ok(OnNetwork { mismatch, .. }) => warp::reply::with_status(
+            mismatch
+                .map(|mismatch| mismatch.to_string())
+                .unwrap_or_default(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        )
+        .into_response(),
+        Err(error) => {
+            warp::reply::with_status(error.to_string(), StatusCode::NOT_FOUND).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pairing::Peer;
+    use identity::core::{Object, Timestamp};
+    use identity::crypto::KeyPair;
+    use identity::iota::IotaDocument;
+
+    #[test]
+    fn delivers_an_envelope_encrypted_for_a_paired_device() {
+        let dir = std::env::temp_dir().join(format!("inbox-reply-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let inbox: String = dir.join("inbox").to_string_lossy().to_string();
+        let peers_path: String = dir.join("peers.json").to_string_lossy().to_string();
+
+        let holder = IotaDocument::new(&KeyPair::new_ed25519().unwrap()).unwrap();
+        let issuer = IotaDocument::new(&KeyPair::new_ed25519().unwrap()).unwrap();
+        let peer = Peer {
+            label: "phone".to_string(),
+            holder: holder.id().to_string(),
+            secret: hex::encode([7u8; 32]),
+            paired: Timestamp::now_utc(),
+        };
+        let credential: Credential = issue::issue_claims(
+            &issuer,
+            "https://example.edu/credentials/3732",
+            holder.id().as_str(),
+            "DegreeCredential",
+            &[],
+            Object::new(),
+        )
+        .unwrap();
+        let envelope: String = didcomm::encrypt(
+            &didcomm::credential(didcomm::ISSUE, &credential).unwrap(),
+            &peer,
+        )
+        .unwrap();
+
+        // Without the pairing the envelope can't be opened.
+        assert!(inbox_reply(&inbox, &peers_path, &envelope).is_err());
+
+        let peers = Peers { peers: vec![peer] };
+        peers.save(&peers_path).unwrap();
+        inbox_reply(&inbox, &peers_path, &envelope).unwrap();
+        let received: Vec<Credential> = peers.receive(&inbox, holder.id().as_str());
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].id, credential.id);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::dates;
+use crate::report::VerificationReport;
+use serde::{Deserialize, Serialize};
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem},
+};
+
+/// Number of verifications kept.
+const KEEP: usize = 50;
+
+/// Recent verifications, newest first.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct VerificationHistory {
+    pub reports: Vec<VerificationReport>,
+}
+
+impl VerificationHistory {
+    pub fn record(&mut self, report: VerificationReport) {
+        self.reports.insert(0, report);
+        self.reports.truncate(KEEP);
+    }
+
+    /// Updates the outcome of a recorded verification, e.g. after a trust decision.
+    pub fn update(&mut self, report: &VerificationReport) {
+        if let Some(recorded) = self
+            .reports
+            .iter_mut()
+            .find(|recorded| recorded.checked == report.checked)
+        {
+            recorded.outcome = report.outcome.clone();
+        }
+    }
+}
+
+pub fn render<'a>(history: &VerificationHistory) -> List<'a> {
+    let items: Vec<ListItem> = history
+        .reports
+        .iter()
+        .map(|report| {
+            let (mark, color) = if report.verified {
+                ("[valid]", Color::Green)
+            } else {
+                ("[invalid]", Color::Red)
+            };
+            ListItem::new(vec![
+                Spans::from(vec![
+                    Span::styled(format!("{} ", mark), Style::default().fg(color)),
+                    Span::raw(report.credential_type.clone()),
+                    Span::styled(
+                        format!(" {}", dates::date_time(report.checked)),
+                        Style::default().fg(Color::Gray),
+                    ),
+                ]),
+                Spans::from(vec![Span::styled(
+                    format!("  {}", report.issuer),
+                    Style::default().fg(Color::Gray),
+                )]),
+            ])
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title("Recent verifications (enter opens)")
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+}
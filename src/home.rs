@@ -0,0 +1,133 @@
+use crate::dates;
+use crate::redact;
+use crate::status::Status;
+use crate::wallet::{Entry, Wallet};
+use std::collections::HashMap;
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem},
+};
+
+/// A card on the Home tab.
+#[derive(Clone, Debug)]
+pub enum Pin {
+    /// Id of a pinned wallet credential.
+    Credential(String),
+    Identity(String),
+}
+
+/// The pinned credentials in wallet order, then the pinned DIDs. Archived credentials drop out.
+pub fn pins(wallet: &Wallet) -> Vec<Pin> {
+    wallet
+        .entries
+        .iter()
+        .filter(|entry| entry.pinned && entry.archived.is_none())
+        .map(|entry| Pin::Credential(entry.id.clone()))
+        .chain(
+            wallet
+                .pinned_identities
+                .iter()
+                .map(|did| Pin::Identity(did.clone())),
+        )
+        .collect()
+}
+
+/// The pinned cards with their status and what enter and b do on them, or the welcome text
+/// until something is pinned.
+pub fn render<'a>(
+    pins: &[Pin],
+    wallet: &Wallet,
+    statuses: &HashMap<String, Status>,
+    holder: &str,
+    issuer: &str,
+    redacted: bool,
+) -> List<'a> {
+    let shown = |did: &str| {
+        if redacted {
+            redact::dids(did)
+        } else {
+            did.to_string()
+        }
+    };
+    let items: Vec<ListItem> = if pins.is_empty() {
+        vec![
+            ListItem::new(Spans::from(vec![Span::styled(
+                "Welcome to SSI @ IOTA",
+                Style::default().fg(Color::LightBlue),
+            )])),
+            ListItem::new(Spans::from(vec![Span::raw(
+                "F pins the selected credential on the Wallet tab and the resolved DID on the Verify tab.",
+            )])),
+            ListItem::new(Spans::from(vec![Span::raw("Press q to quit.")])),
+        ]
+    } else {
+        pins.iter()
+            .map(|pin| match pin {
+                Pin::Credential(id) => match wallet.entries.iter().find(|entry| entry.id == *id) {
+                    Some(entry) => credential_card(entry, statuses.get(id)),
+                    None => ListItem::new(Span::raw("")),
+                },
+                Pin::Identity(did) => {
+                    let role = if did == holder {
+                        " (current holder)"
+                    } else if did == issuer {
+                        " (issuer)"
+                    } else {
+                        ""
+                    };
+                    ListItem::new(vec![
+                        Spans::from(vec![
+                            Span::styled("[did] ", Style::default().fg(Color::LightBlue)),
+                            Span::styled(shown(did), Style::default().add_modifier(Modifier::BOLD)),
+                            Span::raw(role),
+                        ]),
+                        Spans::from(vec![Span::styled(
+                            "  enter resolves, F unpins",
+                            Style::default().fg(Color::Gray),
+                        )]),
+                    ])
+                }
+            })
+            .collect()
+    };
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title("Home")
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+}
+
+fn credential_card<'a>(entry: &Entry, status: Option<&Status>) -> ListItem<'a> {
+    let (badge, color) = status
+        .map(Status::badge)
+        .unwrap_or(("[checking]", Color::Gray));
+    let icon = entry
+        .appearance
+        .icon
+        .as_ref()
+        .map(|icon| format!("{} ", icon))
+        .unwrap_or_default();
+    let expiry = entry
+        .credential
+        .expiration_date
+        .map(|expires| format!("expires {}, ", dates::relative(expires)))
+        .unwrap_or_default();
+    ListItem::new(vec![
+        Spans::from(vec![
+            Span::styled(format!("{} ", badge), Style::default().fg(color)),
+            Span::styled(
+                format!("{}{}", icon, entry.name()),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Spans::from(vec![Span::styled(
+            format!("  {}enter verifies, b presents, F unpins", expiry),
+            Style::default().fg(Color::Gray),
+        )]),
+    ])
+}
@@ -0,0 +1,155 @@
+use identity::core::{Object, Timestamp, Value};
+use std::collections::BTreeMap;
+
+/// Normalizes or enriches the claims of a credential before it is signed.
+/// A new hook implements this trait and is added to the registry in `Registry::with_defaults`.
+pub trait ClaimHook: Send + Sync {
+    /// Name the hook is referred to by in `claim_hooks` of the config.
+    fn name(&self) -> &'static str;
+    fn apply(&self, claims: &mut Object) -> Result<(), String>;
+}
+
+/// Removes surrounding whitespace from all string claims.
+pub struct Trim;
+
+impl ClaimHook for Trim {
+    fn name(&self) -> &'static str {
+        "trim"
+    }
+
+    fn apply(&self, claims: &mut Object) -> Result<(), String> {
+        for value in claims.values_mut() {
+            trim(value);
+        }
+        Ok(())
+    }
+}
+
+fn trim(value: &mut Value) {
+    match value {
+        Value::String(text) => *text = text.trim().to_string(),
+        Value::Object(object) => object.values_mut().for_each(trim),
+        _ => {}
+    }
+}
+
+/// Uppercases country codes like `country=de` or `address.countryCode=ch`.
+pub struct UppercaseCountry;
+
+impl ClaimHook for UppercaseCountry {
+    fn name(&self) -> &'static str {
+        "uppercase_country"
+    }
+
+    fn apply(&self, claims: &mut Object) -> Result<(), String> {
+        for (key, value) in claims.iter_mut() {
+            uppercase_country(key, value);
+        }
+        Ok(())
+    }
+}
+
+fn uppercase_country(key: &str, value: &mut Value) {
+    match value {
+        Value::String(text) if key.to_lowercase().starts_with("country") => {
+            *text = text.to_uppercase();
+        }
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                uppercase_country(key, value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Derives `age` in years from a `birthDate` claim.
+pub struct AgeFromBirthdate;
+
+impl ClaimHook for AgeFromBirthdate {
+    fn name(&self) -> &'static str {
+        "age_from_birthdate"
+    }
+
+    fn apply(&self, claims: &mut Object) -> Result<(), String> {
+        let birthdate: String = match claims.get("birthDate") {
+            Some(Value::String(birthdate)) => birthdate.clone(),
+            Some(_) => return Err("birthDate is not a date".to_string()),
+            None => return Ok(()),
+        };
+        let age = age(&birthdate, &Timestamp::now_utc().to_string())?;
+        claims.insert("age".to_string(), Value::from(age));
+        Ok(())
+    }
+}
+
+/// Full years between two dates given as `YYYY-MM-DD` or RFC 3339.
+pub fn age(birthdate: &str, today: &str) -> Result<u32, String> {
+    let date = |input: &str| -> Option<(i32, u32, u32)> {
+        let mut parts = input.get(..10)?.split('-');
+        Some((
+            parts.next()?.parse().ok()?,
+            parts.next()?.parse().ok()?,
+            parts.next()?.parse().ok()?,
+        ))
+    };
+    let (by, bm, bd) = date(birthdate).ok_or_else(|| format!("{} is not a date", birthdate))?;
+    let (ty, tm, td) = date(today).ok_or_else(|| format!("{} is not a date", today))?;
+    let years = ty - by - if (tm, td) < (bm, bd) { 1 } else { 0 };
+    if years < 0 {
+        return Err(format!("birthdate {} lies in the future", birthdate));
+    }
+    Ok(years as u32)
+}
+
+/// The known hooks by name.
+pub struct Registry {
+    hooks: BTreeMap<&'static str, Box<dyn ClaimHook>>,
+}
+
+impl Registry {
+    pub fn with_defaults() -> Self {
+        let mut registry = Registry {
+            hooks: BTreeMap::new(),
+        };
+        registry.register(Box::new(Trim));
+        registry.register(Box::new(UppercaseCountry));
+        registry.register(Box::new(AgeFromBirthdate));
+        registry
+    }
+
+    pub fn register(&mut self, hook: Box<dyn ClaimHook>) {
+        self.hooks.insert(hook.name(), hook);
+    }
+
+    /// Runs the named hooks in order, an unknown name or a failing hook stops the pipeline.
+    pub fn run(&self, names: &[String], claims: &mut Object) -> Result<(), String> {
+        for name in names {
+            let hook = self
+                .hooks
+                .get(name.as_str())
+                .ok_or_else(|| format!("unknown claim hook {}", name))?;
+            hook.apply(claims)
+                .map_err(|error| format!("claim hook {} failed: {}", name, error))?;
+        }
+        Ok(())
+    }
+}
+
+/// Default hooks per credential type.
+pub fn defaults() -> BTreeMap<String, Vec<String>> {
+    let mut hooks = BTreeMap::new();
+    hooks.insert(
+        "UniversityDegreeCredential".to_string(),
+        vec!["trim".to_string()],
+    );
+    hooks.insert(
+        "PersonCredential".to_string(),
+        vec![
+            "trim".to_string(),
+            "uppercase_country".to_string(),
+            "age_from_birthdate".to_string(),
+        ],
+    );
+    hooks
+}
@@ -0,0 +1,56 @@
+use crate::handle;
+use crate::issued::IssuanceLog;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How the id of an issued credential is made. Every strategy draws a number from the
+/// issuance log, so ids stay unique across sessions as long as the log is kept.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "strategy", rename_all = "lowercase")]
+pub enum IdStrategy {
+    /// `urn:uuid:<random v4 uuid>`.
+    Uuid,
+    /// `<domain>/credentials/<n>` with the next number of the issuer.
+    Sequential { domain: String },
+    /// `urn:sha256:<hash>` over subject, type, claims and the drawn number.
+    Hash,
+}
+
+impl Default for IdStrategy {
+    fn default() -> Self {
+        IdStrategy::Sequential {
+            domain: "https://example.edu".to_string(),
+        }
+    }
+}
+
+/// A random `urn:uuid` id, for credentials issued outside of the issuance log.
+pub fn uuid() -> String {
+    format!("urn:uuid:{}", Uuid::new_v4())
+}
+
+/// Makes the id of a new credential, skipping ids the log already holds.
+pub fn generate(
+    strategy: &IdStrategy,
+    issued: &mut IssuanceLog,
+    subject: &str,
+    credential_type: &str,
+    claims: &str,
+) -> String {
+    loop {
+        let n = issued.next_sequence();
+        let id = match strategy {
+            IdStrategy::Uuid => uuid(),
+            IdStrategy::Sequential { domain } => {
+                format!("{}/credentials/{}", domain.trim_end_matches('/'), n)
+            }
+            IdStrategy::Hash => format!(
+                "urn:sha256:{}",
+                handle::hash(&format!("{}{}{}{}", subject, credential_type, claims, n))
+            ),
+        };
+        if !issued.contains(&id) {
+            return id;
+        }
+    }
+}
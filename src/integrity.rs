@@ -0,0 +1,196 @@
+use identity::core::Timestamp;
+use identity::iota::{Client, DocumentChain, IotaDID, MessageId, TangleRef};
+use identity::prelude::*;
+use std::error::Error;
+use tui::style::Color;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Integrity {
+    Signed,
+    Unsigned,
+    Invalid,
+    /// Not checked, the resolution did not finish within the time budget.
+    Unknown,
+}
+
+impl Integrity {
+    pub fn badge(&self) -> (&'static str, Color) {
+        match self {
+            Integrity::Signed => ("[signed]", Color::Green),
+            Integrity::Unsigned => ("[unsigned]", Color::Yellow),
+            Integrity::Invalid => ("[invalid]", Color::Red),
+            Integrity::Unknown => ("[unknown]", Color::DarkGray),
+        }
+    }
+}
+
+/// Outcome of re-checking a resolved document instead of trusting the resolver.
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub did: String,
+    pub integrity: Integrity,
+    pub details: Vec<String>,
+    /// Integration messages of the document, oldest first, the current document last.
+    pub versions: Vec<IotaDocument>,
+}
+
+/// Resolves the document and re-verifies its self-signature and integration message chain.
+pub async fn resolve(client: &Client, did: &IotaDID) -> Result<(IotaDocument, Report)> {
+    let chain: DocumentChain = client.read_document_chain(did).await?;
+    let document: IotaDocument = chain.current().clone();
+    let history: &[IotaDocument] = chain.integration_chain().history().unwrap_or_default();
+    let report: Report = check_chain(history, &document, chain.diff().len());
+    Ok((document, report))
+}
+
+/// Checks the integration messages, `history` oldest first and the current `document` last.
+pub fn check_chain(history: &[IotaDocument], document: &IotaDocument, diffs: usize) -> Report {
+    let mut report = check(document);
+    report.versions = history
+        .iter()
+        .chain(std::iter::once(document))
+        .cloned()
+        .collect();
+
+    // Every integration message has to point back to the one before it.
+    let mut previous: Option<&MessageId> = None;
+    for (index, older) in history.iter().chain(std::iter::once(document)).enumerate() {
+        if let Some(previous) = previous {
+            if older.previous_message_id() != previous {
+                report.integrity = Integrity::Invalid;
+                report.details.push(format!(
+                    "Integration message {} breaks the message chain",
+                    index
+                ));
+            }
+        }
+        if older.verify().is_err() {
+            report.integrity = Integrity::Invalid;
+            report.details.push(format!(
+                "Integration message {} carries an invalid signature",
+                index
+            ));
+        }
+        previous = Some(older.message_id());
+    }
+    report.details.push(format!(
+        "{} integration messages, {} diff messages",
+        history.len() + 1,
+        diffs
+    ));
+    report
+}
+
+/// Resolves the document as of the integration message `message_id` instead of the latest one,
+/// to reproduce past verifications. The whole chain is still checked, later versions drop out.
+pub async fn resolve_at(
+    client: &Client,
+    did: &IotaDID,
+    message_id: &str,
+) -> std::result::Result<(IotaDocument, Report), Box<dyn Error>> {
+    pin(resolve(client, did).await?.1, did, message_id)
+}
+
+/// The version of the resolved chain integrated by `message_id`, later versions dropped.
+pub fn pin(
+    mut report: Report,
+    did: &IotaDID,
+    message_id: &str,
+) -> std::result::Result<(IotaDocument, Report), Box<dyn Error>> {
+    let index = version_of(&report, message_id)
+        .ok_or_else(|| format!("{} is no integration message of {}", message_id, did))?;
+    report.details.push(format!(
+        "Pinned to version {} of {}, message {}",
+        index + 1,
+        report.versions.len(),
+        message_id
+    ));
+    report.versions.truncate(index + 1);
+    let document = report.versions[index].clone();
+    Ok((document, report))
+}
+
+/// Index of the version integrated by the message in `report.versions`.
+pub fn version_of(report: &Report, message_id: &str) -> Option<usize> {
+    report
+        .versions
+        .iter()
+        .position(|version| version.message_id().to_string() == message_id)
+}
+
+/// Checks the self-signature of a single document.
+pub fn check(document: &IotaDocument) -> Report {
+    let mut details = Vec::new();
+    let integrity = if document.proof().is_none() {
+        details.push("The document carries no proof".to_string());
+        Integrity::Unsigned
+    } else {
+        match document.verify() {
+            Ok(()) => {
+                details.push("Self-signature verified".to_string());
+                Integrity::Signed
+            }
+            Err(error) => {
+                details.push(format!("Self-signature invalid: {}", error));
+                Integrity::Invalid
+            }
+        }
+    };
+    if document.message_id().is_null() {
+        details.push("The document has not been published".to_string());
+    } else {
+        details.push(format!("Message {}", document.message_id()));
+    }
+
+    Report {
+        did: document.id().to_string(),
+        integrity,
+        details,
+        versions: vec![document.clone()],
+    }
+}
+
+/// Looks at the signing method across the document history instead of only at its key.
+/// Warns if the document is unsigned, if the method is gone from the current document or if it
+/// was added or got another key after `issued`.
+pub fn check_method(
+    report: &Report,
+    current: &IotaDocument,
+    method: &str,
+    issued: Timestamp,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if report.integrity == Integrity::Unsigned {
+        warnings.push("The issuer document carries no self-signature".to_string());
+    }
+    let key = |document: &IotaDocument| document.resolve(method).map(|m| m.key_data().clone());
+    let current_key = match key(current) {
+        Some(current_key) => current_key,
+        None => {
+            warnings.push(format!(
+                "{} was removed from the latest issuer document",
+                method
+            ));
+            return warnings;
+        }
+    };
+    // The newest version published before the credential, the first one for older credentials.
+    let at_issuance = report
+        .versions
+        .iter()
+        .rev()
+        .find(|version| version.updated() <= issued)
+        .or_else(|| report.versions.first());
+    match at_issuance.map(key) {
+        Some(None) => warnings.push(format!(
+            "{} was added to the issuer document after the credential was issued",
+            method
+        )),
+        Some(Some(issued_key)) if issued_key != current_key => warnings.push(format!(
+            "{} was rotated after the credential was issued",
+            method
+        )),
+        _ => {}
+    }
+    warnings
+}
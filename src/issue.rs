@@ -1,12 +1,63 @@
+use crate::clock;
+use crate::request::CredentialRequest;
 use identity::core::json;
+use identity::core::Context;
 use identity::core::FromJson;
+use identity::core::Object;
+use identity::core::Timestamp;
+use identity::core::ToJson;
 use identity::core::Url;
 use identity::credential::Credential;
 use identity::credential::CredentialBuilder;
+use identity::credential::Evidence;
 use identity::credential::Subject;
 use identity::prelude::*;
+use serde_json::Value;
 
-pub fn issue_degree(issuer: &IotaDocument, subject: &IotaDocument) -> Result<Credential> {
+pub fn issue_enrollment(issuer: &IotaDocument, subject: &IotaDocument) -> Result<Credential> {
+    let subject: Subject = Subject::from_json_value(json!({
+      "id": subject.id().as_str(),
+      "name": "Alice",
+      "enrollment": {
+        "type": "BachelorProgram",
+        "name": "Bachelor of Science and Arts",
+      },
+    }))?;
+
+    let credential: Credential = CredentialBuilder::default()
+        .id(Url::parse("https://example.edu/enrollments/1872")?)
+        .issuer(Url::parse(issuer.id().as_str())?)
+        .type_("EnrollmentCredential")
+        .subject(subject)
+        .build()?;
+
+    Ok(credential)
+}
+
+/// Checks that the prerequisite is an enrollment credential about `subject`, its proof is
+/// checked by `Service::verify_prerequisite`.
+pub fn is_prerequisite(prerequisite: &Credential, subject: &IotaDocument) -> bool {
+    let is_enrollment = prerequisite
+        .types
+        .iter()
+        .any(|type_| type_ == "EnrollmentCredential");
+    let is_about_subject = prerequisite
+        .credential_subject
+        .iter()
+        .any(|s| s.id.as_ref().map(|id| id.as_str()) == Some(subject.id().as_str()));
+
+    is_enrollment && is_about_subject
+}
+
+/// Type of the degree credential, its id strategy is configured under this name.
+pub const DEGREE: &str = "UniversityDegreeCredential";
+
+pub fn issue_degree(
+    issuer: &IotaDocument,
+    id: &str,
+    subject: &IotaDocument,
+    prerequisite: &Credential,
+) -> Result<Credential> {
     let subject: Subject = Subject::from_json_value(json!({
       "id": subject.id().as_str(),
       "name": "Alice",
@@ -17,13 +68,147 @@ pub fn issue_degree(issuer: &IotaDocument, subject: &IotaDocument) -> Result<Cre
       "GPA": "4.0",
     }))?;
 
+    // Reference the verified prerequisite credential as evidence.
+    let evidence: Evidence = Evidence::from_json_value(json!({
+      "id": prerequisite.id.as_ref().map(|id| id.as_str()),
+      "type": ["DocumentVerification"],
+      "verifier": issuer.id().as_str(),
+      "evidenceDocument": "EnrollmentCredential",
+      "subjectPresence": "Digital",
+      "documentPresence": "Digital",
+    }))?;
+
     // Build credential using subject above and issuer.
     let credential: Credential = CredentialBuilder::default()
-        .id(Url::parse("https://example.edu/credentials/3732")?)
+        .id(Url::parse(id)?)
         .issuer(Url::parse(issuer.id().as_str())?)
-        .type_("UniversityDegreeCredential")
+        .type_(DEGREE)
         .subject(subject)
+        .evidence(evidence)
         .build()?;
 
     Ok(credential)
 }
+
+/// Membership of the subject in a student club, meant to come from a second issuer.
+pub fn issue_membership(issuer: &IotaDocument, subject: &IotaDocument) -> Result<Credential> {
+    let subject: Subject = Subject::from_json_value(json!({
+      "id": subject.id().as_str(),
+      "name": "Alice",
+      "membership": {
+        "organization": "IOTA Student Club",
+        "role": "Member",
+      },
+    }))?;
+
+    let credential: Credential = CredentialBuilder::default()
+        .id(Url::parse("https://club.example.org/memberships/42")?)
+        .issuer(Url::parse(issuer.id().as_str())?)
+        .type_("MembershipCredential")
+        .subject(subject)
+        .build()?;
+
+    Ok(credential)
+}
+
+/// Issues a credential of the given type with the claims about the subject.
+/// The contexts are added after the base credentials context.
+pub fn issue_claims(
+    issuer: &IotaDocument,
+    id: &str,
+    subject: &str,
+    credential_type: &str,
+    contexts: &[String],
+    claims: Object,
+) -> Result<Credential> {
+    let subject: Subject = Subject::with_id_and_properties(Url::parse(subject)?, claims);
+
+    let mut builder: CredentialBuilder = CredentialBuilder::default();
+    for context in contexts {
+        builder = builder.context(Context::Url(Url::parse(context)?));
+    }
+    let credential: Credential = builder
+        .id(Url::parse(id)?)
+        .issuer(Url::parse(issuer.id().as_str())?)
+        .type_(credential_type)
+        .subject(subject)
+        .build()?;
+
+    Ok(credential)
+}
+
+/// Dates the credential by the app's clock and lets it expire after `days`, 0 never expires it.
+pub fn set_validity(credential: &mut Credential, days: u64) {
+    let now = clock::now();
+    credential.issuance_date = now;
+    credential.expiration_date = match days {
+        0 => None,
+        days => Some(Timestamp::from_unix(now.to_unix() + days as i64 * 86_400)),
+    };
+}
+
+/// Puts `to` in place of the subject `from`, the claims stay.
+pub fn replace_subject(credential: &mut Credential, from: &str, to: &str) -> Result<()> {
+    let mut json: Value = credential.to_json_value()?;
+    let subjects: Vec<&mut Value> = match &mut json["credentialSubject"] {
+        Value::Array(subjects) => subjects.iter_mut().collect(),
+        subject => vec![subject],
+    };
+    for subject in subjects {
+        if subject["id"] == from {
+            subject["id"] = json!(to);
+        }
+    }
+    *credential = Credential::from_json_value(json)?;
+    Ok(())
+}
+
+/// Parses a credential edited by hand, still unsigned and issued by `issuer`.
+pub fn parse_edited(json: &str, issuer: &IotaDocument) -> std::result::Result<Credential, String> {
+    let credential: Credential = Credential::from_json(json).map_err(|error| error.to_string())?;
+    credential
+        .check_structure()
+        .map_err(|error| error.to_string())?;
+    if credential.issuer.url().as_str() != issuer.id().as_str() {
+        return Err(format!("the issuer must stay {}", issuer.id()));
+    }
+    if credential.proof.is_some() {
+        return Err("remove the proof, the credential is signed after editing".to_string());
+    }
+    Ok(credential)
+}
+
+/// Type of the credentials a holder issues about itself.
+pub const SELF_ATTESTED: &str = "SelfAttestedCredential";
+
+/// A credential the holder issues about itself with the claims, like a preferred name.
+/// Nobody but the holder vouches for them.
+pub fn self_attest(holder: &IotaDocument, id: &str, claims: Object) -> Result<Credential> {
+    issue_claims(holder, id, holder.id().as_str(), SELF_ATTESTED, &[], claims)
+}
+
+/// Whether the issuer of the credential is its subject.
+pub fn is_self_issued(credential: &Credential) -> bool {
+    let issuer: &str = credential.issuer.url().as_str();
+    credential
+        .credential_subject
+        .iter()
+        .any(|subject| subject.id.as_ref().map(|id| id.as_str()) == Some(issuer))
+}
+
+/// Issues the credential a holder asked for, with their self-attested claims as subject.
+pub fn issue_requested(
+    issuer: &IotaDocument,
+    id: &str,
+    contexts: &[String],
+    request: &CredentialRequest,
+) -> Result<Credential> {
+    issue_claims(
+        issuer,
+        id,
+        &request.holder,
+        &request.credential_type,
+        contexts,
+        request.claims.clone(),
+    )
+}
@@ -0,0 +1,109 @@
+use crate::clock;
+use identity::core::Timestamp;
+use identity::credential::{Credential, Presentation};
+use identity::crypto::TrySignature;
+use identity::iota::IotaDocument;
+use serde::{Deserialize, Serialize};
+
+/// A credential this issuer signed, kept without its claims.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Issued {
+    pub id: String,
+    pub credential_type: String,
+    pub issuer: String,
+    pub subject: String,
+    /// Verification method the credential was signed with.
+    pub method: String,
+    pub issued: Timestamp,
+    pub expires: Option<Timestamp>,
+    /// When the signing method was found removed from the issuer document.
+    pub revoked: Option<Timestamp>,
+}
+
+/// A presentation the holder signed, kept for the key usage without its credentials.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Presented {
+    pub method: String,
+    pub signed: Timestamp,
+}
+
+/// Register of the issued credentials, the source of the dashboard.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct IssuanceLog {
+    pub records: Vec<Issued>,
+    /// Last number drawn for a credential id.
+    #[serde(default)]
+    pub sequence: u64,
+    #[serde(default)]
+    pub presentations: Vec<Presented>,
+}
+
+impl IssuanceLog {
+    /// Draws the next number for a credential id. Logs written before there was a sequence
+    /// continue after their records.
+    pub fn next_sequence(&mut self) -> u64 {
+        self.sequence = self.sequence.max(self.records.len() as u64) + 1;
+        self.sequence
+    }
+
+    /// Whether a credential with this id was issued already.
+    pub fn contains(&self, id: &str) -> bool {
+        self.records.iter().any(|record| record.id == id)
+    }
+
+    /// Whether a credential with this id was revoked.
+    pub fn is_revoked(&self, id: &str) -> bool {
+        self.records
+            .iter()
+            .any(|record| record.id == id && record.revoked.is_some())
+    }
+
+    pub fn record(&mut self, credential: &Credential) {
+        self.records.push(Issued {
+            id: credential
+                .id
+                .as_ref()
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            credential_type: credential.types.iter().last().cloned().unwrap_or_default(),
+            issuer: credential.issuer.url().to_string(),
+            subject: credential
+                .credential_subject
+                .iter()
+                .find_map(|subject| subject.id.as_ref().map(|id| id.to_string()))
+                .unwrap_or_default(),
+            method: credential
+                .signature()
+                .map(|signature| signature.verification_method().to_string())
+                .unwrap_or_default(),
+            issued: credential.issuance_date,
+            expires: credential.expiration_date,
+            revoked: None,
+        });
+    }
+
+    pub fn record_presentation(&mut self, presentation: &Presentation) {
+        if let Some(signature) = presentation.signature() {
+            self.presentations.push(Presented {
+                method: signature.verification_method().to_string(),
+                signed: clock::now(),
+            });
+        }
+    }
+
+    /// Marks the credentials of the issuer whose signing method the document no longer has.
+    /// Returns the number of newly revoked credentials.
+    pub fn mark_revoked(&mut self, document: &IotaDocument) -> usize {
+        let now = Timestamp::now_utc();
+        let mut revoked = 0;
+        for record in self.records.iter_mut().filter(|record| {
+            record.revoked.is_none()
+                && record.issuer == document.id().as_str()
+                && document.resolve(record.method.as_str()).is_none()
+        }) {
+            record.revoked = Some(now);
+            revoked += 1;
+        }
+        revoked
+    }
+}
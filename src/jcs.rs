@@ -0,0 +1,67 @@
+use serde_json::{Number, Value};
+
+/// Serializes the value in the JSON Canonicalization Scheme (RFC 8785): no whitespace,
+/// object members sorted by their UTF-16 code units and numbers in their shortest form.
+///
+/// Floats with a fractional part are written as serde_json writes them, which matches the
+/// scheme except for exponents beyond 1e21 that credentials don't use.
+pub fn canonicalize(value: &Value) -> String {
+    let mut out = String::new();
+    write(value, &mut out);
+    out
+}
+
+/// Canonical form of a JSON document, `None` if the input is no JSON.
+pub fn canonical(json: &str) -> Option<String> {
+    serde_json::from_str::<Value>(json)
+        .ok()
+        .map(|value| canonicalize(&value))
+}
+
+fn write(value: &Value, out: &mut String) {
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => {
+            out.push_str(&value.to_string());
+        }
+        Value::Number(number) => out.push_str(&number_string(number)),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(object) => {
+            let mut members: Vec<(&String, &Value)> = object.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (key, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write(value, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn number_string(number: &Number) -> String {
+    match number.as_f64() {
+        // Integral floats like 4.0 are written as 4, like JavaScript does.
+        Some(float)
+            if !number.is_i64()
+                && !number.is_u64()
+                && float.fract() == 0.0
+                && float.abs() < 1e21 =>
+        {
+            format!("{}", float as i64)
+        }
+        _ => number.to_string(),
+    }
+}
@@ -0,0 +1,127 @@
+use crate::did;
+use crate::pseudonym;
+use identity::core::{decode_b64, encode_b58, encode_b64, json, FromJson};
+use identity::did::MethodType;
+use identity::iota::{IotaDocument, IotaVerificationMethod};
+use serde_json::Value;
+use std::error::Error;
+
+/// Public OKP JSON Web Key of an Ed25519 key.
+pub fn public(kid: Option<&str>, public: &[u8]) -> Value {
+    let mut jwk = json!({
+      "kty": "OKP",
+      "crv": "Ed25519",
+      "x": encode_b64(public),
+    });
+    if let Some(kid) = kid {
+        jwk["kid"] = Value::String(kid.to_string());
+    }
+    jwk
+}
+
+/// Represents an Ed25519 verification method as a JWK with its id as `kid`.
+pub fn from_method(method: &IotaVerificationMethod) -> Result<Value, Box<dyn Error>> {
+    if method.key_type() != MethodType::Ed25519VerificationKey2018 {
+        return Err(format!("{} is not an Ed25519 key", method.id()).into());
+    }
+    let key: Vec<u8> = method.key_data().try_decode()?;
+    Ok(public(Some(&method.id().to_string()), &key))
+}
+
+/// Encoding the document view shows public keys in, to compare them with other SSI tools.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    Jwk,
+    /// `publicKeyBase58` of `Ed25519VerificationKey2018`.
+    Base58,
+    /// `publicKeyMultibase` of `Ed25519VerificationKey2020`, base58btc with the multicodec prefix.
+    Multibase,
+    Hex,
+}
+
+impl Encoding {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Encoding::Jwk => "JWK",
+            Encoding::Base58 => "base58",
+            Encoding::Multibase => "multibase",
+            Encoding::Hex => "hex",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Encoding::Jwk => Encoding::Base58,
+            Encoding::Base58 => Encoding::Multibase,
+            Encoding::Multibase => Encoding::Hex,
+            Encoding::Hex => Encoding::Jwk,
+        }
+    }
+}
+
+/// The public key of an Ed25519 verification method in the encoding, the JWK names the method
+/// as `kid`, the others are prefixed with its fragment.
+pub fn encode(
+    method: &IotaVerificationMethod,
+    encoding: Encoding,
+) -> Result<String, Box<dyn Error>> {
+    if encoding == Encoding::Jwk {
+        return Ok(from_method(method)?.to_string());
+    }
+    if method.key_type() != MethodType::Ed25519VerificationKey2018 {
+        return Err(format!("{} is not an Ed25519 key", method.id()).into());
+    }
+    let key: Vec<u8> = method.key_data().try_decode()?;
+    let encoded = match encoding {
+        Encoding::Base58 => encode_b58(&key),
+        Encoding::Multibase => format!(
+            "z{}",
+            encode_b58(&[&pseudonym::ED25519_CODEC[..], &key].concat())
+        ),
+        _ => hex::encode(&key),
+    };
+    let id = method.id().to_string();
+    Ok(format!(
+        "#{}: {}",
+        id.rsplit('#').next().unwrap_or(&id),
+        encoded
+    ))
+}
+
+/// JWK Set of all Ed25519 verification methods of a document.
+pub fn key_set<'a>(methods: impl Iterator<Item = &'a IotaVerificationMethod>) -> Value {
+    let keys: Vec<Value> = methods
+        .filter_map(|method| from_method(method).ok())
+        .collect();
+    json!({ "keys": keys })
+}
+
+/// Reads the public key and the fragment of `kid`, if any, from an Ed25519 JWK.
+pub fn parse(input: &str) -> Result<(Vec<u8>, Option<String>), Box<dyn Error>> {
+    let jwk: Value = Value::from_json(input.trim())?;
+    if jwk["kty"] != "OKP" || jwk["crv"] != "Ed25519" {
+        return Err("JWK is not an Ed25519 key".into());
+    }
+    let public: Vec<u8> = match jwk["x"].as_str() {
+        Some(x) => decode_b64(x)?,
+        None => return Err("JWK has no public key".into()),
+    };
+    let fragment: Option<String> = jwk["kid"]
+        .as_str()
+        .map(|kid| kid.rsplit('#').next().unwrap_or(kid).to_string());
+    Ok((public, fragment))
+}
+
+/// Reads a JWK to add as method of the document: the public key and the fragment of its `kid`,
+/// or the next free `key-<n>`. Fails on fragments the document can't take.
+pub fn parse_method(
+    document: &IotaDocument,
+    input: &str,
+) -> Result<(Vec<u8>, String), Box<dyn Error>> {
+    let (public, fragment) = parse(input)?;
+    let fragment: String = fragment.unwrap_or_else(|| did::free_fragment(document));
+    match did::fragment_error(document, &fragment) {
+        Some(error) => Err(error.into()),
+        None => Ok((public, fragment)),
+    }
+}
@@ -0,0 +1,72 @@
+use crate::integrity::{self, Report};
+use identity::iota::{Client, IotaDID, IotaDocument, MessageId, TangleRef};
+use rand::RngCore;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+/// Where issuer and subject documents are resolved from while verifying.
+#[derive(Clone)]
+pub enum Ledger {
+    Tangle(Client),
+    /// Documents published in this process only, for scenario runs without a node.
+    Memory(MemoryTangle),
+}
+
+impl Ledger {
+    /// Resolves the document and checks the integrity of its message chain.
+    pub async fn resolve(&self, did: &IotaDID) -> Result<(IotaDocument, Report), Box<dyn Error>> {
+        match self {
+            Ledger::Tangle(client) => Ok(integrity::resolve(client, did).await?),
+            Ledger::Memory(memory) => memory.resolve(did),
+        }
+    }
+
+    /// Resolves the document as of the integration message `message_id`.
+    pub async fn resolve_at(
+        &self,
+        did: &IotaDID,
+        message_id: &str,
+    ) -> Result<(IotaDocument, Report), Box<dyn Error>> {
+        match self {
+            Ledger::Tangle(client) => integrity::resolve_at(client, did, message_id).await,
+            Ledger::Memory(memory) => integrity::pin(memory.resolve(did)?.1, did, message_id),
+        }
+    }
+}
+
+/// Integration messages by DID, kept in memory like a Tangle nobody else reads.
+#[derive(Clone, Default)]
+pub struct MemoryTangle {
+    documents: Arc<Mutex<HashMap<String, Vec<IotaDocument>>>>,
+}
+
+impl MemoryTangle {
+    /// Integrates the signed document under a random message id, which it is given.
+    /// Updates have to point to the previous message before they are signed, as on the Tangle.
+    pub fn publish(&self, document: &mut IotaDocument) -> MessageId {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let message_id = MessageId::new(bytes);
+        document.set_message_id(message_id);
+        self.documents
+            .lock()
+            .unwrap()
+            .entry(document.id().to_string())
+            .or_default()
+            .push(document.clone());
+        message_id
+    }
+
+    pub fn resolve(&self, did: &IotaDID) -> Result<(IotaDocument, Report), Box<dyn Error>> {
+        let documents = self.documents.lock().unwrap();
+        let (document, history) = documents
+            .get(did.as_str())
+            .and_then(|versions| versions.split_last())
+            .ok_or_else(|| format!("{} was never published to the in-memory ledger", did))?;
+        Ok((
+            document.clone(),
+            integrity::check_chain(history, document, 0),
+        ))
+    }
+}
@@ -0,0 +1,94 @@
+//! The issuing and verifying demo as a library, so other examples can embed its flows.
+//!
+//! `run_app` runs the whole TUI with a config. The modules behind it, like `core` for
+//! creating identities and issuing, `verification` for verifying and `config` for the
+//! settings, can also be driven without the TUI, as the `quickstart` does.
+
+pub mod account;
+pub mod actions;
+mod app;
+pub mod armor;
+pub mod backup;
+pub mod batch;
+pub mod branding;
+pub mod bundle;
+pub mod bus;
+pub mod cache;
+pub mod cas;
+pub mod claims;
+pub mod clock;
+pub mod compare;
+pub mod compromise;
+pub mod config;
+pub mod confirm;
+pub mod consent;
+pub mod console;
+pub mod core;
+pub mod dates;
+pub mod derive;
+pub mod did;
+pub mod didcomm;
+pub mod discovery;
+pub mod editor;
+pub mod events;
+pub mod explorer;
+pub mod handle;
+pub mod history;
+pub mod home;
+pub mod hooks;
+pub mod ids;
+pub mod integrity;
+pub mod issue;
+pub mod issued;
+pub mod jcs;
+pub mod jwk;
+pub mod keys;
+pub mod ledger;
+pub mod link;
+pub mod lint;
+pub mod lock;
+pub mod mobile;
+pub mod modal;
+pub mod network;
+pub mod nodes;
+pub mod oob;
+pub mod pairing;
+pub mod palette;
+pub mod paths;
+pub mod portable;
+pub mod present;
+pub mod profile;
+pub mod proof;
+pub mod pseudonym;
+pub mod qr;
+pub mod queue;
+pub mod quickstart;
+pub mod receipts;
+pub mod redact;
+pub mod refresh;
+pub mod renderers;
+pub mod report;
+pub mod request;
+pub mod resolution;
+pub mod retry;
+pub mod revocation;
+pub mod scenario;
+pub mod seed;
+pub mod selftest;
+pub mod session;
+pub mod sharing;
+pub mod signer;
+pub mod state;
+pub mod stats;
+pub mod status;
+pub mod store;
+pub mod templates;
+pub mod toast;
+pub mod trust;
+pub mod usage;
+pub mod verification;
+pub mod verify;
+pub mod wallet;
+
+pub use app::{run_app, run_app_with, Options};
+pub use config::Config;
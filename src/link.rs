@@ -0,0 +1,119 @@
+use crate::armor::{self, Kind};
+use serde_json::Value;
+
+/// Query parameters carrying a credential to verify.
+const CREDENTIAL_PARAMS: [&str; 3] = ["vc", "credential", "jws"];
+/// Query parameters carrying an offer the holder may accept into the wallet.
+const OFFER_PARAMS: [&str; 2] = ["credential_offer", "offer"];
+/// Query parameter carrying the invitation of a device to pair with.
+const PAIR_PARAM: &str = "pair";
+
+/// What a pasted link carries, a credential (JSON, JWS or handle), an offer of one
+/// or a pairing invitation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Payload {
+    Verify(String),
+    Offer(String),
+    Pair(String),
+}
+
+/// Decodes links like `https://.../?vc=...` or `openid-credential-offer://?credential_offer=...`.
+/// Parameter values may be percent-encoded and base64 (URL safe or standard) encoded.
+/// A link without a known parameter is taken as a handle to fetch the credential from.
+/// An armored credential is unwrapped.
+pub fn decode(link: &str) -> Result<Payload, String> {
+    if armor::is_armored(link) {
+        return match armor::decode(link).map_err(|error| error.to_string())? {
+            (Kind::Credential, credential) => Ok(Payload::Verify(credential)),
+            (Kind::Presentation, _) => {
+                Err("this is a presentation, paste it with e on the Verify tab".to_string())
+            }
+        };
+    }
+    let link = link.trim();
+    if link.starts_with('{') || (!link.contains("://") && !link.contains('?')) {
+        return Ok(Payload::Verify(link.to_string()));
+    }
+    let query = match link.split_once('?') {
+        Some((_, query)) => query.split('#').next().unwrap_or_default(),
+        None => return Ok(Payload::Verify(link.to_string())),
+    };
+    for pair in query.split('&') {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = decode_value(&percent_decode(value)?);
+        if CREDENTIAL_PARAMS.contains(&name) {
+            return Ok(Payload::Verify(value));
+        }
+        if OFFER_PARAMS.contains(&name) {
+            return Ok(Payload::Offer(offered(value)));
+        }
+        if name == PAIR_PARAM {
+            return Ok(Payload::Pair(value));
+        }
+    }
+    if link.starts_with("http://") || link.starts_with("https://") {
+        Ok(Payload::Verify(link.to_string()))
+    } else {
+        Err(format!(
+            "link carries none of the parameters {}",
+            [&CREDENTIAL_PARAMS[..], &OFFER_PARAMS[..], &[PAIR_PARAM]]
+                .concat()
+                .join(", ")
+        ))
+    }
+}
+
+fn percent_decode(input: &str) -> Result<String, String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = input
+                    .get(i + 1..i + 3)
+                    .ok_or_else(|| "truncated percent escape".to_string())?;
+                decoded.push(
+                    u8::from_str_radix(hex, 16).map_err(|_| format!("invalid escape %{}", hex))?,
+                );
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).map_err(|error| error.to_string())
+}
+
+/// Unwraps base64 payloads, JSON and JWS values are returned as they are.
+fn decode_value(value: &str) -> String {
+    if value.starts_with('{') || value.matches('.').count() == 2 || value.contains("://") {
+        return value.to_string();
+    }
+    base64::decode_config(value, base64::URL_SAFE_NO_PAD)
+        .or_else(|_| base64::decode(value))
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// The credential inside an offer object, or a handle to fetch it from.
+fn offered(offer: String) -> String {
+    match serde_json::from_str::<Value>(&offer) {
+        Ok(Value::Object(object)) => match object
+            .get("credential")
+            .or_else(|| object.get("credential_uri"))
+        {
+            Some(Value::String(credential)) => credential.clone(),
+            Some(credential) => credential.to_string(),
+            None => offer,
+        },
+        _ => offer,
+    }
+}
@@ -0,0 +1,82 @@
+use identity::core::ToJson;
+use identity::credential::Credential;
+use identity::iota::IotaDocument;
+use serde_json::Value;
+
+/// A DID message beyond this no longer fits into a single Tangle message.
+const MAX_DOCUMENT_BYTES: usize = 32 * 1024;
+/// Credentials beyond this are slow to resolve and hand over, and never fit a QR.
+const MAX_CREDENTIAL_BYTES: usize = 16 * 1024;
+const MAX_METHODS: usize = 10;
+/// Levels of objects in a subject that verifiers still map to their schemas.
+const MAX_SUBJECT_DEPTH: usize = 4;
+/// Proof types the identity library signs and verifies.
+const KNOWN_PROOF_TYPES: [&str; 1] = ["JcsEd25519Signature2020"];
+
+/// Warnings about a credential before it is signed, none if it looks fine.
+pub fn credential(credential: &Credential) -> Vec<String> {
+    let json: Value = match credential.to_json_value() {
+        Ok(json) => json,
+        Err(error) => return vec![format!("the credential does not serialize: {}", error)],
+    };
+    let mut warnings = Vec::new();
+    size(&json, "credential", MAX_CREDENTIAL_BYTES, &mut warnings);
+    proof_type(&json, &mut warnings);
+    let depth = match &json["credentialSubject"] {
+        Value::Array(subjects) => subjects.iter().map(depth).max().unwrap_or_default(),
+        subject => depth(subject),
+    };
+    if depth > MAX_SUBJECT_DEPTH {
+        warnings.push(format!(
+            "the subject nests objects {} levels deep, more than {}",
+            depth, MAX_SUBJECT_DEPTH
+        ));
+    }
+    warnings
+}
+
+/// Warnings about a DID document before it is published with `adding` more methods.
+pub fn document(document: &IotaDocument, adding: usize) -> Vec<String> {
+    let json: Value = match document.to_json_value() {
+        Ok(json) => json,
+        Err(error) => return vec![format!("the document does not serialize: {}", error)],
+    };
+    let mut warnings = Vec::new();
+    size(&json, "document", MAX_DOCUMENT_BYTES, &mut warnings);
+    proof_type(&json, &mut warnings);
+    let methods = document.methods().count() + adding;
+    if methods > MAX_METHODS {
+        warnings.push(format!(
+            "the document has {} verification methods, more than {}",
+            methods, MAX_METHODS
+        ));
+    }
+    warnings
+}
+
+fn size(json: &Value, what: &str, limit: usize, warnings: &mut Vec<String>) {
+    let bytes = json.to_string().len();
+    if bytes > limit {
+        warnings.push(format!(
+            "the {} has {} bytes, more than {}",
+            what, bytes, limit
+        ));
+    }
+}
+
+fn proof_type(json: &Value, warnings: &mut Vec<String>) {
+    if let Some(type_) = json["proof"]["type"].as_str() {
+        if !KNOWN_PROOF_TYPES.contains(&type_) {
+            warnings.push(format!("the proof type {} is unknown", type_));
+        }
+    }
+}
+
+/// Levels of nested objects, a flat subject is one level deep.
+fn depth(value: &Value) -> usize {
+    match value {
+        Value::Object(object) => 1 + object.values().map(depth).max().unwrap_or_default(),
+        Value::Array(values) => values.iter().map(depth).max().unwrap_or_default(),
+        _ => 0,
+    }
+}
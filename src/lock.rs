@@ -0,0 +1,176 @@
+use crate::handle;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::time::{Duration, Instant};
+
+/// A PIN is short by design, it only protects screens, not keys.
+const PIN_LENGTH: std::ops::RangeInclusive<usize> = 4..=8;
+const MAX_PASSWORD_LENGTH: usize = 128;
+
+/// Compares the input to the secret in constant time, the time taken tells nothing about
+/// how many characters matched.
+pub fn matches(input: &str, secret: &str) -> bool {
+    let (input, secret) = (input.as_bytes(), secret.as_bytes());
+    input.len() == secret.len()
+        && input
+            .iter()
+            .zip(secret)
+            .fold(0u8, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// Hash of the PIN as kept in `pin_hash` of the config.
+pub fn hash(pin: &str) -> Result<String, String> {
+    if !PIN_LENGTH.contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!(
+            "a PIN has {} to {} digits",
+            PIN_LENGTH.start(),
+            PIN_LENGTH.end()
+        ));
+    }
+    Ok(handle::hash(&format!("iota-identity-examples-pin:{}", pin)))
+}
+
+/// What unlocks the screens.
+enum Secret {
+    /// Hash of the PIN.
+    Pin(String),
+    /// The Stronghold password, for holders without a PIN.
+    Password(String),
+}
+
+/// UX level lock of the wallet and signing screens.
+/// Disabled without a PIN or password, otherwise locked on start, on demand, after inactivity
+/// and once the unlocked session ran out.
+pub struct Lock {
+    secret: Option<Secret>,
+    timeout: Duration,
+    /// Longest an unlocked session lasts however active it is, `None` for no limit.
+    session: Option<Duration>,
+    last_activity: Instant,
+    unlocked_at: Instant,
+    locked: bool,
+    pub input: String,
+    pub error: Option<String>,
+}
+
+impl Lock {
+    /// A lock opened by the PIN, or by the password if no PIN is configured.
+    pub fn new(
+        pin_hash: Option<String>,
+        password: Option<String>,
+        timeout: Duration,
+        session: Option<Duration>,
+    ) -> Self {
+        let secret: Option<Secret> = pin_hash.map(Secret::Pin).or(password.map(Secret::Password));
+        Lock {
+            locked: secret.is_some(),
+            secret,
+            timeout,
+            session,
+            last_activity: Instant::now(),
+            unlocked_at: Instant::now(),
+            input: String::new(),
+            error: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.secret.is_some()
+    }
+
+    /// What the lock screen asks for.
+    pub fn label(&self) -> &'static str {
+        match self.secret {
+            Some(Secret::Password(_)) => "Password",
+            _ => "PIN",
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn lock(&mut self) {
+        if self.secret.is_some() {
+            self.locked = true;
+            self.input.clear();
+        }
+    }
+
+    /// Records user activity, called for every key press.
+    pub fn touch(&mut self) {
+        self.last_activity = Instant::now();
+    }
+
+    /// Locks once there was no activity for the configured time or the session ran out.
+    pub fn check_idle(&mut self) {
+        if self.locked {
+            return;
+        }
+        if self.last_activity.elapsed() >= self.timeout {
+            self.lock();
+        } else if self
+            .session
+            .map_or(false, |session| self.unlocked_at.elapsed() >= session)
+        {
+            self.lock();
+            self.error = Some(format!(
+                "The session timed out, enter the {} again",
+                self.label()
+            ));
+        }
+    }
+
+    /// Time until the screens lock unless the lock is disabled or already locked,
+    /// whichever of inactivity and session length comes first.
+    pub fn remaining(&self) -> Option<Duration> {
+        if self.secret.is_none() || self.locked {
+            return None;
+        }
+        let idle: Duration = self.timeout.saturating_sub(self.last_activity.elapsed());
+        Some(match self.session {
+            Some(session) => idle.min(session.saturating_sub(self.unlocked_at.elapsed())),
+            None => idle,
+        })
+    }
+
+    /// Collects the PIN or password, returns true once it unlocked.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match (key.code, &self.secret) {
+            (KeyCode::Char(c), Some(Secret::Pin(_)))
+                if c.is_ascii_digit() && self.input.len() < *PIN_LENGTH.end() =>
+            {
+                self.input.push(c)
+            }
+            (KeyCode::Char(c), Some(Secret::Password(_)))
+                if self.input.len() < MAX_PASSWORD_LENGTH =>
+            {
+                self.input.push(c)
+            }
+            (KeyCode::Backspace, _) => {
+                self.input.pop();
+            }
+            (KeyCode::Enter, Some(secret)) => {
+                let unlocks: bool = match secret {
+                    Secret::Pin(pin_hash) => hash(&self.input).ok().as_ref() == Some(pin_hash),
+                    Secret::Password(password) => matches(&self.input, password),
+                };
+                self.input.clear();
+                if unlocks {
+                    self.locked = false;
+                    self.error = None;
+                    self.unlocked_at = Instant::now();
+                    self.last_activity = Instant::now();
+                    return true;
+                }
+                self.error = Some(format!("Wrong {}", self.label()));
+            }
+            _ => {}
+        }
+        false
+    }
+
+    pub fn masked(&self) -> String {
+        "*".repeat(self.input.len())
+    }
+}
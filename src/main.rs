@@ -1,264 +1,128 @@
-use crossterm::{
-    event::{self, Event as CEvent, KeyCode},
-    terminal::{disable_raw_mode, enable_raw_mode},
+use identity::iota::IotaDID;
+use iota_identity_examples::proof::ProofFormat;
+use iota_identity_examples::{
+    batch, clock, compare, compromise, config, core, lock, nodes, paths, quickstart, scenario,
+    seed, selftest, store, Options,
 };
-use identity::account::Result;
-use identity::credential::Credential;
-use identity::crypto::KeyPair;
-use identity::iota::{IotaDocument, Receipt};
-use qrcode::render::unicode;
-use qrcode::QrCode;
-use std::io;
-use std::sync::mpsc;
-use std::thread;
-use std::time::{Duration, Instant};
-use tui::{
-    backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
-    text::{Span, Spans},
-    widgets::{Block, BorderType, Borders, ListState, Paragraph, Tabs, Wrap},
-    Terminal,
-};
-
-mod did;
-mod issue;
-
-enum Event<I> {
-    Input(I),
-    Tick,
-}
-
-#[derive(Copy, Clone, Debug)]
-enum MenuItem {
-    Home,
-    Issue,
-    Verify,
-}
-
-impl From<MenuItem> for usize {
-    fn from(input: MenuItem) -> usize {
-        match input {
-            MenuItem::Home => 0,
-            MenuItem::Issue => 1,
-            MenuItem::Verify => 2,
-        }
-    }
-}
+use std::env;
+use std::fs;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let (issuer_doc, issuer_key, _): (IotaDocument, KeyPair, Receipt) = did::create_did().await?;
-
-    println!("[Example] Local Document = {:#?}", issuer_doc);
-
-    let did_id = issuer_doc.id().as_str();
-    println!("{}", did_id);
-    let code = QrCode::new(did_id).unwrap();
-    let image = code
-        .render::<unicode::Dense1x2>()
-        .dark_color(unicode::Dense1x2::Light)
-        .light_color(unicode::Dense1x2::Dark)
-        .build();
-    println!("{}", image);
-
-    // Create a signed DID Document/KeyPair for the credential subject (see create_did.rs).
-    let (subject_doc, _, _): (IotaDocument, KeyPair, Receipt) = did::create_did().await?;
-
-    // Create an unsigned Credential with claims about `subject` specified by `issuer`.
-    let mut credential: Credential = issue::issue_degree(&issuer_doc, &subject_doc)?;
-    // Sign the Credential with the issuer's private key.
-    issuer_doc.sign_data(&mut credential, issuer_key.private())?;
-
-    let credential_str = credential.to_string();
-    let vc: &str = credential_str.as_str();
-
-    let code = QrCode::new(credential.to_string()).unwrap();
-    let image = code
-        .render::<unicode::Dense1x2>()
-        .dark_color(unicode::Dense1x2::Light)
-        .light_color(unicode::Dense1x2::Dark)
-        .build();
-    println!("{}", image);
-
-    println!("[Example] Tangle Document = {:#?}", issuer_doc);
-
-    enable_raw_mode().expect("can run in raw mode");
-
-    let (tx, rx) = mpsc::channel();
-    let tick_rate = Duration::from_millis(200);
-    thread::spawn(move || {
-        let mut last_tick = Instant::now();
-        loop {
-            let timeout = tick_rate
-                .checked_sub(last_tick.elapsed())
-                .unwrap_or_else(|| Duration::from_secs(0));
-
-            if event::poll(timeout).expect("poll works") {
-                if let CEvent::Key(key) = event::read().expect("can read events") {
-                    tx.send(Event::Input(key)).expect("can send events");
-                }
-            }
+    // Kiosk-style runs on shared machines leave nothing behind.
+    let ephemeral: bool = env::args().any(|arg| arg == "--ephemeral");
+    let config = if ephemeral {
+        config::load().ephemeral(&paths::ephemeral_dir())
+    } else {
+        config::load()
+    };
+    clock::set_offset(clock::parse_offset(&config.clock_offset)?);
+
+    if let Some(pin) = env::args().skip_while(|arg| arg != "--hash-pin").nth(1) {
+        println!("{}", lock::hash(&pin)?);
+        return Ok(());
+    }
 
-            if last_tick.elapsed() >= tick_rate {
-                if let Ok(_) = tx.send(Event::Tick) {
-                    last_tick = Instant::now();
-                }
-            }
+    if env::args().nth(1).as_deref() == Some("self-test") {
+        let node: Option<String> = env::args().skip_while(|arg| arg != "--node").nth(1);
+        if selftest::run(&config, node).await {
+            return Ok(());
         }
-    });
-
-    let stdout = io::stdout();
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-    terminal.clear()?;
-
-    let menu_titles = vec!["Home", "Issue", "Verify"];
-    let mut active_menu_item = MenuItem::Home;
-    let mut pet_list_state = ListState::default();
-    pet_list_state.select(Some(0));
-
-    loop {
-        terminal.draw(|rect| {
-            let size = rect.size();
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .margin(2)
-                .constraints(
-                    [
-                        Constraint::Length(3),
-                        Constraint::Min(2),
-                        Constraint::Length(3),
-                    ]
-                    .as_ref(),
-                )
-                .split(size);
-
-            let copyright = Paragraph::new("Do what you want.")
-                .style(Style::default().fg(Color::LightCyan))
-                .alignment(Alignment::Center)
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .style(Style::default().fg(Color::White))
-                        .title("Copyright")
-                        .border_type(BorderType::Plain),
-                );
-
-            let menu = menu_titles
-                .iter()
-                .map(|t| {
-                    let (first, rest) = t.split_at(1);
-                    Spans::from(vec![
-                        Span::styled(
-                            first,
-                            Style::default()
-                                .fg(Color::Yellow)
-                                .add_modifier(Modifier::UNDERLINED),
-                        ),
-                        Span::styled(rest, Style::default().fg(Color::White)),
-                    ])
-                })
-                .collect();
-
-            let tabs = Tabs::new(menu)
-                .select(active_menu_item.into())
-                .block(Block::default().title("Menu").borders(Borders::ALL))
-                .style(Style::default().fg(Color::White))
-                .highlight_style(Style::default().fg(Color::Yellow))
-                .divider(Span::raw("|"));
+        return Err("self-test failed".into());
+    }
 
-            rect.render_widget(tabs, chunks[0]);
-            match active_menu_item {
-                MenuItem::Home => rect.render_widget(render_home(), chunks[1]),
-                MenuItem::Issue => rect.render_widget(render_issue(did_id, vc), chunks[1]),
-                MenuItem::Verify => rect.render_widget(render_verify(), chunks[1]),
-            }
-            rect.render_widget(copyright, chunks[2]);
-        })?;
+    let seed: Option<String> = env::args().skip_while(|arg| arg != "--seed").nth(1);
+    if seed.is_some() {
+        seed::warn();
+    }
 
-        match rx.recv()? {
-            Event::Input(event) => match event.code {
-                KeyCode::Char('q') => {
-                    disable_raw_mode()?;
-                    terminal.show_cursor()?;
-                    break;
-                }
-                KeyCode::Char('h') => active_menu_item = MenuItem::Home,
-                KeyCode::Char('i') => active_menu_item = MenuItem::Issue,
-                KeyCode::Char('v') => active_menu_item = MenuItem::Verify,
-                _ => {}
-            },
-            Event::Tick => {}
+    if env::args().nth(1).as_deref() == Some("create-did") {
+        let count: usize = match env::args().skip_while(|arg| arg != "--count").nth(1) {
+            Some(count) => count.parse()?,
+            None => 1,
+        };
+        let parallel: usize = match env::args().skip_while(|arg| arg != "--parallel").nth(1) {
+            Some(parallel) => parallel.parse()?,
+            None => batch::DEFAULT_PARALLEL,
+        };
+        let service = core::Service::new(&config, nodes::from_urls(&config.nodes));
+        let created = batch::create_dids(
+            &service,
+            store::open(&config),
+            &config.export_dir,
+            count,
+            parallel,
+            seed.as_deref(),
+        )
+        .await?;
+        if created == count {
+            return Ok(());
         }
+        return Err(format!("{} of {} identities failed", count - created, count).into());
     }
 
-    Ok(())
-}
-
-fn render_home<'a>() -> Paragraph<'a> {
-    let home = Paragraph::new(vec![
-        Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::raw("Welcome")]),
-        Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::raw("to")]),
-        Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::styled(
-            "SSI @ IOTA",
-            Style::default().fg(Color::LightBlue),
-        )]),
-        Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::raw("Press q to quit.")]),
-    ])
-    .alignment(Alignment::Center)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .style(Style::default().fg(Color::White))
-            .title("Home")
-            .border_type(BorderType::Plain),
-    );
-    home
-}
+    if env::args().nth(1).as_deref() == Some("compare-nodes") {
+        let did = IotaDID::parse(
+            env::args()
+                .nth(2)
+                .ok_or("usage: compare-nodes <did>")?
+                .as_str(),
+        )?;
+        let results = compare::resolve_all(
+            &config.nodes,
+            &did,
+            Duration::from_secs(config.verification_timeout),
+        )
+        .await;
+        println!("{}", compare::summary(&did, &results));
+        if compare::divergence(&results).is_empty()
+            && results.iter().any(|result| result.resolved.is_ok())
+        {
+            return Ok(());
+        }
+        return Err("the nodes do not agree on the DID".into());
+    }
 
-fn render_issue<'a>(did: &'a str, credential: &'a str) -> Paragraph<'a> {
-    let code = QrCode::new(credential.to_string()).unwrap();
-    let image: String = code
-        .render::<unicode::Dense1x2>()
-        .dark_color(unicode::Dense1x2::Light)
-        .light_color(unicode::Dense1x2::Dark)
-        .build();
+    if env::args().nth(1).as_deref() == Some("compromise") {
+        if compromise::run(&config, seed.as_deref()).await? {
+            return Ok(());
+        }
+        return Err("a verifier decided otherwise than expected".into());
+    }
 
-    let text = tui::text::Text::from(format!("{}\nissued by {}", image, did));
+    if env::args().nth(1).as_deref() == Some("scenario") {
+        let path: String = env::args()
+            .nth(2)
+            .ok_or("usage: scenario <file.json> [--seed <text>] [--tangle]")?;
+        let tangle: bool = env::args().any(|arg| arg == "--tangle");
+        let failed: usize =
+            scenario::run(&config, &scenario::load(&path)?, seed.as_deref(), tangle).await?;
+        if failed == 0 {
+            return Ok(());
+        }
+        return Err(format!("{} scenario assertions failed", failed).into());
+    }
 
-    let issue = Paragraph::new(text)
-        .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::White))
-                .title("Issue")
-                .border_type(BorderType::Plain),
-        )
-        .wrap(Wrap { trim: true });
-    issue
-}
+    if env::args().any(|arg| arg == "--quickstart") {
+        let format: ProofFormat = match env::args().skip_while(|arg| arg != "--proof").nth(1) {
+            Some(format) => format.parse()?,
+            None => config.proof_format,
+        };
+        if quickstart::run(&config, format, seed.as_deref()).await? {
+            return Ok(());
+        }
+        return Err("quickstart did not verify".into());
+    }
 
-fn render_verify<'a>() -> Paragraph<'a> {
-    let verify = Paragraph::new(vec![
-        Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::raw("Verify")]),
-        Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::raw("Press q to quit.")]),
-    ])
-    .alignment(Alignment::Center)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .style(Style::default().fg(Color::White))
-            .title("Verify")
-            .border_type(BorderType::Plain),
-    );
-    verify
+    let options = Options {
+        seed,
+        profile: env::args().skip_while(|arg| arg != "--profile").nth(1),
+    };
+    let result = iota_identity_examples::run_app_with(config, options).await;
+    if ephemeral {
+        // Inbox, HTTP store and pseudonyms were only kept for this run.
+        let _ = fs::remove_dir_all(paths::ephemeral_dir());
+    }
+    result
 }
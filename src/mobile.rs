@@ -0,0 +1,230 @@
+use crate::branding::{self, Display};
+use crate::palette::centered_rect;
+use identity::credential::Credential;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Characters of the title a card in a typical mobile wallet fits before cutting it off.
+const TITLE_LENGTH: usize = 28;
+/// Fields a card shows on its front, the others only in the details.
+const FRONT_FIELDS: usize = 2;
+/// Contrast ratio WCAG asks of normal text.
+const MIN_CONTRAST: f64 = 4.5;
+
+/// The credential mapped onto the card of a typical mobile wallet.
+pub struct MobileCard {
+    pub title: String,
+    pub subtitle: String,
+    pub background: String,
+    pub foreground: String,
+    pub logo: Option<String>,
+    /// Shown on the front of the card.
+    pub front: Vec<(String, String)>,
+    /// Only shown when the card is opened.
+    pub details: Vec<(String, String)>,
+    /// What would make the card look better, for the issuer to fix before issuing.
+    pub hints: Vec<String>,
+}
+
+/// `UniversityDegreeCredential` as `University Degree`.
+fn humanize(type_: &str) -> String {
+    let name: &str = type_.strip_suffix("Credential").unwrap_or(type_);
+    let mut words = String::new();
+    for c in name.chars() {
+        if c.is_uppercase() && !words.is_empty() && !words.ends_with(' ') {
+            words.push(' ');
+        }
+        words.push(c);
+    }
+    words
+}
+
+/// Relative luminance of a `#rrggbb` color.
+fn luminance(hex: &str) -> Option<f64> {
+    let hex = hex.trim().trim_start_matches('#');
+    let channel = |i: usize| -> Option<f64> {
+        let c = u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()? as f64 / 255.0;
+        Some(if c <= 0.039_28 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        })
+    };
+    if hex.len() != 6 {
+        return None;
+    }
+    Some(0.2126 * channel(0)? + 0.7152 * channel(2)? + 0.0722 * channel(4)?)
+}
+
+fn contrast(a: &str, b: &str) -> Option<f64> {
+    let (a, b) = (luminance(a)?, luminance(b)?);
+    Some((a.max(b) + 0.05) / (a.min(b) + 0.05))
+}
+
+impl MobileCard {
+    /// Maps the credential and the claim `rows` of its detail renderer onto the card.
+    pub fn new(credential: &Credential, rows: Vec<(String, String)>) -> Self {
+        let display: Option<Display> = branding::of(credential);
+        let mut hints: Vec<String> = Vec::new();
+        let type_: String = credential.types.iter().last().cloned().unwrap_or_default();
+        let mut title: String = humanize(&type_);
+        if title.chars().count() > TITLE_LENGTH {
+            hints.push(format!(
+                "The title {} is cut to {} characters on the card",
+                title, TITLE_LENGTH
+            ));
+            title = title.chars().take(TITLE_LENGTH - 1).collect::<String>() + "…";
+        }
+        let subtitle: String = match &display {
+            Some(display) => display.name.clone(),
+            None => {
+                hints.push(
+                    "No display metadata: wallets show the issuer DID and their default colors"
+                        .to_string(),
+                );
+                credential.issuer.url().to_string()
+            }
+        };
+        let background: String = display
+            .as_ref()
+            .and_then(|display| display.background_color.clone())
+            .unwrap_or_else(|| "#ffffff".to_string());
+        let foreground: String = display
+            .as_ref()
+            .and_then(|display| display.text_color.clone())
+            .unwrap_or_else(|| "#000000".to_string());
+        for color in [&background, &foreground].iter() {
+            if luminance(color).is_none() {
+                hints.push(format!("{} is no #rrggbb color, wallets ignore it", color));
+            }
+        }
+        if let Some(ratio) =
+            contrast(&background, &foreground).filter(|ratio| *ratio < MIN_CONTRAST)
+        {
+            hints.push(format!(
+                "Text on the background has a contrast of {:.1}:1, below the {}:1 readable text needs",
+                ratio, MIN_CONTRAST
+            ));
+        }
+        let logo: Option<String> = match display.and_then(|display| display.logo) {
+            Some(logo) => {
+                if logo.alt_text.is_none() {
+                    hints.push("The logo has no alt text for screen readers".to_string());
+                }
+                Some(logo.alt_text.unwrap_or(logo.url))
+            }
+            None => None,
+        };
+        let mut front: Vec<(String, String)> = rows;
+        let details: Vec<(String, String)> = front.split_off(FRONT_FIELDS.min(front.len()));
+        if !details.is_empty() {
+            hints.push(format!(
+                "{} more fields only show once the card is opened, the renderer orders them",
+                details.len()
+            ));
+        }
+        if credential.expiration_date.is_none() {
+            hints.push("Without an expiration date wallets show no validity".to_string());
+        }
+        MobileCard {
+            title,
+            subtitle,
+            background,
+            foreground,
+            logo,
+            front,
+            details,
+            hints,
+        }
+    }
+
+    /// The card at phone width, its details and the hints below, over the screen like a modal.
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>, area: Rect, hint: &str) {
+        let area = centered_rect(45, 90, area);
+        let style = Style::default()
+            .bg(branding::color(&self.background).unwrap_or(Color::White))
+            .fg(branding::color(&self.foreground).unwrap_or(Color::Black));
+        let mut card: Vec<Spans> = vec![
+            Spans::from(Span::styled(
+                self.title.clone(),
+                style.add_modifier(Modifier::BOLD),
+            )),
+            Spans::from(Span::styled(self.subtitle.clone(), style)),
+            Spans::from(Span::raw("")),
+        ];
+        for (label, value) in &self.front {
+            card.push(Spans::from(vec![
+                Span::styled(
+                    format!("{} ", label.to_uppercase()),
+                    style.add_modifier(Modifier::DIM),
+                ),
+                Span::styled(value.clone(), style.add_modifier(Modifier::BOLD)),
+            ]));
+        }
+        if let Some(logo) = &self.logo {
+            card.push(Spans::from(Span::styled(
+                format!("[{}]", logo),
+                style.add_modifier(Modifier::ITALIC),
+            )));
+        }
+        let mut below: Vec<Spans> = self
+            .details
+            .iter()
+            .map(|(label, value)| Spans::from(format!("{}: {}", label, value)))
+            .collect();
+        below.push(Spans::from(""));
+        below.extend(self.hints.iter().map(|hint| {
+            Spans::from(Span::styled(
+                format!("! {}", hint),
+                Style::default().fg(Color::LightYellow),
+            ))
+        }));
+        below.push(Spans::from(""));
+        below.push(Spans::from(Span::styled(
+            hint.to_string(),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )));
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(card.len() as u16 + 2),
+                Constraint::Min(2),
+            ])
+            .split(area);
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(card)
+                .style(style)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .style(style)
+                        .title("Wallet card")
+                        .border_type(BorderType::Rounded),
+                )
+                .wrap(Wrap { trim: true }),
+            rows[0],
+        );
+        f.render_widget(
+            Paragraph::new(below)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .style(Style::default().fg(Color::White))
+                        .title("Details")
+                        .border_type(BorderType::Plain),
+                )
+                .wrap(Wrap { trim: false }),
+            rows[1],
+        );
+    }
+}
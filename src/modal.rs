@@ -0,0 +1,71 @@
+use crate::palette::centered_rect;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Asks for the prompt input in a modal that spells out what confirming means.
+pub fn render<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    title: &str,
+    warning: &str,
+    label: &str,
+    input: &str,
+) {
+    let area = centered_rect(60, 40, area);
+    let text = vec![
+        Spans::from(vec![Span::styled(
+            warning.to_string(),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )]),
+        Spans::from(vec![Span::raw("")]),
+        Spans::from(vec![Span::raw(format!("{}: {}", label, input))]),
+        Spans::from(vec![Span::raw("")]),
+        Spans::from(vec![Span::raw("enter confirms, esc cancels")]),
+    ];
+    let modal = Paragraph::new(text).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title(title.to_string())
+            .border_type(BorderType::Double),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(modal, area);
+}
+
+/// Shows a document to confirm before acting on it.
+pub fn render_document<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    title: &str,
+    document: &str,
+    hint: &str,
+) {
+    let area = centered_rect(80, 90, area);
+    let mut text: Vec<Spans> = document
+        .lines()
+        .map(|line| Spans::from(vec![Span::raw(line.to_string())]))
+        .collect();
+    text.push(Spans::from(vec![Span::raw("")]));
+    text.push(Spans::from(vec![Span::styled(
+        hint.to_string(),
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    )]));
+    let modal = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title(title.to_string())
+            .border_type(BorderType::Double),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(modal, area);
+}
@@ -0,0 +1,93 @@
+use crate::proof;
+use identity::core::{decode_b64, FromJson};
+use identity::credential::Credential;
+use identity::iota::{Client, IotaDID, Network};
+use identity::prelude::*;
+use std::fmt;
+
+/// A DID living on another network than the client is connected to.
+#[derive(Clone, Debug)]
+pub struct Mismatch {
+    pub did: String,
+    pub did_network: Network,
+    pub client_network: Network,
+    /// Whether resolution switched to the network of the DID.
+    pub switched: bool,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.switched {
+            write!(
+                f,
+                "{} belongs to the {} network, resolved there instead of on {}",
+                self.did,
+                self.did_network.name(),
+                self.client_network.name()
+            )
+        } else {
+            write!(
+                f,
+                "{} belongs to the {} network but the client is connected to {}, enable auto_switch_network to resolve it there",
+                self.did,
+                self.did_network.name(),
+                self.client_network.name()
+            )
+        }
+    }
+}
+
+/// Compares the network of the DID with the one of the client.
+pub fn check(client: &Client, did: &IotaDID) -> Option<Mismatch> {
+    let did_network = Network::from_did(did);
+    let client_network = client.network();
+    if did_network == client_network {
+        return None;
+    }
+    Some(Mismatch {
+        did: did.to_string(),
+        did_network,
+        client_network,
+        switched: false,
+    })
+}
+
+/// Returns a client able to resolve the DID along with the mismatch if there is one.
+/// With `auto_switch` a client for the network of the DID replaces the configured one,
+/// otherwise the configured client is returned and the caller reports the mismatch.
+pub async fn client_for(
+    client: Client,
+    did: Option<&IotaDID>,
+    auto_switch: bool,
+) -> Result<(Client, Option<Mismatch>)> {
+    let mismatch = match did.and_then(|did| check(&client, did)) {
+        Some(mismatch) => mismatch,
+        None => return Ok((client, None)),
+    };
+    if !auto_switch {
+        return Ok((client, Some(mismatch)));
+    }
+    let switched: Client = Client::builder()
+        .network(mismatch.did_network)
+        .build()
+        .await?;
+    Ok((
+        switched,
+        Some(Mismatch {
+            switched: true,
+            ..mismatch
+        }),
+    ))
+}
+
+/// The issuer DID of a credential given as JSON or compact JWS, without verifying anything.
+pub fn issuer_did(input: &str) -> Option<IotaDID> {
+    let issuer: String = if proof::is_jws(input) {
+        let payload = decode_b64(input.trim().split('.').nth(1)?).ok()?;
+        let payload: serde_json::Value = serde_json::Value::from_json_slice(&payload).ok()?;
+        payload["iss"].as_str()?.to_string()
+    } else {
+        Credential::from_json(input).ok()?.issuer.url().to_string()
+    };
+    IotaDID::parse(issuer).ok()
+}
@@ -0,0 +1,166 @@
+use futures::future::join_all;
+use identity::iota::{Client, Network};
+use identity::prelude::*;
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+#[derive(Clone, Debug)]
+pub struct Node {
+    pub url: String,
+    pub enabled: bool,
+    /// Pinned by hand, requests go to this node regardless of its latency.
+    pub pinned: bool,
+    pub health: Option<Health>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Health {
+    pub healthy: bool,
+    pub synced: bool,
+    pub latency: Duration,
+    pub milestone: u32,
+}
+
+pub type Nodes = Arc<Mutex<Vec<Node>>>;
+
+#[derive(Deserialize)]
+struct InfoResponse {
+    data: Info,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Info {
+    is_healthy: bool,
+    latest_milestone_index: u32,
+    confirmed_milestone_index: u32,
+}
+
+pub fn from_urls(urls: &[String]) -> Nodes {
+    let nodes = urls
+        .iter()
+        .map(|url| Node {
+            url: url.clone(),
+            enabled: true,
+            pinned: false,
+            health: None,
+        })
+        .collect();
+    Arc::new(Mutex::new(nodes))
+}
+
+/// Asks the node for its info and measures how long the answer took.
+pub async fn check(http: &reqwest::Client, url: &str) -> Health {
+    let start = Instant::now();
+    let info = match http.get(format!("{}/api/v1/info", url)).send().await {
+        Ok(response) => response.json::<InfoResponse>().await.ok(),
+        Err(_) => None,
+    };
+    let latency = start.elapsed();
+
+    match info {
+        Some(InfoResponse { data }) => Health {
+            healthy: data.is_healthy,
+            synced: data.latest_milestone_index == data.confirmed_milestone_index,
+            latency,
+            milestone: data.confirmed_milestone_index,
+        },
+        None => Health {
+            healthy: false,
+            synced: false,
+            latency,
+            milestone: 0,
+        },
+    }
+}
+
+/// Polls the health of all configured nodes at once in the background. A node that does not
+/// answer within the interval counts as unhealthy, so one slow node can't hold up the others.
+pub fn spawn_monitor(nodes: Nodes, interval: Duration) -> JoinHandle<()> {
+    let http = reqwest::Client::builder()
+        .timeout(interval)
+        .build()
+        .expect("can build an HTTP client");
+    tokio::spawn(async move {
+        loop {
+            let urls: Vec<String> = nodes
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|node| node.url.clone())
+                .collect();
+            let healths: Vec<Health> = join_all(urls.iter().map(|url| check(&http, url))).await;
+            for (url, health) in urls.iter().zip(healths) {
+                if let Some(node) = nodes
+                    .lock()
+                    .unwrap()
+                    .iter_mut()
+                    .find(|node| &node.url == url)
+                {
+                    node.health = Some(health);
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+    })
+}
+
+pub fn toggle(nodes: &Nodes, index: usize) {
+    if let Some(node) = nodes.lock().unwrap().get_mut(index) {
+        node.enabled = !node.enabled;
+    }
+}
+
+/// Pins the node, or releases the pin if it is pinned already. Only one node is pinned at a time.
+pub fn pin(nodes: &Nodes, index: usize) {
+    let mut nodes = nodes.lock().unwrap();
+    let pinned = nodes.get(index).map_or(false, |node| !node.pinned);
+    for (i, node) in nodes.iter_mut().enumerate() {
+        node.pinned = pinned && i == index;
+    }
+}
+
+/// Enabled nodes requests go to: the pinned node if it is enabled, otherwise the fastest
+/// healthy and synced node. Before the first health check all enabled nodes are used.
+pub fn route(nodes: &Nodes) -> Vec<String> {
+    let nodes = nodes.lock().unwrap();
+    let enabled: Vec<&Node> = nodes.iter().filter(|node| node.enabled).collect();
+    if let Some(pinned) = enabled.iter().find(|node| node.pinned) {
+        return vec![pinned.url.clone()];
+    }
+    let fastest = enabled
+        .iter()
+        .filter_map(|node| Some((node, node.health.as_ref()?)))
+        .filter(|(_, health)| health.healthy && health.synced)
+        .min_by_key(|(_, health)| health.latency)
+        .map(|(node, _)| node.url.clone());
+    match fastest {
+        Some(url) => vec![url],
+        None => enabled.iter().map(|node| node.url.clone()).collect(),
+    }
+}
+
+/// Builds a client that only talks to the node, to compare what the nodes answer.
+pub async fn single(url: &str) -> Result<Client> {
+    let client: Client = Client::builder()
+        .network(Network::Mainnet)
+        .nodes(&[url])?
+        .build()
+        .await?;
+    Ok(client)
+}
+
+/// Builds a client that talks to the nodes requests are routed to.
+pub async fn client(nodes: &Nodes) -> Result<Client> {
+    let urls: Vec<String> = route(nodes);
+    let urls: Vec<&str> = urls.iter().map(String::as_str).collect();
+
+    let client: Client = Client::builder()
+        .network(Network::Mainnet)
+        .nodes(&urls)?
+        .build()
+        .await?;
+    Ok(client)
+}
@@ -0,0 +1,130 @@
+use crate::handle;
+use identity::core::{FromJson, Timestamp, ToJson, Value};
+use identity::credential::Presentation;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Property of the presentation echoing the challenge of the request.
+const CHALLENGE: &str = "challenge";
+
+/// A verifier asking out of band, e.g. by QR code, for a presentation of some credential types.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationRequest {
+    pub id: String,
+    pub verifier: String,
+    /// Credential types the presentation has to contain, empty accepts any.
+    pub credential_types: Vec<String>,
+    /// Nonce the holder echoes so an old presentation can't be replayed.
+    pub challenge: String,
+    pub created: Timestamp,
+}
+
+impl PresentationRequest {
+    pub fn new(verifier: &str, credential_types: Vec<String>) -> Self {
+        let created = Timestamp::now_utc();
+        let challenge = handle::hash(&format!("{}{}{:?}", verifier, created, credential_types));
+        PresentationRequest {
+            id: challenge[..16].to_string(),
+            verifier: verifier.to_string(),
+            credential_types,
+            challenge: challenge[16..48].to_string(),
+            created,
+        }
+    }
+
+    /// Problems of the presentation regarding this request, empty if it answers it.
+    pub fn check(&self, presentation: &Presentation) -> Vec<String> {
+        let mut problems = Vec::new();
+        match presentation.properties.get(CHALLENGE) {
+            Some(Value::String(challenge)) if *challenge == self.challenge => {}
+            Some(_) => problems.push("the challenge does not match the request".to_string()),
+            None => problems.push("the presentation carries no challenge".to_string()),
+        }
+        for credential_type in &self.credential_types {
+            let presented = presentation.verifiable_credential.iter().any(|credential| {
+                credential
+                    .types
+                    .iter()
+                    .any(|type_| type_ == credential_type)
+            });
+            if !presented {
+                problems.push(format!("no {} was presented", credential_type));
+            }
+        }
+        problems
+    }
+}
+
+/// Adds the challenge of the request to the presentation, before the holder signs it.
+pub fn answer(presentation: &mut Presentation, request: &PresentationRequest) {
+    presentation.properties.insert(
+        CHALLENGE.to_string(),
+        Value::String(request.challenge.clone()),
+    );
+}
+
+pub fn responses_dir(inbox: &str) -> PathBuf {
+    Path::new(inbox).join("presentations")
+}
+
+/// Delivers the presentation for the request through the file inbox.
+pub fn respond(
+    inbox: &str,
+    request: &PresentationRequest,
+    presentation: &Presentation,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let dir = responses_dir(inbox);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.json", request.id));
+    fs::write(&path, presentation.to_json_pretty()?)?;
+    Ok(path)
+}
+
+/// Keeps a presentation sent without a request, like one from another instance on the LAN.
+pub fn keep_unsolicited(
+    inbox: &str,
+    presentation: &Presentation,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let dir = responses_dir(inbox).join("unsolicited");
+    fs::create_dir_all(&dir)?;
+    let json = presentation.to_json_pretty()?;
+    let path = dir.join(format!("{}.json", handle::digest(&json)));
+    fs::write(&path, json)?;
+    Ok(path)
+}
+
+/// The oldest presentation sent without a request, taken out of the inbox.
+pub fn receive_unsolicited(inbox: &str) -> Result<Option<Presentation>, Box<dyn Error>> {
+    let oldest = fs::read_dir(responses_dir(inbox).join("unsolicited"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+                .min()
+        })
+        .unwrap_or_default();
+    let path = match oldest {
+        Some((_, path)) => path,
+        None => return Ok(None),
+    };
+    let presentation = Presentation::from_json(&fs::read_to_string(&path)?)?;
+    fs::remove_file(&path)?;
+    Ok(Some(presentation))
+}
+
+/// The presentation answering the request from the file inbox, if it arrived.
+pub fn receive(
+    inbox: &str,
+    request: &PresentationRequest,
+) -> Result<Option<Presentation>, Box<dyn Error>> {
+    let path = responses_dir(inbox).join(format!("{}.json", request.id));
+    if !path.exists() {
+        return Ok(None);
+    }
+    let presentation = Presentation::from_json(&fs::read_to_string(&path)?)?;
+    fs::remove_file(&path)?;
+    Ok(Some(presentation))
+}
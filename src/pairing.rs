@@ -0,0 +1,187 @@
+use crate::didcomm;
+use crate::handle;
+use crate::paths;
+use chacha20poly1305::aead::{Aead, NewAead};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use identity::core::{FromJson, Timestamp};
+use identity::credential::Credential;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Scheme of the link a pairing QR carries.
+pub const SCHEME: &str = "iota-identity-pair://";
+const NONCE_LEN: usize = 12;
+
+/// A device paired through a shared secret. Credentials for `holder` travel encrypted
+/// with the secret instead of in plain through the shared inbox.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Peer {
+    /// Name this device gave the other one.
+    pub label: String,
+    /// Holder DID the credentials on this channel are issued to.
+    pub holder: String,
+    /// Hex encoded 256 bit key of the channel.
+    pub secret: String,
+    pub paired: Timestamp,
+}
+
+/// What the pairing QR carries.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Invitation {
+    pub holder: String,
+    pub secret: String,
+}
+
+impl Peer {
+    /// The cipher keyed with the pairing secret.
+    pub fn cipher(&self) -> Result<ChaCha20Poly1305, Box<dyn Error>> {
+        let key = hex::decode(&self.secret)?;
+        if key.len() != 32 {
+            return Err("the pairing secret is no 256 bit key".into());
+        }
+        Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+    }
+
+    /// Names the channel without revealing the secret, the hash of the secret.
+    pub fn channel_id(&self) -> String {
+        handle::hash(&self.secret)[..16].to_string()
+    }
+
+    /// Directory of the channel inside the inbox.
+    pub fn channel(&self, inbox: &str) -> PathBuf {
+        Path::new(inbox).join("paired").join(self.channel_id())
+    }
+
+    /// Decrypts a credential sealed by earlier versions, the nonce in front of the ciphertext.
+    pub fn open(&self, sealed: &str) -> Result<String, Box<dyn Error>> {
+        let sealed = base64::decode(sealed.trim())?;
+        if sealed.len() < NONCE_LEN {
+            return Err("the sealed message is too short".into());
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let message = self
+            .cipher()?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| "the message was not sealed with the pairing secret")?;
+        Ok(String::from_utf8(message)?)
+    }
+}
+
+/// The devices this one is paired with.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Peers {
+    pub peers: Vec<Peer>,
+}
+
+impl Peers {
+    /// Loads the peers, none if the file is missing or unreadable.
+    pub fn load(path: &str) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves the peers readable only by the owner, they hold the channel keys.
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        paths::restrict_file(Path::new(path))?;
+        Ok(())
+    }
+
+    /// The channel credentials for the holder go through, if that holder was paired.
+    pub fn for_holder(&self, holder: &str) -> Option<&Peer> {
+        self.peers.iter().rev().find(|peer| peer.holder == holder)
+    }
+
+    /// Adds the peer, a new pairing with the same holder and label replaces the old one.
+    pub fn add(&mut self, peer: Peer) {
+        self.peers
+            .retain(|known| !(known.holder == peer.holder && known.label == peer.label));
+        self.peers.push(peer);
+    }
+
+    /// Pairs with a new device for credentials issued to `holder`, returns the link for its QR.
+    pub fn invite(&mut self, label: &str, holder: &str) -> Result<String, Box<dyn Error>> {
+        let mut key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        let invitation = Invitation {
+            holder: holder.to_string(),
+            secret: hex::encode(key),
+        };
+        let link = format!(
+            "{}?pair={}",
+            SCHEME,
+            base64::encode_config(serde_json::to_string(&invitation)?, base64::URL_SAFE_NO_PAD)
+        );
+        self.add(Peer {
+            label: label.to_string(),
+            holder: invitation.holder,
+            secret: invitation.secret,
+            paired: Timestamp::now_utc(),
+        });
+        Ok(link)
+    }
+
+    /// Pairs with the device whose invitation was scanned.
+    pub fn accept(&mut self, label: &str, invitation: &str) -> Result<&Peer, Box<dyn Error>> {
+        let invitation: Invitation = serde_json::from_str(invitation)?;
+        let peer = Peer {
+            label: label.to_string(),
+            holder: invitation.holder,
+            secret: invitation.secret,
+            paired: Timestamp::now_utc(),
+        };
+        // Refuse a secret that can't key the channel before storing it.
+        peer.cipher()?;
+        self.add(peer);
+        Ok(self.peers.last().expect("the peer was just added"))
+    }
+
+    /// Decrypts the credentials waiting in the channels to this holder, issue messages in
+    /// encrypted DIDComm envelopes or sealed credentials of earlier versions.
+    pub fn receive(&self, inbox: &str, holder: &str) -> Vec<Credential> {
+        self.peers
+            .iter()
+            .filter(|peer| peer.holder == holder)
+            .flat_map(|peer| {
+                fs::read_dir(peer.channel(inbox))
+                    .map(|entries| {
+                        entries
+                            .filter_map(|entry| entry.ok())
+                            .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+                            .filter_map(|sealed| {
+                                match didcomm::unpack(&sealed, std::slice::from_ref(peer)) {
+                                    Ok(unpacked) => didcomm::credential_of(&unpacked.message),
+                                    Err(_) => peer
+                                        .open(&sealed)
+                                        .ok()
+                                        .and_then(|json| Credential::from_json(&json).ok()),
+                                }
+                            })
+                            .collect::<Vec<Credential>>()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+/// Drops the credential into the channel of the peer, as issue message in an encrypted
+/// DIDComm envelope.
+pub fn send(
+    inbox: &str,
+    peer: &Peer,
+    id: &str,
+    credential: &Credential,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let message = didcomm::credential(didcomm::ISSUE, credential)?;
+    let dir = peer.channel(inbox);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.sealed", id));
+    fs::write(&path, didcomm::encrypt(&message, peer)?)?;
+    Ok(path)
+}
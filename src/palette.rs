@@ -0,0 +1,171 @@
+use crate::actions::Action;
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+#[derive(Default)]
+pub struct Palette {
+    pub visible: bool,
+    pub query: String,
+    selected: usize,
+}
+
+impl Palette {
+    pub fn open(&mut self) {
+        self.visible = true;
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    /// Actions matching the query, best match first.
+    pub fn matches(&self) -> Vec<Action> {
+        let mut scored: Vec<(i32, Action)> = Action::ALL
+            .iter()
+            .filter_map(|action| score(&self.query, action.title()).map(|score| (score, *action)))
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0));
+        scored.into_iter().map(|(_, action)| action).collect()
+    }
+
+    /// Handles a key while the palette is open and returns the action to execute, if any.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Esc => self.visible = false,
+            KeyCode::Enter => {
+                self.visible = false;
+                return self.matches().get(self.selected).copied();
+            }
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                if self.selected + 1 < self.matches().len() {
+                    self.selected += 1;
+                }
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.selected = 0;
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let area = centered_rect(60, 60, area);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
+            .split(area);
+
+        let input = Paragraph::new(format!("> {}", self.query)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title("Command palette")
+                .border_type(BorderType::Plain),
+        );
+
+        let items: Vec<ListItem> = self
+            .matches()
+            .iter()
+            .map(|action| ListItem::new(action.title()))
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(Color::White))
+                    .border_type(BorderType::Plain),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(Color::Yellow)
+                    .fg(Color::Black)
+                    .add_modifier(Modifier::BOLD),
+            );
+        let mut state = ListState::default();
+        state.select(Some(self.selected));
+
+        f.render_widget(Clear, area);
+        f.render_widget(input, chunks[0]);
+        f.render_stateful_widget(list, chunks[1], &mut state);
+    }
+}
+
+/// Fuzzy matches the query as a case-insensitive subsequence of the text.
+/// Consecutive and word-start matches score higher.
+fn score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for c in query.to_lowercase().chars() {
+        let found = position + text[position..].iter().position(|t| *t == c)?;
+        score += 1;
+        if previous.map(|p| p + 1 == found).unwrap_or(false) {
+            score += 5;
+        }
+        if found == 0 || text[found - 1] == ' ' {
+            score += 3;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_y) / 2),
+                Constraint::Percentage(percent_y),
+                Constraint::Percentage((100 - percent_y) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_x) / 2),
+                Constraint::Percentage(percent_x),
+                Constraint::Percentage((100 - percent_x) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(vertical[1])[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_every_action_for_an_empty_query() {
+        let palette = Palette::default();
+        assert_eq!(palette.matches(), Action::ALL.to_vec());
+    }
+
+    #[test]
+    fn finds_an_action_by_its_title() {
+        let palette = Palette {
+            query: "signatures per key".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(palette.matches().first(), Some(&Action::ShowKeys));
+    }
+}
@@ -0,0 +1,77 @@
+use directories::ProjectDirs;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// OS specific directory for app data, e.g. `~/.local/share/iota-identity-examples` on Linux.
+/// Falls back to the working directory if the OS has no home directory.
+pub fn data_dir() -> PathBuf {
+    ProjectDirs::from("org", "iota", "iota-identity-examples")
+        .map(|dirs| dirs.data_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Scratch directory of an `--ephemeral` run, one per process.
+pub fn ephemeral_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("iota-identity-ephemeral-{}", std::process::id()))
+}
+
+pub fn default_path(name: &str) -> String {
+    data_dir().join(name).to_string_lossy().into_owned()
+}
+
+/// Creates the directory, only accessible by the user on Unix.
+pub fn restrict_dir(path: &Path) -> io::Result<()> {
+    fs::create_dir_all(path)?;
+    set_mode(path, 0o700)
+}
+
+/// Makes an existing file only readable and writable by the user on Unix.
+pub fn restrict_file(path: &Path) -> io::Result<()> {
+    if path.exists() {
+        set_mode(path, 0o600)?;
+    }
+    Ok(())
+}
+
+/// Creates a new file only readable and writable by the user on Unix, failing if the file
+/// exists. The permissions hold from the start, unlike with `restrict_file` after writing.
+pub fn create_private(path: &Path) -> io::Result<fs::File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+/// Permissions of the file for display, `None` if it doesn't exist.
+pub fn mode(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        Some(format!("{:o}", metadata.permissions().mode() & 0o777))
+    }
+    #[cfg(not(unix))]
+    {
+        Some(if metadata.permissions().readonly() {
+            "read-only".to_string()
+        } else {
+            "read-write".to_string()
+        })
+    }
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> io::Result<()> {
+    Ok(())
+}
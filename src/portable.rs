@@ -0,0 +1,123 @@
+use crate::armor::{self, Kind};
+use crate::handle;
+use crate::wallet::Entry;
+use identity::core::Timestamp;
+use identity::credential::Credential;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Layout version of the export, bumped when files are renamed or change meaning.
+const FORMAT: u32 = 1;
+const MANIFEST: &str = "manifest.json";
+const CREDENTIALS: &str = "credentials.json";
+
+/// Describes a wallet export, so an import can tell it is complete.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub format: u32,
+    pub created: Timestamp,
+    pub holder: String,
+    /// Tag the wallet was filtered by, `None` if every credential of the view was exported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    pub count: usize,
+    /// SHA-256 of the credentials file.
+    pub credentials: String,
+}
+
+/// How one credential of an import fared.
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub title: String,
+    pub verified: bool,
+    /// Why it does not verify, `None` if it does.
+    pub error: Option<String>,
+    /// Whether the wallet did not hold it yet.
+    pub added: bool,
+    /// Whether a credential of the same type, issuer and subject is held, the holder decides.
+    pub duplicate: bool,
+}
+
+impl Outcome {
+    pub fn line(&self) -> String {
+        format!(
+            "{}: {}{}",
+            self.title,
+            match &self.error {
+                None if self.verified => "verified".to_string(),
+                None => "does not verify".to_string(),
+                Some(error) => format!("does not verify ({})", error),
+            },
+            match (self.added, self.duplicate) {
+                (true, _) => "",
+                (false, true) => ", near duplicate of a held one",
+                (false, false) => ", already held",
+            }
+        )
+    }
+}
+
+/// Writes the credentials as a plain JSON array of W3C verifiable credentials into
+/// `wallet-<unix time>/` below `dir`, with a manifest next to it. Wallet organisation like tags
+/// and folders stays behind, any wallet reads the array.
+pub fn export(
+    dir: &str,
+    holder: &str,
+    filter: Option<&str>,
+    entries: &[Entry],
+) -> Result<PathBuf, Box<dyn Error>> {
+    let credentials: Vec<&Credential> = entries.iter().map(|entry| &entry.credential).collect();
+    let json: String = serde_json::to_string_pretty(&credentials)?;
+    let created = Timestamp::now_utc();
+    let manifest = Manifest {
+        format: FORMAT,
+        created,
+        holder: holder.to_string(),
+        filter: filter.map(str::to_string),
+        count: credentials.len(),
+        credentials: handle::hash(&json),
+    };
+    let target = Path::new(dir).join(format!("wallet-{}", created.to_unix()));
+    fs::create_dir_all(&target)?;
+    fs::write(target.join(CREDENTIALS), &json)?;
+    fs::write(
+        target.join(MANIFEST),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok(target)
+}
+
+/// Reads an export from its directory, refusing it if the credentials do not match the manifest.
+/// A credentials file on its own is read without a manifest.
+pub fn import(path: &Path) -> Result<(Option<Manifest>, Vec<Credential>), Box<dyn Error>> {
+    let (dir, file) = if path.is_dir() {
+        (path, path.join(CREDENTIALS))
+    } else {
+        (path.parent().unwrap_or(path), path.to_path_buf())
+    };
+    let json: String = fs::read_to_string(&file)?;
+    // A single credential pasted from chat or email into a file.
+    if armor::is_armored(&json) {
+        return match armor::decode(&json)? {
+            (Kind::Credential, credential) => Ok((None, vec![serde_json::from_str(&credential)?])),
+            (Kind::Presentation, _) => Err("a presentation is no wallet export".into()),
+        };
+    }
+    let manifest: Option<Manifest> = match fs::read_to_string(dir.join(MANIFEST)) {
+        Ok(manifest) => Some(serde_json::from_str(&manifest)?),
+        Err(_) => None,
+    };
+    if let Some(manifest) = &manifest {
+        if manifest.format > FORMAT {
+            return Err(format!("export format {} is newer than this app", manifest.format).into());
+        }
+        if manifest.credentials != handle::hash(&json) {
+            return Err(format!("{} does not match the manifest", CREDENTIALS).into());
+        }
+    }
+    let credentials: Vec<Credential> = serde_json::from_str(&json)?;
+    Ok((manifest, credentials))
+}
@@ -0,0 +1,31 @@
+use crate::oob::{self, PresentationRequest};
+use identity::core::Url;
+use identity::credential::Credential;
+use identity::credential::Presentation;
+use identity::credential::PresentationBuilder;
+use identity::prelude::*;
+
+/// Bundles the credentials, possibly from different issuers, into a presentation signed by the holder.
+/// Answering a verifier request echoes its challenge inside the signed presentation.
+pub fn present(
+    holder: &IotaDocument,
+    key: &KeyPair,
+    credentials: Vec<Credential>,
+    request: Option<&PresentationRequest>,
+) -> Result<Presentation> {
+    let mut builder: PresentationBuilder = PresentationBuilder::default()
+        .id(Url::parse("https://example.org/presentations/1")?)
+        .holder(Url::parse(holder.id().as_str())?);
+    for credential in credentials {
+        builder = builder.credential(credential);
+    }
+    let mut presentation: Presentation = builder.build()?;
+    if let Some(request) = request {
+        oob::answer(&mut presentation, request);
+    }
+
+    // Sign the Presentation with the holder's private key.
+    holder.sign_data(&mut presentation, key.private())?;
+
+    Ok(presentation)
+}
@@ -0,0 +1,59 @@
+use crate::actions::Action;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Capability {
+    Issue,
+    Publish,
+    ExportKeys,
+}
+
+/// A role sharing the machine and storage with other profiles, limited to its capabilities.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Profile {
+    pub name: String,
+    pub can_issue: bool,
+    pub can_publish: bool,
+    pub can_export_keys: bool,
+}
+
+impl Profile {
+    pub fn has(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Issue => self.can_issue,
+            Capability::Publish => self.can_publish,
+            Capability::ExportKeys => self.can_export_keys,
+        }
+    }
+
+    /// Checks the action against the profile, returning the missing capability if it is not allowed.
+    pub fn check(&self, action: Action) -> Result<(), Capability> {
+        match action.capability() {
+            Some(capability) if !self.has(capability) => Err(capability),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn defaults() -> Vec<Profile> {
+    vec![
+        Profile {
+            name: "issuer".to_string(),
+            can_issue: true,
+            can_publish: true,
+            can_export_keys: true,
+        },
+        Profile {
+            name: "holder".to_string(),
+            can_issue: false,
+            can_publish: true,
+            can_export_keys: true,
+        },
+        Profile {
+            name: "verifier".to_string(),
+            can_issue: false,
+            can_publish: false,
+            can_export_keys: false,
+        },
+    ]
+}
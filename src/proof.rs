@@ -0,0 +1,90 @@
+use crate::clock;
+use identity::core::{encode_b64, json, ToJson};
+use identity::credential::Credential;
+use identity::crypto::{Ed25519, Sign};
+use identity::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofFormat {
+    /// Linked-data style proof embedded in the credential JSON.
+    Embedded,
+    /// Compact JWS (VC-JWT) wrapping the credential.
+    Jws,
+}
+
+impl FromStr for ProofFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "embedded" => Ok(ProofFormat::Embedded),
+            "jws" => Ok(ProofFormat::Jws),
+            other => Err(format!("unknown proof format {}", other)),
+        }
+    }
+}
+
+/// Signs the credential with the issuer key in the chosen representation.
+pub fn sign(
+    issuer: &IotaDocument,
+    key: &KeyPair,
+    mut credential: Credential,
+    format: ProofFormat,
+) -> Result<String> {
+    match format {
+        ProofFormat::Embedded => {
+            issuer.sign_data(&mut credential, key.private())?;
+            Ok(credential.to_string())
+        }
+        ProofFormat::Jws => {
+            let method = issuer.authentication().id().to_string();
+            sign_jws(issuer, &method, key, &credential)
+        }
+    }
+}
+
+/// Detects whether the input carries a compact JWS.
+pub fn is_jws(input: &str) -> bool {
+    let input = input.trim();
+    !input.starts_with('{') && input.split('.').count() == 3
+}
+
+/// Signs the credential as compact JWS with the key of the issuer method `method`.
+pub fn sign_jws(
+    issuer: &IotaDocument,
+    method: &str,
+    key: &KeyPair,
+    credential: &Credential,
+) -> Result<String> {
+    let header = json!({
+      "alg": "EdDSA",
+      "typ": "JWT",
+      "kid": method,
+    });
+    let mut payload = json!({
+      "iss": issuer.id().as_str(),
+      "sub": credential
+        .credential_subject
+        .iter()
+        .find_map(|subject| subject.id.as_ref().map(|id| id.to_string())),
+      "jti": credential.id.as_ref().map(|id| id.to_string()),
+      "nbf": credential.issuance_date.to_unix(),
+      "iat": clock::now().to_unix(),
+      "vc": credential,
+    });
+    if let Some(expires) = credential.expiration_date {
+        payload["exp"] = json!(expires.to_unix());
+    }
+
+    let signing_input = format!(
+        "{}.{}",
+        encode_b64(&header.to_json_vec()?),
+        encode_b64(&payload.to_json_vec()?)
+    );
+    let signature = Ed25519::sign(signing_input.as_bytes(), key.private())?;
+
+    Ok(format!("{}.{}", signing_input, encode_b64(&signature)))
+}
@@ -0,0 +1,85 @@
+use identity::core::{encode_b58, Timestamp};
+use identity::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fs;
+
+/// Multicodec prefix of an Ed25519 public key in a did:key or `publicKeyMultibase`.
+pub const ED25519_CODEC: [u8; 2] = [0xed, 0x01];
+
+/// A did:key the holder shows only to one issuer, so two issuers can't correlate their
+/// credentials by the subject.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Pseudonym {
+    pub did: String,
+    pub issuer: String,
+    /// The real holder DID behind the pseudonym.
+    pub holder: String,
+    pub created: Timestamp,
+}
+
+/// Pairwise pseudonyms of the holders, kept locally and never shared.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Pseudonyms {
+    pseudonyms: Vec<Pseudonym>,
+}
+
+impl Pseudonyms {
+    /// Loads the pseudonyms, none if the file is missing or unreadable.
+    pub fn load(path: &str) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The pseudonym of the holder towards the issuer, recorded on first use.
+    /// Its key follows from the holder key and the issuer, only the mapping needs keeping.
+    pub fn for_issuer(
+        &mut self,
+        holder: &IotaDocument,
+        holder_key: &KeyPair,
+        issuer: &str,
+    ) -> Result<String, Box<dyn Error>> {
+        let did = did_key(&keypair(holder_key, issuer)?);
+        if self.get(&did).is_none() {
+            self.pseudonyms.push(Pseudonym {
+                did: did.clone(),
+                issuer: issuer.to_string(),
+                holder: holder.id().to_string(),
+                created: Timestamp::now_utc(),
+            });
+        }
+        Ok(did)
+    }
+
+    /// The pseudonym with this DID, `None` for any other DID.
+    pub fn get(&self, did: &str) -> Option<&Pseudonym> {
+        self.pseudonyms
+            .iter()
+            .find(|pseudonym| pseudonym.did == did)
+    }
+}
+
+/// Key of the pseudonym towards the issuer, derived from the holder key.
+pub fn keypair(holder_key: &KeyPair, issuer: &str) -> Result<KeyPair, Box<dyn Error>> {
+    let private = Sha256::new()
+        .chain(holder_key.private().as_ref())
+        .chain(b"/pseudonym/")
+        .chain(issuer.as_bytes())
+        .finalize();
+    Ok(KeyPair::try_from_ed25519_bytes(&private)?)
+}
+
+/// The did:key of an Ed25519 key pair.
+pub fn did_key(key: &KeyPair) -> String {
+    let mut bytes: Vec<u8> = ED25519_CODEC.to_vec();
+    bytes.extend_from_slice(key.public().as_ref());
+    format!("did:key:z{}", encode_b58(&bytes))
+}
@@ -0,0 +1,101 @@
+use crate::proof;
+use qrcode::render::unicode;
+use qrcode::types::QrError;
+use qrcode::{EcLevel, QrCode};
+
+/// Bytes a version 40 code holds in byte mode, the hard limit.
+fn max_bytes(level: EcLevel) -> usize {
+    match level {
+        EcLevel::L => 2953,
+        EcLevel::M => 2331,
+        EcLevel::Q => 1663,
+        EcLevel::H => 1273,
+    }
+}
+
+/// Bytes a version 20 code holds in byte mode. Denser codes rarely scan from a terminal.
+fn reliable_bytes(level: EcLevel) -> usize {
+    match level {
+        EcLevel::L => 858,
+        EcLevel::M => 666,
+        EcLevel::Q => 482,
+        EcLevel::H => 382,
+    }
+}
+
+pub fn parse_level(level: &str) -> EcLevel {
+    match level {
+        "L" => EcLevel::L,
+        "Q" => EcLevel::Q,
+        "H" => EcLevel::H,
+        _ => EcLevel::M,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Preflight {
+    pub bytes: usize,
+    pub level: EcLevel,
+}
+
+impl Preflight {
+    pub fn new(data: &str, level: EcLevel) -> Self {
+        Preflight {
+            bytes: data.len(),
+            level,
+        }
+    }
+
+    pub fn fits(&self) -> bool {
+        self.bytes <= max_bytes(self.level)
+    }
+
+    pub fn is_reliable(&self) -> bool {
+        self.bytes <= reliable_bytes(self.level)
+    }
+
+    pub fn warning(&self) -> Option<String> {
+        if !self.fits() {
+            Some(format!(
+                "{} bytes exceed the QR capacity of {} bytes at error correction {:?}",
+                self.bytes,
+                max_bytes(self.level),
+                self.level
+            ))
+        } else if !self.is_reliable() {
+            Some(format!(
+                "{} bytes exceed the {} bytes that scan reliably at error correction {:?}",
+                self.bytes,
+                reliable_bytes(self.level),
+                self.level
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Alternatives to a single oversized code.
+    pub fn suggestions(&self, data: &str) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        if !proof::is_jws(data) {
+            suggestions
+                .push("Sign as compact JWS (--proof jws) to drop the JSON overhead".to_string());
+        }
+        let chunks = (self.bytes + reliable_bytes(self.level) - 1) / reliable_bytes(self.level);
+        suggestions.push(format!("Split the payload into {} chunked codes", chunks));
+        suggestions.push("Publish the credential and encode only a short URL handle".to_string());
+        if self.level != EcLevel::L {
+            suggestions.push("Lower the error correction level (qr_ec_level: \"L\")".to_string());
+        }
+        suggestions
+    }
+}
+
+pub fn render(data: &str, level: EcLevel) -> Result<String, QrError> {
+    let code = QrCode::with_error_correction_level(data, level)?;
+    Ok(code
+        .render::<unicode::Dense1x2>()
+        .dark_color(unicode::Dense1x2::Light)
+        .light_color(unicode::Dense1x2::Dark)
+        .build())
+}
@@ -0,0 +1,102 @@
+use identity::credential::Credential;
+use tui::{
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem},
+};
+
+/// Where a queued credential is on its way to the holder.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ItemStatus {
+    Drafted,
+    Signed,
+    Delivered,
+    Failed(String),
+}
+
+impl ItemStatus {
+    pub fn badge(&self) -> (String, Color) {
+        match self {
+            ItemStatus::Drafted => ("[drafted]".to_string(), Color::Gray),
+            ItemStatus::Signed => ("[signed]".to_string(), Color::Yellow),
+            ItemStatus::Delivered => ("[delivered]".to_string(), Color::Green),
+            ItemStatus::Failed(error) => (format!("[failed: {}]", error), Color::Red),
+        }
+    }
+}
+
+/// A credential drafted in the Issue tab, waiting to be signed and delivered with the others.
+#[derive(Clone, Debug)]
+pub struct QueueItem {
+    pub subject: String,
+    pub credential: Credential,
+    pub status: ItemStatus,
+}
+
+impl QueueItem {
+    pub fn title(&self) -> String {
+        self.credential
+            .types
+            .iter()
+            .last()
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct IssueQueue {
+    pub items: Vec<QueueItem>,
+}
+
+impl IssueQueue {
+    pub fn push(&mut self, subject: &str, credential: Credential) {
+        self.items.push(QueueItem {
+            subject: subject.to_string(),
+            credential,
+            status: ItemStatus::Drafted,
+        });
+    }
+
+    /// Items still to be signed or delivered, failed ones are tried again.
+    pub fn open(&mut self) -> impl Iterator<Item = &mut QueueItem> {
+        self.items
+            .iter_mut()
+            .filter(|item| item.status != ItemStatus::Delivered)
+    }
+
+    /// Drops the delivered items and returns how many there were.
+    pub fn clear_delivered(&mut self) -> usize {
+        let before = self.items.len();
+        self.items
+            .retain(|item| item.status != ItemStatus::Delivered);
+        before - self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+pub fn render<'a>(queue: &IssueQueue) -> List<'a> {
+    let items: Vec<ListItem> = queue
+        .items
+        .iter()
+        .map(|item| {
+            let (badge, color) = item.status.badge();
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{} ", badge), Style::default().fg(color)),
+                Span::raw(format!("{} ", item.title())),
+                Span::styled(item.subject.clone(), Style::default().fg(Color::Gray)),
+            ]))
+        })
+        .collect();
+
+    List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title("Queue (u adds the form, g signs and delivers all, x clears delivered)")
+            .border_type(BorderType::Plain),
+    )
+}
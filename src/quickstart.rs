@@ -0,0 +1,128 @@
+use crate::config::Config;
+use crate::core::Service;
+use crate::ids;
+use crate::issue;
+use crate::nodes;
+use crate::present;
+use crate::proof::{self, ProofFormat};
+use crate::seed;
+use crate::verification::Verified;
+use identity::core::ToJson;
+use identity::credential::{Credential, Presentation};
+use identity::iota::Receipt;
+use identity::prelude::*;
+use std::error::Error;
+
+/// Runs the whole issue/verify happy path without the TUI and prints every artifact.
+pub async fn run(
+    config: &Config,
+    format: ProofFormat,
+    seed: Option<&str>,
+) -> std::result::Result<bool, Box<dyn Error>> {
+    let service = Service::new(config, nodes::from_urls(&config.nodes));
+
+    step(1, "Create and publish the issuer identity");
+    let (issuer_doc, issuer_key, receipt): (IotaDocument, KeyPair, Receipt) = service
+        .create_identity(seed::keypair(seed, "issuer")?)
+        .await?;
+    artifact("Issuer DID Document", &issuer_doc.to_json_pretty()?);
+    note(&format!("Published in {}", receipt.message_url()?));
+
+    step(2, "Create and publish the holder identity");
+    let (holder_doc, holder_key, receipt): (IotaDocument, KeyPair, Receipt) = service
+        .create_identity(seed::keypair(seed, "holder")?)
+        .await?;
+    artifact("Holder DID Document", &holder_doc.to_json_pretty()?);
+    note(&format!("Published in {}", receipt.message_url()?));
+
+    step(3, "Issue the prerequisite enrollment credential");
+    let mut enrollment: Credential = issue::issue_enrollment(&issuer_doc, &holder_doc)?;
+    issuer_doc.sign_data(&mut enrollment, issuer_key.private())?;
+    artifact("Enrollment Credential", &enrollment.to_json_pretty()?);
+
+    step(4, "Issuer verifies the enrollment presented by the holder");
+    let enrolled: bool = service
+        .verify_prerequisite(&enrollment, &holder_doc)
+        .await?;
+    note(&format!("Prerequisite verified: {}", enrolled));
+    if !enrolled {
+        return Ok(false);
+    }
+
+    step(5, "Issue and sign the degree credential");
+    // Nothing keeps an issuance log between quickstart runs, a random id stays unique.
+    let credential: Credential =
+        issue::issue_degree(&issuer_doc, &ids::uuid(), &holder_doc, &enrollment)?;
+    let signed: String = proof::sign(&issuer_doc, &issuer_key, credential, format)?;
+    artifact(&format!("Degree Credential ({:?} proof)", format), &signed);
+
+    step(6, "Verify the degree credential");
+    let Verified {
+        credential,
+        verified,
+        format,
+        ..
+    } = service
+        .verify_credential(&signed)
+        .await?
+        .value
+        .ok_or("the issuer is on another network than the client")?;
+    note(&format!(
+        "Detected {:?} proof, credential verified: {}",
+        format, verified
+    ));
+    if format == ProofFormat::Jws {
+        // A VC-JWT is handed to the verifier as is, there is no presentation to wrap it in.
+        return Ok(verified);
+    }
+
+    step(
+        7,
+        "A second issuer, the student club, issues a membership credential",
+    );
+    let (club_doc, club_key, receipt): (IotaDocument, KeyPair, Receipt) = service
+        .create_identity(seed::keypair(seed, "club")?)
+        .await?;
+    note(&format!(
+        "Club {} published in {}",
+        club_doc.id(),
+        receipt.message_url()?
+    ));
+    let mut membership: Credential = issue::issue_membership(&club_doc, &holder_doc)?;
+    club_doc.sign_data(&mut membership, club_key.private())?;
+    artifact("Membership Credential", &membership.to_json_pretty()?);
+
+    step(8, "Holder presents degree and membership together");
+    let presentation: Presentation =
+        present::present(&holder_doc, &holder_key, vec![credential, membership], None)?;
+    artifact("Verifiable Presentation", &presentation.to_json_pretty()?);
+
+    step(9, "Verifier checks the presentation");
+    let report = service.verify_presentation(&presentation, &[]).await?;
+    for (issuer, credentials) in report.by_issuer() {
+        note(&format!("Issuer {}", issuer));
+        for credential in credentials {
+            note(&format!(
+                "    {} verified: {}",
+                credential.title, credential.verified
+            ));
+        }
+    }
+    note(&format!("Presentation verified: {}", validation.verified));
+
+    Ok(validation.verified)
+}
+
+pub fn step(number: usize, title: &str) {
+    println!();
+    println!("=== Step {}: {} ===", number, title);
+}
+
+pub fn artifact(name: &str, json: &str) {
+    println!("--- {} ---", name);
+    println!("{}", json);
+}
+
+pub fn note(text: &str) {
+    println!("> {}", text);
+}
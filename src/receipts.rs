@@ -0,0 +1,297 @@
+use crate::bus::{Bus, Message};
+use crate::config::Config;
+use crate::nodes::{self, Nodes};
+use crate::redact;
+use crate::store::IdentityStore;
+use identity::core::{FromJson, Timestamp, ToJson};
+use identity::iota::{Client, IotaDocument, Network, Receipt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem},
+};
+
+/// What a publish did to the DID document.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Create,
+    Update,
+    Republish,
+}
+
+impl Operation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Operation::Create => "created",
+            Operation::Update => "updated",
+            Operation::Republish => "republished",
+        }
+    }
+}
+
+/// Proof that a DID document was published, kept apart from the identities to find the
+/// message again and link it in the explorer without resolving the DID.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PublishReceipt {
+    pub did: String,
+    pub message_id: String,
+    pub published: Timestamp,
+    /// Name of the network the message went to, like `main` or `dev`.
+    #[serde(default)]
+    pub network: String,
+    /// `None` for receipts written before the operation was kept.
+    #[serde(default)]
+    pub operation: Option<Operation>,
+    /// The message in the explorer of the network, if it has one.
+    #[serde(default)]
+    pub explorer_url: Option<String>,
+    /// The published document, kept to publish it again if the message is not confirmed.
+    #[serde(default)]
+    pub document: Option<String>,
+    /// Message the document was published again with.
+    #[serde(default)]
+    pub republished_as: Option<String>,
+}
+
+impl PublishReceipt {
+    pub fn new(
+        document: &IotaDocument,
+        receipt: &Receipt,
+        operation: Operation,
+    ) -> Result<Self, Box<dyn Error>> {
+        Ok(PublishReceipt {
+            did: document.id().to_string(),
+            message_id: receipt.message_id().to_string(),
+            published: Timestamp::now_utc(),
+            network: Network::from_did(document.id()).name().to_string(),
+            operation: Some(operation),
+            explorer_url: receipt.message_url().ok().map(|url| url.to_string()),
+            document: Some(document.to_json()?),
+            republished_as: None,
+        })
+    }
+}
+
+/// The receipts of the DID, newest first.
+pub fn for_did<'a>(receipts: &'a [PublishReceipt], did: &str) -> Vec<&'a PublishReceipt> {
+    let mut found: Vec<&PublishReceipt> = receipts
+        .iter()
+        .filter(|receipt| receipt.did == did)
+        .collect();
+    found.sort_by(|a, b| b.published.cmp(&a.published));
+    found
+}
+
+/// The receipt of the message, to link it without asking a node.
+pub fn by_message<'a>(
+    receipts: &'a [PublishReceipt],
+    message_id: &str,
+) -> Option<&'a PublishReceipt> {
+    receipts
+        .iter()
+        .find(|receipt| receipt.message_id == message_id)
+}
+
+/// Where a published DID message is on the Tangle.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Inclusion {
+    /// Not referenced by a milestone yet.
+    Pending,
+    /// Referenced by the milestone with this index.
+    Confirmed(u32),
+    /// Referenced, but the node did not apply it.
+    Conflicting,
+    /// Still unreferenced after the timeout or flagged for reattachment by the node.
+    Stale,
+    /// The node does not know the message or could not be asked.
+    Unknown,
+}
+
+impl Inclusion {
+    pub fn badge(&self) -> (String, Color) {
+        match self {
+            Inclusion::Pending => ("[pending]".to_string(), Color::Yellow),
+            Inclusion::Confirmed(milestone) => {
+                (format!("[confirmed #{}]", milestone), Color::Green)
+            }
+            Inclusion::Conflicting => ("[conflicting]".to_string(), Color::Red),
+            Inclusion::Stale => ("[stale, republish]".to_string(), Color::Red),
+            Inclusion::Unknown => ("[unknown]".to_string(), Color::Gray),
+        }
+    }
+
+    /// Whether the message can't make it anymore and should be published again.
+    pub fn needs_republish(&self) -> bool {
+        matches!(self, Inclusion::Stale | Inclusion::Conflicting)
+    }
+
+    fn is_final(&self) -> bool {
+        matches!(self, Inclusion::Confirmed(_) | Inclusion::Conflicting)
+    }
+}
+
+#[derive(Deserialize)]
+struct MetadataResponse {
+    data: Metadata,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Metadata {
+    referenced_by_milestone_index: Option<u32>,
+    ledger_inclusion_state: Option<String>,
+    should_reattach: Option<bool>,
+}
+
+/// Asks the node for the metadata of the published message.
+/// Messages unreferenced for more than `timeout` seconds count as stale.
+pub async fn check(node: &str, receipt: &PublishReceipt, timeout: u64) -> Inclusion {
+    let url = format!("{}/api/v1/messages/{}/metadata", node, receipt.message_id);
+    let metadata = match reqwest::get(url).await {
+        Ok(response) => response.json::<MetadataResponse>().await.ok(),
+        Err(_) => None,
+    };
+    let metadata: Metadata = match metadata {
+        Some(MetadataResponse { data }) => data,
+        None => return Inclusion::Unknown,
+    };
+    let age = Timestamp::now_utc().to_unix() - receipt.published.to_unix();
+
+    match metadata.referenced_by_milestone_index {
+        Some(_) if metadata.ledger_inclusion_state.as_deref() == Some("conflicting") => {
+            Inclusion::Conflicting
+        }
+        Some(milestone) => Inclusion::Confirmed(milestone),
+        None if metadata.should_reattach == Some(true) || age > timeout as i64 => Inclusion::Stale,
+        None => Inclusion::Pending,
+    }
+}
+
+/// Publishes the document of the receipt again as a new message and returns its receipt.
+/// Receipts written before the document was kept can't be republished.
+pub async fn republish(
+    client: &Client,
+    receipt: &PublishReceipt,
+) -> Result<PublishReceipt, Box<dyn Error>> {
+    let json = receipt
+        .document
+        .as_ref()
+        .ok_or("the receipt does not keep the published document")?;
+    let document = IotaDocument::from_json(json)?;
+    let published: Receipt = client.publish_document(&document).await?;
+    PublishReceipt::new(&document, &published, Operation::Republish)
+}
+
+/// Checks the receipts not confirmed yet periodically and reports their inclusion on the bus.
+pub fn spawn_monitor(
+    config: &Config,
+    store: Arc<dyn IdentityStore>,
+    nodes: Nodes,
+    bus: Bus,
+) -> JoinHandle<()> {
+    let interval = config.poll_interval();
+    let timeout = config.confirm_timeout;
+
+    tokio::spawn(async move {
+        let mut known: HashMap<String, Inclusion> = HashMap::new();
+        loop {
+            if let Some(node) = nodes::route(&nodes).into_iter().next() {
+                for receipt in store.receipts() {
+                    let previous = known.get(&receipt.message_id).cloned();
+                    if previous.as_ref().map_or(false, Inclusion::is_final)
+                        || receipt.republished_as.is_some()
+                    {
+                        continue;
+                    }
+                    let inclusion = check(&node, &receipt, timeout).await;
+                    if inclusion == Inclusion::Stale && previous != Some(Inclusion::Stale) {
+                        bus.log(format!(
+                            "Publish of {} is not confirmed after {}s, republish it on the History tab",
+                            receipt.did, timeout
+                        ));
+                    }
+                    known.insert(receipt.message_id.clone(), inclusion.clone());
+                    bus.publish(Message::Inclusion {
+                        message_id: receipt.message_id.clone(),
+                        inclusion,
+                    });
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+    })
+}
+
+pub fn render<'a>(
+    receipts: &[PublishReceipt],
+    inclusions: &HashMap<String, Inclusion>,
+    redacted: bool,
+) -> List<'a> {
+    let items: Vec<ListItem> = receipts
+        .iter()
+        .map(|receipt| {
+            let (badge, color) = match &receipt.republished_as {
+                Some(message_id) => (format!("[republished as {:.12}]", message_id), Color::Gray),
+                None => inclusions
+                    .get(&receipt.message_id)
+                    .unwrap_or(&Inclusion::Pending)
+                    .badge(),
+            };
+            ListItem::new(vec![
+                Spans::from(vec![
+                    Span::styled(format!("{} ", badge), Style::default().fg(color)),
+                    Span::raw(if redacted {
+                        redact::dids(&receipt.did)
+                    } else {
+                        receipt.did.clone()
+                    }),
+                ]),
+                Spans::from(vec![Span::styled(
+                    format!(
+                        "  {} {} at {}{}",
+                        receipt.operation.map_or("published", |op| op.label()),
+                        receipt.message_id,
+                        receipt.published,
+                        if receipt.network.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" on {}", receipt.network)
+                        }
+                    ),
+                    Style::default().fg(Color::Gray),
+                )]),
+                Spans::from(vec![Span::styled(
+                    format!(
+                        "  {}",
+                        receipt
+                            .explorer_url
+                            .as_deref()
+                            .unwrap_or("no explorer link")
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                )]),
+            ])
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title("Published DID messages (u refreshes, e republishes stale ones)")
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+}
@@ -0,0 +1,106 @@
+use crate::events::LogEntry;
+use serde_json::Value;
+
+/// Shown instead of a claim value in presentation mode.
+pub const MASK: &str = "•••";
+const DID_PREFIX: &str = "did:iota:";
+/// Characters of the method specific id kept at the start and the end.
+const KEEP: usize = 4;
+
+/// Shortens every IOTA DID in the text to `did:iota:abcd…wxyz`, fragments stay readable.
+pub fn dids(text: &str) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(DID_PREFIX) {
+        redacted.push_str(&rest[..start + DID_PREFIX.len()]);
+        rest = &rest[start + DID_PREFIX.len()..];
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == ':'))
+            .unwrap_or_else(|| rest.len());
+        // The network name, if any, is kept as it is.
+        let (network, id) = match rest[..end].rfind(':') {
+            Some(colon) => rest[..end].split_at(colon + 1),
+            None => ("", &rest[..end]),
+        };
+        redacted.push_str(network);
+        if id.len() > 2 * KEEP + 1 {
+            redacted.push_str(&id[..KEEP]);
+            redacted.push('…');
+            redacted.push_str(&id[id.len() - KEEP..]);
+        } else {
+            redacted.push_str(id);
+        }
+        rest = &rest[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+/// Masks every value, keeps the keys so the structure of the claims stays visible.
+pub fn value(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), self::value(value)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.iter().map(self::value).collect()),
+        _ => Value::String(MASK.to_string()),
+    }
+}
+
+/// Masks the subject claims and the proof value of a credential or presentation JSON and
+/// shortens its DIDs. Input that is no JSON only gets its DIDs shortened.
+pub fn document(json: &str) -> String {
+    let mut document: Value = match serde_json::from_str(json) {
+        Ok(document) => document,
+        Err(_) => return dids(json),
+    };
+    mask_claims(&mut document);
+    serde_json::to_string_pretty(&document)
+        .map(|json| dids(&json))
+        .unwrap_or_else(|_| dids(json))
+}
+
+fn mask_claims(document: &mut Value) {
+    match document {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                match key.as_str() {
+                    "credentialSubject" => mask_subjects(value),
+                    "signatureValue" | "jws" => *value = Value::String(MASK.to_string()),
+                    _ => mask_claims(value),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(mask_claims),
+        _ => {}
+    }
+}
+
+/// The subject id is kept, the DID gets shortened with the rest of the document.
+fn mask_subjects(subjects: &mut Value) {
+    match subjects {
+        Value::Object(subject) => {
+            for (key, claim) in subject.iter_mut() {
+                if key != "id" {
+                    *claim = value(claim);
+                }
+            }
+        }
+        Value::Array(subjects) => subjects.iter_mut().for_each(mask_subjects),
+        _ => {}
+    }
+}
+
+/// The event log with shortened DIDs.
+pub fn log(entries: &[LogEntry]) -> Vec<LogEntry> {
+    entries
+        .iter()
+        .map(|entry| LogEntry {
+            time: entry.time,
+            message: dids(&entry.message),
+        })
+        .collect()
+}
@@ -0,0 +1,123 @@
+use crate::config::{Config, CredentialStore};
+use crate::core::Service;
+use crate::issue;
+use crate::issued::IssuanceLog;
+use crate::present;
+use crate::signer::Signer;
+use crate::verification::VerificationError;
+use identity::core::{FromJson, ToJson};
+use identity::credential::{Credential, Presentation};
+use identity::crypto::KeyPair;
+use identity::iota::IotaDocument;
+use serde_json::{json, Value};
+use std::error::Error;
+
+/// Type of the refresh service: the holder posts the credential and gets the renewed one back.
+const SERVICE_TYPE: &str = "ManualRefreshService2018";
+
+/// Points the unsigned credential at the `/refresh` endpoint of the HTTP store, if the issuer
+/// runs one and the config asks for it.
+pub fn attach(credential: &mut Credential, config: &Config) -> Result<(), Box<dyn Error>> {
+    if !config.refresh_service || config.credential_store != CredentialStore::Http {
+        return Ok(());
+    }
+    let mut json: Value = credential.to_json_value()?;
+    json["refreshService"] = json!({
+        "id": format!("{}/refresh", config.store_url.trim_end_matches('/')),
+        "type": SERVICE_TYPE,
+    });
+    *credential = Credential::from_json_value(json)?;
+    Ok(())
+}
+
+/// URL of the refresh service the credential names, if any.
+pub fn endpoint(credential: &Credential) -> Option<String> {
+    let json: Value = credential.to_json_value().ok()?;
+    let service = match &json["refreshService"] {
+        Value::Array(services) => services.first()?.clone(),
+        service => service.clone(),
+    };
+    service["id"].as_str().map(str::to_string)
+}
+
+/// Holder side: posts the credential to its refresh service, in a presentation signed by the
+/// holder to prove it is the subject, and returns the renewed one.
+pub async fn request(
+    credential: &Credential,
+    holder: &IotaDocument,
+    key: &KeyPair,
+) -> Result<Credential, Box<dyn Error>> {
+    let url = endpoint(credential).ok_or("the credential names no refresh service")?;
+    let presentation: Presentation = present::present(holder, key, vec![credential.clone()], None)?;
+    let response = reqwest::Client::new()
+        .post(&url)
+        .body(presentation.to_json()?)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(format!("{} refused: {}", url, response.text().await?).into());
+    }
+    Ok(Credential::from_json(&response.text().await?)?)
+}
+
+/// Issuer side: renews a credential this issuer signed whose proof still holds, expired or not.
+/// It comes in a presentation its subject signed, revoked credentials are not renewed.
+/// The claims and id stay, dates and proof are new; the caller records it in the log.
+pub async fn renew(
+    service: &Service,
+    signer: &dyn Signer,
+    validity_days: u64,
+    log: &IssuanceLog,
+    input: &str,
+) -> Result<Credential, Box<dyn Error>> {
+    let presentation: Presentation = Presentation::from_json(input)?;
+    let mut credentials = presentation.verifiable_credential.iter();
+    let credential: &Credential = match (credentials.next(), credentials.next()) {
+        (Some(credential), None) => credential,
+        _ => return Err("the presentation has to hold exactly the credential to renew".into()),
+    };
+    let holder: &str = presentation
+        .holder
+        .as_ref()
+        .map(|holder| holder.as_str())
+        .ok_or("the presentation names no holder")?;
+    if !credential
+        .credential_subject
+        .iter()
+        .any(|subject| subject.id.as_ref().map(|id| id.as_str()) == Some(holder))
+    {
+        return Err("only the subject of the credential may renew it".into());
+    }
+    if !service
+        .verify_presentation(&presentation, &[])
+        .await?
+        .holder_verified
+    {
+        return Err("the presentation is not signed by its holder".into());
+    }
+    let verified = service
+        .verify_credential(&credential.to_json()?)
+        .await?
+        .value
+        .ok_or("the credential is on another network")?;
+    if verified.credential.issuer.url().as_str() != signer.document().id().as_str() {
+        return Err("the credential was not issued here".into());
+    }
+    if !verified.proof {
+        return Err("the credential proof does not verify".into());
+    }
+    let id: String = verified
+        .credential
+        .id
+        .as_ref()
+        .map(|id| id.to_string())
+        .unwrap_or_default();
+    if verified.error == Some(VerificationError::Revoked) || log.is_revoked(&id) {
+        return Err("the credential is revoked".into());
+    }
+    let mut credential: Credential = verified.credential;
+    credential.proof = None;
+    issue::set_validity(&mut credential, validity_days);
+    service.issue_credential(signer, &mut credential).await?;
+    Ok(credential)
+}
@@ -0,0 +1,101 @@
+use crate::branding;
+use crate::dates;
+use crate::issue;
+use identity::core::Value;
+use identity::credential::Credential;
+use std::collections::BTreeMap;
+
+/// Lays out the claims of one credential type on the wallet card.
+/// A new renderer implements this trait and is added to the registry in `Registry::with_defaults`.
+pub trait DetailRenderer: Send + Sync {
+    /// Credential type the renderer lays out.
+    fn credential_type(&self) -> &'static str;
+    /// `label: value` rows shown below the issuer and the title.
+    fn rows(&self, credential: &Credential) -> Vec<(String, String)>;
+}
+
+/// Shows the degree with its grade and the institution that awarded it.
+pub struct Degree;
+
+impl DetailRenderer for Degree {
+    fn credential_type(&self) -> &'static str {
+        issue::DEGREE
+    }
+
+    fn rows(&self, credential: &Credential) -> Vec<(String, String)> {
+        let subject: Value = subject(credential);
+        let degree = match (
+            text(&subject["degree"]["name"]),
+            text(&subject["degree"]["type"]),
+        ) {
+            (Some(name), Some(kind)) => Some(format!("{} ({})", name, kind)),
+            (name, kind) => name.or(kind),
+        };
+        let mut rows = Vec::new();
+        if let Some(degree) = degree {
+            rows.push(("Degree".to_string(), degree));
+        }
+        if let Some(gpa) = text(&subject["GPA"]) {
+            rows.push(("GPA".to_string(), gpa));
+        }
+        if let Some(name) = text(&subject["name"]) {
+            rows.push(("Graduate".to_string(), name));
+        }
+        let institution = branding::of(credential)
+            .map(|display| display.name)
+            .unwrap_or_else(|| credential.issuer.url().to_string());
+        rows.push(("Institution".to_string(), institution));
+        rows.push(("Awarded".to_string(), dates::date(credential.issuance_date)));
+        rows
+    }
+}
+
+/// Claims of the first subject as one JSON object.
+fn subject(credential: &Credential) -> Value {
+    credential
+        .credential_subject
+        .iter()
+        .next()
+        .map(|subject| Value::Object(subject.properties.clone().into_iter().collect()))
+        .unwrap_or(Value::Null)
+}
+
+/// A claim as text, `None` if it is missing.
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// The known renderers by credential type.
+pub struct Registry {
+    renderers: BTreeMap<&'static str, Box<dyn DetailRenderer>>,
+}
+
+impl Registry {
+    pub fn with_defaults() -> Self {
+        let mut registry = Registry {
+            renderers: BTreeMap::new(),
+        };
+        registry.register(Box::new(Degree));
+        registry
+    }
+
+    pub fn register(&mut self, renderer: Box<dyn DetailRenderer>) {
+        self.renderers.insert(renderer.credential_type(), renderer);
+    }
+
+    /// Rows of the card from the renderer of the most specific type of the credential,
+    /// the generic claim table for types without one.
+    pub fn rows(&self, credential: &Credential) -> Vec<(String, String)> {
+        credential
+            .types
+            .iter()
+            .rev()
+            .find_map(|type_| self.renderers.get(type_.as_str()))
+            .map(|renderer| renderer.rows(credential))
+            .unwrap_or_else(|| branding::claims(credential))
+    }
+}
@@ -0,0 +1,179 @@
+use crate::clock;
+use crate::dates;
+use crate::issue;
+use crate::proof::ProofFormat;
+use identity::core::{Timestamp, ToJson};
+use identity::credential::Credential;
+use identity::crypto::{SetSignature, Signature, TrySignature, TrySignatureMut};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Outcome of one credential verification, signed by the verifier when exported.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_id: Option<String>,
+    pub credential_type: String,
+    pub issuer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    pub proof_format: ProofFormat,
+    pub verified: bool,
+    pub outcome: String,
+    pub verifier: String,
+    pub checked: Timestamp,
+    /// The subject issued the credential about itself, nobody else vouches for the claims.
+    #[serde(default)]
+    pub self_issued: bool,
+    /// When the result was checked on the Tangle if it was taken from the verification cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached: Option<Timestamp>,
+    /// Code of the verification error, like `revoked`, if the credential was rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// One line per proof if the credential carries a proof set, like an issuer and a notary proof.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proofs: Vec<String>,
+    /// Checks that did not finish within the time budget.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unknown: Vec<String>,
+    /// Findings about the issuer document, like a method rotated after issuance.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<Signature>,
+}
+
+impl TrySignature for VerificationReport {
+    fn signature(&self) -> Option<&Signature> {
+        self.proof.as_ref()
+    }
+}
+
+impl TrySignatureMut for VerificationReport {
+    fn signature_mut(&mut self) -> Option<&mut Signature> {
+        self.proof.as_mut()
+    }
+}
+
+impl SetSignature for VerificationReport {
+    fn set_signature(&mut self, signature: Signature) {
+        self.proof = Some(signature);
+    }
+}
+
+impl VerificationReport {
+    pub fn new(
+        credential: &Credential,
+        proof_format: ProofFormat,
+        verified: bool,
+        outcome: &str,
+        verifier: &str,
+    ) -> Self {
+        VerificationReport {
+            credential_id: credential.id.as_ref().map(|id| id.to_string()),
+            credential_type: credential.types.iter().last().cloned().unwrap_or_default(),
+            issuer: credential.issuer.url().to_string(),
+            subject: credential
+                .credential_subject
+                .iter()
+                .find_map(|subject| subject.id.as_ref().map(|id| id.to_string())),
+            proof_format,
+            verified,
+            outcome: outcome.to_string(),
+            verifier: verifier.to_string(),
+            checked: clock::now(),
+            self_issued: issue::is_self_issued(credential),
+            cached: None,
+            error: None,
+            proofs: Vec::new(),
+            unknown: Vec::new(),
+            warnings: Vec::new(),
+            proof: None,
+        }
+    }
+
+    /// `cached result from HH:MM` if the result came from the verification cache.
+    pub fn cached_note(&self) -> Option<String> {
+        self.cached.map(|cached| {
+            let time = cached.to_string();
+            format!("cached result from {}", time.get(11..16).unwrap_or(&time))
+        })
+    }
+
+    /// Plain text summary to paste into a ticket.
+    pub fn text(&self) -> String {
+        let mut text = format!(
+            "Verification report\n\
+             Result:      {}\n\
+             Outcome:     {}\n\
+             Credential:  {} {}\n\
+             Issuer:      {}\n\
+             Subject:     {}\n\
+             Proof:       {:?}\n\
+             Checked:     {}\n\
+             Verifier:    {}\n",
+            if self.verified { "VALID" } else { "NOT VALID" },
+            self.outcome,
+            self.credential_type,
+            self.credential_id.as_deref().unwrap_or("(no id)"),
+            self.issuer,
+            self.subject.as_deref().unwrap_or("(no subject id)"),
+            self.proof_format,
+            dates::date_time(self.checked),
+            self.verifier,
+        );
+        if self.self_issued {
+            text.push_str("Self-issued: the subject vouches for these claims itself\n");
+        }
+        if let Some(note) = self.cached_note() {
+            text.push_str(&format!("Cache:       {}\n", note));
+        }
+        for (i, proof) in self.proofs.iter().enumerate() {
+            text.push_str(&format!("Proof {}:     {}\n", i + 1, proof));
+        }
+        for check in &self.unknown {
+            text.push_str(&format!("Check:       {}: unknown (timeout)\n", check));
+        }
+        for warning in &self.warnings {
+            text.push_str(&format!("Warning:     {}\n", warning));
+        }
+        if self.proof.is_some() {
+            text.push_str("The JSON report next to this file carries the verifier signature.\n");
+        }
+        text
+    }
+
+    /// `verification-<last credential id segment>-<timestamp>`, safe as a file name.
+    pub fn file_stem(&self) -> String {
+        let id: &str = self
+            .credential_id
+            .as_deref()
+            .and_then(|id| id.rsplit(|c| c == '/' || c == ':').next())
+            .unwrap_or("credential");
+        let stem = format!("verification-{}-{}", id, self.checked);
+        stem.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect()
+    }
+
+    /// Writes the signed JSON report and the text summary into the directory.
+    pub fn export(&self, dir: &str) -> Result<(PathBuf, PathBuf), Box<dyn Error>> {
+        let dir = Path::new(dir);
+        fs::create_dir_all(dir)?;
+        let json = dir.join(format!("{}.json", self.file_stem()));
+        let text = dir.join(format!("{}.txt", self.file_stem()));
+        fs::write(&json, self.to_json_pretty()?)?;
+        fs::write(&text, self.text())?;
+        Ok((json, text))
+    }
+}
@@ -0,0 +1,436 @@
+use crate::bus::Bus;
+use crate::claims::ClaimsForm;
+use crate::didcomm::{self, Signed};
+use crate::handle;
+use crate::integrity::{self, Integrity};
+use crate::pairing::{self, Peers};
+use crate::state::State;
+use identity::core::{FromJson, Object, Timestamp, ToJson};
+use identity::credential::Credential;
+use identity::crypto::{SetSignature, Signature, TrySignature, TrySignatureMut};
+use identity::iota::{Client, IotaDID};
+use identity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph},
+};
+
+/// A holder asking an issuer for a credential, signed with the holder key as proof of possession.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialRequest {
+    pub credential_type: String,
+    pub claims: Object,
+    pub holder: String,
+    pub created: Timestamp,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<Signature>,
+}
+
+impl TrySignature for CredentialRequest {
+    fn signature(&self) -> Option<&Signature> {
+        self.proof.as_ref()
+    }
+}
+
+impl TrySignatureMut for CredentialRequest {
+    fn signature_mut(&mut self) -> Option<&mut Signature> {
+        self.proof.as_mut()
+    }
+}
+
+impl SetSignature for CredentialRequest {
+    fn set_signature(&mut self, signature: Signature) {
+        self.proof = Some(signature);
+    }
+}
+
+/// The reason an issuer turned a request down, returned to the holder.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rejection {
+    pub request: String,
+    pub credential_type: String,
+    pub holder: String,
+    pub reason: String,
+    pub created: Timestamp,
+}
+
+/// What the holder got back for their requests.
+pub enum Response {
+    Issued(Credential),
+    Rejected(Rejection),
+}
+
+/// Attachment formats of the request and rejection, which no DIDComm protocol defines.
+const REQUEST_FORMAT: &str = "iota-identity-examples/credential-request@v1";
+const REJECTION_FORMAT: &str = "iota-identity-examples/rejection@v1";
+
+/// A request read from the inbox together with the outcome of its proof-of-possession check.
+#[derive(Clone, Debug)]
+pub struct Incoming {
+    pub id: String,
+    pub path: PathBuf,
+    pub request: CredentialRequest,
+    pub verified: bool,
+}
+
+pub fn create(
+    holder: &IotaDocument,
+    key: &KeyPair,
+    credential_type: &str,
+    claims: Object,
+) -> Result<CredentialRequest> {
+    let mut request = CredentialRequest {
+        credential_type: credential_type.to_string(),
+        claims,
+        holder: holder.id().to_string(),
+        created: Timestamp::now_utc(),
+        proof: None,
+    };
+    holder.sign_data(&mut request, key.private())?;
+    Ok(request)
+}
+
+fn requests_dir(inbox: &str) -> PathBuf {
+    Path::new(inbox).join("requests")
+}
+
+/// Delivers the request by dropping it into the issuer inbox, as request message in a DIDComm
+/// envelope signed by the holder.
+pub fn send(
+    inbox: &str,
+    request: &CredentialRequest,
+    holder: &IotaDocument,
+    key: &KeyPair,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let message = didcomm::Message::new(
+        didcomm::REQUEST,
+        json!({ "goal_code": "issue-vc", "comment": request.credential_type }),
+    )
+    .attach(REQUEST_FORMAT, request.to_json_value()?);
+    let json: String = didcomm::sign(message, holder, key)?;
+    let dir = requests_dir(inbox);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.json", &handle::hash(&json)[..16]));
+    fs::write(&path, json)?;
+    Ok(path)
+}
+
+/// Reads all requests in the inbox and checks the holder signature of each against the Tangle.
+pub async fn receive(client: &Client, inbox: &str) -> Result<Vec<Incoming>> {
+    let entries = match fs::read_dir(requests_dir(inbox)) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let mut incoming = Vec::new();
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        let (request, signed) = match fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| read_request(&json))
+        {
+            Some(read) => read,
+            None => continue,
+        };
+        let verified = verify(client, &request, signed.as_ref()).await;
+        let id = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default()
+            .to_string();
+        incoming.push(Incoming {
+            id,
+            path,
+            request,
+            verified,
+        });
+    }
+    incoming.sort_by(|a, b| a.request.created.cmp(&b.request.created));
+    Ok(incoming)
+}
+
+/// The request in a DIDComm envelope and the envelope signature, or a bare request of
+/// earlier versions.
+fn read_request(json: &str) -> Option<(CredentialRequest, Option<Signed>)> {
+    match didcomm::unpack(json, &[]) {
+        Ok(unpacked) if unpacked.message.type_ == didcomm::REQUEST => {
+            let request = unpacked.message.attachment(REQUEST_FORMAT)?.clone();
+            Some((
+                CredentialRequest::from_json_value(request).ok()?,
+                unpacked.signed,
+            ))
+        }
+        Ok(_) => None,
+        Err(_) => Some((CredentialRequest::from_json(json).ok()?, None)),
+    }
+}
+
+/// Delivers the issued credential to the holder and takes the request off the queue.
+pub fn accept(
+    inbox: &str,
+    peers: &Peers,
+    incoming: &Incoming,
+    credential: &Credential,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let path = deliver(inbox, peers, &incoming.id, credential)?;
+    fs::remove_file(&incoming.path)?;
+    Ok(path)
+}
+
+/// Drops the credential as issue message into the inbox the holder collects credentials from,
+/// in a plaintext DIDComm envelope, or encrypted into the channel of the holder device if it
+/// was paired.
+pub fn deliver(
+    inbox: &str,
+    peers: &Peers,
+    id: &str,
+    credential: &Credential,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let paired = credential
+        .credential_subject
+        .iter()
+        .find_map(|subject| subject.id.as_ref())
+        .and_then(|holder| peers.for_holder(holder.as_str()));
+    if let Some(peer) = paired {
+        return pairing::send(inbox, peer, id, credential);
+    }
+    let dir = Path::new(inbox).join("credentials");
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.json", id));
+    fs::write(
+        &path,
+        didcomm::plain(&didcomm::credential(didcomm::ISSUE, credential)?)?,
+    )?;
+    Ok(path)
+}
+
+/// Returns the reason to the holder as DIDComm problem report and takes the request off the
+/// queue.
+pub fn reject(inbox: &str, incoming: &Incoming, reason: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let rejection = Rejection {
+        request: incoming.id.clone(),
+        credential_type: incoming.request.credential_type.clone(),
+        holder: incoming.request.holder.clone(),
+        reason: reason.to_string(),
+        created: Timestamp::now_utc(),
+    };
+    let message = didcomm::Message::new(
+        didcomm::PROBLEM,
+        json!({ "code": "e.p.req.rejected", "comment": reason }),
+    )
+    .to(&incoming.request.holder)
+    .attach(REJECTION_FORMAT, rejection.to_json_value()?);
+    let dir = Path::new(inbox).join("rejections");
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.json", incoming.id));
+    fs::write(&path, didcomm::plain(&message)?)?;
+    fs::remove_file(&incoming.path)?;
+    Ok(path)
+}
+
+/// Collects the credentials and rejections the issuer sent back to the holder.
+pub fn responses(inbox: &str, holder: &str) -> Vec<Response> {
+    let read = |dir: &str| -> Vec<String> {
+        fs::read_dir(Path::new(inbox).join(dir))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    // Envelopes, or the bare JSON of earlier versions.
+    let unpacked = |json: &String| {
+        didcomm::unpack(json, &[])
+            .ok()
+            .map(|unpacked| unpacked.message)
+    };
+    let issued = read("credentials")
+        .into_iter()
+        .filter_map(|json| match unpacked(&json) {
+            Some(message) => didcomm::credential_of(&message),
+            None => Credential::from_json(&json).ok(),
+        })
+        .filter(|credential| {
+            credential
+                .credential_subject
+                .iter()
+                .any(|subject| subject.id.as_ref().map(|id| id.as_str()) == Some(holder))
+        })
+        .map(Response::Issued);
+    let rejected = read("rejections")
+        .into_iter()
+        .filter_map(|json| match unpacked(&json) {
+            Some(message) if message.type_ == didcomm::PROBLEM => {
+                Rejection::from_json_value(message.attachment(REJECTION_FORMAT)?.clone()).ok()
+            }
+            Some(_) => None,
+            None => Rejection::from_json(&json).ok(),
+        })
+        .filter(|rejection| rejection.holder == holder)
+        .map(Response::Rejected);
+
+    issued.chain(rejected).collect()
+}
+
+fn files(dir: &Path) -> HashSet<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reports requests and credentials dropped into the inbox while the app is running.
+pub fn spawn_watcher(inbox: &str, interval: Duration, state: State, bus: Bus) -> JoinHandle<()> {
+    let requests = requests_dir(inbox);
+    let credentials = Path::new(inbox).join("credentials");
+
+    tokio::spawn(async move {
+        let mut seen_requests = files(&requests);
+        let mut seen_credentials = files(&credentials);
+        loop {
+            tokio::time::sleep(interval).await;
+            let current = files(&requests);
+            for path in current.difference(&seen_requests) {
+                bus.log(format!("Incoming credential request {}", path.display()));
+            }
+            seen_requests = current;
+            let current = files(&credentials);
+            let holder: String = state.read().await.holder.clone();
+            for path in current.difference(&seen_credentials) {
+                let for_holder = fs::read_to_string(path)
+                    .map(|json| json.contains(&holder))
+                    .unwrap_or_default();
+                if for_holder {
+                    bus.log(format!("Incoming credential for you {}", path.display()));
+                } else {
+                    bus.log(format!("Incoming credential {}", path.display()));
+                }
+            }
+            seen_credentials = current;
+        }
+    })
+}
+
+/// Checks the request proof, and the envelope signature if it came in a signed envelope,
+/// which has to be made by the holder too.
+async fn verify(client: &Client, request: &CredentialRequest, signed: Option<&Signed>) -> bool {
+    let did: IotaDID = match IotaDID::parse(&request.holder) {
+        Ok(did) => did,
+        Err(_) => return false,
+    };
+    if signed.map_or(false, |signed| signed.did() != request.holder) {
+        return false;
+    }
+    match integrity::resolve(client, &did).await {
+        Ok((document, report)) => {
+            report.integrity != Integrity::Invalid
+                && document.verify_data(request).is_ok()
+                && signed.map_or(true, |signed| signed.check(&document))
+        }
+        Err(_) => false,
+    }
+}
+
+pub fn render_request<'a>(
+    form: &ClaimsForm,
+    holder: &str,
+    status: &str,
+    responses: &[Response],
+) -> Paragraph<'a> {
+    let mut lines = vec![
+        Spans::from(vec![Span::raw(format!("Holder: {}", holder))]),
+        Spans::from(vec![Span::raw("")]),
+    ];
+    lines.extend(form.lines("signs and sends the request"));
+    lines.push(Spans::from(vec![Span::styled(
+        status.to_string(),
+        Style::default().fg(Color::LightCyan),
+    )]));
+    lines.push(Spans::from(vec![Span::raw("")]));
+    lines.push(Spans::from(vec![Span::raw("Responses:")]));
+    for response in responses {
+        lines.push(match response {
+            Response::Issued(credential) => Spans::from(vec![
+                Span::styled("[issued] ", Style::default().fg(Color::Green)),
+                Span::raw(credential.types.iter().last().cloned().unwrap_or_default()),
+            ]),
+            Response::Rejected(rejection) => Spans::from(vec![
+                Span::styled("[rejected] ", Style::default().fg(Color::Red)),
+                Span::raw(format!(
+                    "{}: {}",
+                    rejection.credential_type, rejection.reason
+                )),
+            ]),
+        });
+    }
+
+    Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title("Request credential")
+            .border_type(BorderType::Plain),
+    )
+}
+
+pub fn render_inbox<'a>(incoming: &[Incoming], prompt: Option<&str>) -> List<'a> {
+    let items: Vec<ListItem> = incoming
+        .iter()
+        .map(|incoming| {
+            let (badge, color) = if incoming.verified {
+                ("[signed]", Color::Green)
+            } else {
+                ("[invalid signature]", Color::Red)
+            };
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{} ", badge), Style::default().fg(color)),
+                Span::raw(format!(
+                    "{} for {} ({} claims)",
+                    incoming.request.credential_type,
+                    incoming.request.holder,
+                    incoming.request.claims.len()
+                )),
+            ]))
+        })
+        .collect();
+
+    let title = match prompt {
+        Some(prompt) => prompt.to_string(),
+        None => "Inbox: credential requests (enter accepts, x rejects)".to_string(),
+    };
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(title)
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+}
@@ -0,0 +1,56 @@
+use crate::integrity::Report;
+use identity::core::Timestamp;
+use identity::iota::{IotaDocument, TangleRef};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Context of the DID Resolution Result, as the Universal Resolver returns it.
+const CONTEXT: &str = "https://w3id.org/did-resolution/v1";
+/// Media type of the document inside the result.
+const DOCUMENT_TYPE: &str = "application/did+ld+json";
+/// Media type of the whole result.
+pub const CONTENT_TYPE: &str = "application/ld+json;profile=\"https://w3id.org/did-resolution\"";
+
+/// The resolved document in the DID Resolution Result envelope, with the integration message
+/// as `versionId` and the integrity check of this app as method specific metadata.
+pub fn result(document: &IotaDocument, report: &Report, duration: Option<Duration>) -> Value {
+    let mut document_metadata = json!({
+        "created": document.created(),
+        "updated": document.updated(),
+        "versionId": document.message_id().to_string(),
+        "integrity": report.integrity.badge().0.trim_matches(|c| c == '[' || c == ']'),
+        "versions": report.versions.len(),
+    });
+    if !document.previous_message_id().is_null() {
+        document_metadata["previousVersionId"] =
+            Value::String(document.previous_message_id().to_string());
+    }
+    let mut resolution_metadata = json!({
+        "contentType": DOCUMENT_TYPE,
+        "retrieved": Timestamp::now_utc(),
+        "integrityDetails": report.details,
+    });
+    if let Some(duration) = duration {
+        resolution_metadata["duration"] = json!(duration.as_millis() as u64);
+    }
+    json!({
+        "@context": CONTEXT,
+        "didDocument": document,
+        "didDocumentMetadata": document_metadata,
+        "didResolutionMetadata": resolution_metadata,
+    })
+}
+
+/// The result of a failed resolution, `error` one of the registered codes like `invalidDid`
+/// or `notFound`.
+pub fn failure(error: &str, message: &str) -> Value {
+    json!({
+        "@context": CONTEXT,
+        "didDocument": null,
+        "didDocumentMetadata": {},
+        "didResolutionMetadata": {
+            "error": error,
+            "message": message,
+        },
+    })
+}
@@ -0,0 +1,146 @@
+use crate::bus::{Bus, Done, Message};
+use crate::config::Config;
+use crate::core::Service;
+use crate::verification::VerificationError;
+use identity::iota::IotaDID;
+use std::error::Error;
+use std::future::Future;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Longest wait between two attempts, however many failed before.
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// How often a resolution or verification is tried, waiting twice as long after each failure.
+#[derive(Clone, Copy, Debug)]
+pub struct Backoff {
+    pub attempts: u32,
+    pub base: Duration,
+}
+
+impl Backoff {
+    pub fn new(config: &Config) -> Self {
+        Backoff {
+            attempts: config.retry_attempts.max(1),
+            base: Duration::from_millis(config.retry_backoff_ms),
+        }
+    }
+
+    /// Wait after the failed attempt, counted from 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.base
+            .checked_mul(1 << attempt.saturating_sub(1).min(16))
+            .map_or(MAX_DELAY, |delay| delay.min(MAX_DELAY))
+    }
+}
+
+/// Whether another attempt may succeed: the nodes or the issuer could not be reached, while a
+/// credential that does not parse fails the same way every time.
+pub fn transient(error: &(dyn Error + 'static)) -> bool {
+    match error.downcast_ref::<VerificationError>() {
+        Some(VerificationError::ResolutionFailed(_)) | Some(VerificationError::Timeout) => true,
+        Some(_) => false,
+        None => true,
+    }
+}
+
+/// Runs the operation until it succeeds, fails for good or the attempts run out, and shows
+/// the attempt counter on the bus while it runs.
+pub async fn run<T, F, Fut>(
+    backoff: Backoff,
+    bus: &Bus,
+    label: &str,
+    mut operation: F,
+) -> Result<T, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Box<dyn Error>>>,
+{
+    let mut attempt: u32 = 1;
+    loop {
+        bus.publish(Message::Progress(Some(format!(
+            "{} (attempt {}/{}), Esc cancels",
+            label, attempt, backoff.attempts
+        ))));
+        let error: String = match operation().await {
+            Ok(value) => {
+                bus.publish(Message::Progress(None));
+                return Ok(value);
+            }
+            Err(error) if attempt >= backoff.attempts || !transient(&*error) => {
+                bus.publish(Message::Progress(None));
+                return Err(error.to_string());
+            }
+            Err(error) => error.to_string(),
+        };
+        let delay = backoff.delay(attempt);
+        bus.log(format!(
+            "{} failed (attempt {}/{}): {}, retrying in {:.1}s",
+            label,
+            attempt,
+            backoff.attempts,
+            error,
+            delay.as_secs_f32()
+        ));
+        bus.publish(Message::Progress(Some(format!(
+            "{} failed (attempt {}/{}), retrying in {:.1}s, Esc cancels",
+            label,
+            attempt,
+            backoff.attempts,
+            delay.as_secs_f32()
+        ))));
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Verifies the credential in the background, the outcome arrives on the bus as
+/// `Done::Verified` of the task. Aborting the handle cancels it.
+pub fn verify(
+    service: Service,
+    bus: Bus,
+    backoff: Backoff,
+    task: u64,
+    input: String,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let (service, input) = (&service, &input);
+        let mut attempt: u32 = 0;
+        let checked = run(backoff, &bus, "Verifying", move || {
+            attempt += 1;
+            let last: bool = attempt >= backoff.attempts;
+            async move {
+                let checked = service.verify_credential(input).await?;
+                // An issuer that could not be resolved is tried again, the last report is kept.
+                match checked
+                    .value
+                    .as_ref()
+                    .and_then(|verified| verified.error.clone())
+                {
+                    Some(error @ VerificationError::ResolutionFailed(_)) if !last => {
+                        Err(error.into())
+                    }
+                    _ => Ok(checked),
+                }
+            }
+        })
+        .await;
+        bus.finish(task, Done::Verified(checked));
+    })
+}
+
+/// Resolves the DID in the background, the outcome arrives on the bus as `Done::Resolved`
+/// of the task. Aborting the handle cancels it.
+pub fn resolve(
+    service: Service,
+    bus: Bus,
+    backoff: Backoff,
+    task: u64,
+    did: IotaDID,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let label = format!("Resolving {}", did);
+        let resolved = run(backoff, &bus, &label, || service.resolve(&did)).await;
+        bus.finish(task, Done::Resolved(resolved));
+    })
+}
@@ -0,0 +1,132 @@
+use crate::config::{Config, CredentialStore};
+use crate::issued::IssuanceLog;
+use identity::core::{FromJson, Timestamp, ToJson, Url};
+use identity::credential::Credential;
+use identity::iota::IotaDocument;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// Type of the status entry: the credential counts as revoked while the list names its id.
+pub const STATUS_TYPE: &str = "CredentialStatusList2017";
+/// Fragment of the service in the issuer document that names the HTTP store its list is at.
+pub const SERVICE: &str = "status-list";
+/// File in the store directory the server hands out at `/status`.
+pub const FILE: &str = "status.json";
+
+/// The revoked credentials of the issuer, as served at `<store_url>/status`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StatusList {
+    pub id: String,
+    pub issuer: String,
+    pub updated: Timestamp,
+    pub revoked: Vec<Revoked>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Revoked {
+    /// Id of the credential.
+    pub id: String,
+    pub revoked: Timestamp,
+}
+
+/// URL the HTTP store serves the status list at.
+pub fn url(config: &Config) -> String {
+    format!("{}/status", config.store_url.trim_end_matches('/'))
+}
+
+/// Points the unsigned credential at its entry in the status list, if the issuer runs the
+/// HTTP store.
+pub fn attach(credential: &mut Credential, config: &Config) -> Result<(), Box<dyn Error>> {
+    if config.credential_store != CredentialStore::Http {
+        return Ok(());
+    }
+    let id = credential
+        .id
+        .as_ref()
+        .map(|id| id.to_string())
+        .ok_or("the credential has no id to revoke it by")?;
+    let mut json: Value = credential.to_json_value()?;
+    json["credentialStatus"] = json!({
+        "id": format!("{}#{}", url(config), id),
+        "type": STATUS_TYPE,
+    });
+    *credential = Credential::from_json_value(json)?;
+    Ok(())
+}
+
+/// Whether the URL is a service endpoint of the document or lies below one.
+pub fn is_hosted_by(document: &IotaDocument, url: &Url) -> bool {
+    document.service().iter().any(|service| {
+        let endpoint: &str = service.service_endpoint().as_str().trim_end_matches('/');
+        url.as_str() == endpoint || url.as_str().starts_with(&format!("{}/", endpoint))
+    })
+}
+
+/// When the issuer revoked the credential, by the status list its `credentialStatus` names.
+/// `None` if the list does not name it or the credential has no entry in a status list.
+/// Only lists under a service endpoint of the `issuer` document are fetched, the credential
+/// can't send the verifier to any other host.
+pub async fn revoked(
+    credential: &Credential,
+    issuer: &IotaDocument,
+) -> Result<Option<Timestamp>, Box<dyn Error>> {
+    let json: Value = credential.to_json_value()?;
+    let status: &Value = &json["credentialStatus"];
+    if status["type"].as_str() != Some(STATUS_TYPE) {
+        return Ok(None);
+    }
+    let (list, id) = status["id"]
+        .as_str()
+        .and_then(|entry| entry.split_once('#'))
+        .ok_or("the credential status names no entry of the status list")?;
+    let list: Url = Url::parse(list)?;
+    if !is_hosted_by(issuer, &list) {
+        return Err(format!("{} is not under a service endpoint of the issuer", list).into());
+    }
+    let response = reqwest::get(list.as_str()).await?;
+    if !response.status().is_success() {
+        return Err(format!("{} answered {}", list, response.status()).into());
+    }
+    let list: StatusList = serde_json::from_str(&response.text().await?)?;
+    if list.issuer != credential.issuer.url().as_str() {
+        return Err(format!("the status list {} is not the one of the issuer", list.id).into());
+    }
+    Ok(list
+        .revoked
+        .iter()
+        .find(|revoked| revoked.id == id)
+        .map(|revoked| revoked.revoked))
+}
+
+/// Writes the status list of the issuer from the issuance log, for the server to hand out.
+/// Does nothing without the HTTP store.
+pub fn publish(config: &Config, log: &IssuanceLog, issuer: &str) -> Result<(), Box<dyn Error>> {
+    if config.credential_store != CredentialStore::Http {
+        return Ok(());
+    }
+    let list = StatusList {
+        id: url(config),
+        issuer: issuer.to_string(),
+        updated: Timestamp::now_utc(),
+        revoked: log
+            .records
+            .iter()
+            .filter(|record| record.issuer == issuer)
+            .filter_map(|record| {
+                record.revoked.map(|revoked| Revoked {
+                    id: record.id.clone(),
+                    revoked,
+                })
+            })
+            .collect(),
+    };
+    fs::create_dir_all(&config.store_dir)?;
+    fs::write(
+        Path::new(&config.store_dir).join(FILE),
+        serde_json::to_string_pretty(&list)?,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,326 @@
+use crate::config::Config;
+use crate::core::Service;
+use crate::ids;
+use crate::issue;
+use crate::nodes;
+use crate::proof;
+use crate::quickstart::{artifact, note, step};
+use crate::seed;
+use crate::verification::{self, Verified};
+use crate::wallet::Wallet;
+use identity::core::Object;
+use identity::credential::Credential;
+use identity::prelude::*;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+
+/// A scripted demo: the steps run in order, failing assertions are counted, failing steps stop it.
+#[derive(Debug, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: String,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Step {
+    Do(Operation),
+    Assert(Assertion),
+}
+
+/// What a step does, named by its `do`. Identities and credentials are named by `as` and
+/// referred to by that name in later steps.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "do", rename_all = "kebab-case")]
+pub enum Operation {
+    /// Creates and publishes an identity.
+    CreateIdentity {
+        #[serde(rename = "as")]
+        name: String,
+    },
+    /// Issues and signs a credential of the identity `issuer` about `holder`.
+    Issue {
+        #[serde(rename = "as")]
+        name: String,
+        issuer: String,
+        holder: String,
+        /// `enrollment`, `membership` or a credential type with `claims`.
+        #[serde(rename = "type")]
+        type_: String,
+        #[serde(default)]
+        claims: Object,
+    },
+    /// Changes a claim after signing, so the proof no longer matches.
+    Tamper { credential: String },
+    /// Verifies the credential, the result is kept for `verified` assertions.
+    Verify { credential: String },
+    /// Takes the credential into the scenario wallet.
+    Store { credential: String },
+}
+
+/// What must hold at this point, named by its `assert`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "assert", rename_all = "kebab-case")]
+pub enum Assertion {
+    /// The last verification of the credential passed, or failed with `expected: false`.
+    Verified {
+        credential: String,
+        #[serde(default = "passes")]
+        expected: bool,
+    },
+    /// The scenario wallet holds exactly this many credentials.
+    CredentialCount { equals: usize },
+    /// The credential names the identity as issuer.
+    Issuer {
+        credential: String,
+        identity: String,
+    },
+}
+
+fn passes() -> bool {
+    true
+}
+
+impl Step {
+    fn title(&self) -> String {
+        match self {
+            Step::Do(Operation::CreateIdentity { name }) => format!("Create the identity {}", name),
+            Step::Do(Operation::Issue {
+                name,
+                issuer,
+                holder,
+                type_,
+                ..
+            }) => format!("{} issues {} ({}) to {}", issuer, name, type_, holder),
+            Step::Do(Operation::Tamper { credential }) => format!("Tamper with {}", credential),
+            Step::Do(Operation::Verify { credential }) => format!("Verify {}", credential),
+            Step::Do(Operation::Store { credential }) => {
+                format!("Store {} in the wallet", credential)
+            }
+            Step::Assert(Assertion::Verified {
+                credential,
+                expected: true,
+            }) => format!("Assert {} verifies", credential),
+            Step::Assert(Assertion::Verified { credential, .. }) => {
+                format!("Assert {} does not verify", credential)
+            }
+            Step::Assert(Assertion::CredentialCount { equals }) => {
+                format!("Assert the wallet holds {} credentials", equals)
+            }
+            Step::Assert(Assertion::Issuer {
+                credential,
+                identity,
+            }) => format!("Assert {} is issued by {}", credential, identity),
+        }
+    }
+}
+
+/// Identities, credentials and results the steps refer to by name.
+#[derive(Default)]
+struct Names {
+    identities: HashMap<String, (IotaDocument, KeyPair)>,
+    /// Signed credentials, JSON or JWS.
+    credentials: HashMap<String, String>,
+    verified: HashMap<String, Verified>,
+    wallet: Wallet,
+}
+
+impl Names {
+    fn identity(
+        &self,
+        name: &str,
+    ) -> std::result::Result<&(IotaDocument, KeyPair), Box<dyn Error>> {
+        self.identities
+            .get(name)
+            .ok_or_else(|| format!("no identity named {} was created before", name).into())
+    }
+
+    fn credential(&self, name: &str) -> std::result::Result<&String, Box<dyn Error>> {
+        self.credentials
+            .get(name)
+            .ok_or_else(|| format!("no credential named {} was issued before", name).into())
+    }
+}
+
+pub fn load(path: &str) -> std::result::Result<Scenario, Box<dyn Error>> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Runs the scenario without the TUI and prints every step, returns the failed assertions.
+/// Identities live in memory unless `tangle` publishes them to the configured nodes.
+/// Steps that fail, like a resolution the nodes refuse, end the run with the error.
+pub async fn run(
+    config: &Config,
+    scenario: &Scenario,
+    seed: Option<&str>,
+    tangle: bool,
+) -> std::result::Result<usize, Box<dyn Error>> {
+    let service = if tangle {
+        Service::new(config, nodes::from_urls(&config.nodes))
+    } else {
+        Service::in_memory(config)
+    };
+    let mut names = Names::default();
+    let mut failed: usize = 0;
+    if !scenario.name.is_empty() {
+        note(&format!("Scenario {}", scenario.name));
+    }
+    for (index, current) in scenario.steps.iter().enumerate() {
+        step(index + 1, &current.title());
+        match current {
+            Step::Do(operation) => {
+                perform(config, &service, &mut names, operation, seed).await?;
+            }
+            Step::Assert(assertion) => match check(&names, assertion) {
+                Ok(()) => note("passed"),
+                Err(error) => {
+                    note(&format!("FAILED: {}", error));
+                    failed += 1;
+                }
+            },
+        }
+    }
+    note(&format!(
+        "{} of {} assertions failed",
+        failed,
+        scenario
+            .steps
+            .iter()
+            .filter(|step| matches!(step, Step::Assert(_)))
+            .count()
+    ));
+    Ok(failed)
+}
+
+async fn perform(
+    config: &Config,
+    service: &Service,
+    names: &mut Names,
+    operation: &Operation,
+    seed: Option<&str>,
+) -> std::result::Result<(), Box<dyn Error>> {
+    match operation {
+        Operation::CreateIdentity { name } => {
+            let (document, key, published) = service
+                .register_identity(seed::keypair(seed, &format!("scenario-{}", name))?)
+                .await?;
+            note(&format!(
+                "{} is {}, published in {}",
+                name,
+                document.id(),
+                published
+            ));
+            names.identities.insert(name.clone(), (document, key));
+        }
+        Operation::Issue {
+            name,
+            issuer,
+            holder,
+            type_,
+            claims,
+        } => {
+            let (issuer_doc, issuer_key) = names.identity(issuer)?;
+            let (holder_doc, _) = names.identity(holder)?;
+            let credential: Credential = match type_.as_str() {
+                "enrollment" => issue::issue_enrollment(issuer_doc, holder_doc)?,
+                "membership" => issue::issue_membership(issuer_doc, holder_doc)?,
+                _ => issue::issue_claims(
+                    issuer_doc,
+                    &ids::uuid(),
+                    holder_doc.id().as_str(),
+                    type_,
+                    &[],
+                    claims.clone(),
+                )?,
+            };
+            let signed: String =
+                proof::sign(issuer_doc, issuer_key, credential, config.proof_format)?;
+            artifact(name, &signed);
+            names.credentials.insert(name.clone(), signed);
+        }
+        Operation::Tamper { credential } => {
+            let mut json: Value = serde_json::from_str(names.credential(credential)?)
+                .map_err(|_| "tampering needs a credential with an embedded proof")?;
+            json["credentialSubject"]["tampered"] = Value::Bool(true);
+            names
+                .credentials
+                .insert(credential.clone(), serde_json::to_string_pretty(&json)?);
+            note(&format!("Added a claim to {} after signing", credential));
+        }
+        Operation::Verify { credential } => {
+            let verified: Verified = service
+                .verify_credential(names.credential(credential)?)
+                .await?
+                .value
+                .ok_or("the issuer is on another network than the client")?;
+            note(&match &verified.error {
+                None => format!("{} verifies", credential),
+                Some(error) => format!("{} is rejected: {} ({})", credential, error, error.code()),
+            });
+            names.verified.insert(credential.clone(), verified);
+        }
+        Operation::Store { credential } => {
+            let parsed = verification::parse(names.credential(credential)?)?;
+            if !names.wallet.add(parsed.credential)? {
+                note(&format!("{} is held already", credential));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check(names: &Names, assertion: &Assertion) -> std::result::Result<(), String> {
+    match assertion {
+        Assertion::Verified {
+            credential,
+            expected,
+        } => match names.verified.get(credential) {
+            Some(verified) if verified.verified == *expected => Ok(()),
+            Some(verified) => Err(format!(
+                "{} {}",
+                credential,
+                match &verified.error {
+                    Some(error) => format!("does not verify: {}", error),
+                    None => "verifies".to_string(),
+                }
+            )),
+            None => Err(format!("{} was not verified before", credential)),
+        },
+        Assertion::CredentialCount { equals } => {
+            let held: usize = names
+                .wallet
+                .entries
+                .iter()
+                .filter(|entry| entry.archived.is_none())
+                .count();
+            if held == *equals {
+                Ok(())
+            } else {
+                Err(format!("the wallet holds {} credentials", held))
+            }
+        }
+        Assertion::Issuer {
+            credential,
+            identity,
+        } => {
+            let did: String = names
+                .identity(identity)
+                .map_err(|error| error.to_string())?
+                .0
+                .id()
+                .to_string();
+            let parsed = names
+                .credential(credential)
+                .map_err(|error| error.to_string())
+                .and_then(|input| verification::parse(input).map_err(|error| error.to_string()))?;
+            match parsed.credential.issuer.url().as_str() {
+                issuer if issuer == did => Ok(()),
+                issuer => Err(format!("{} is issued by {}", credential, issuer)),
+            }
+        }
+    }
+}
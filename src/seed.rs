@@ -0,0 +1,30 @@
+use identity::prelude::*;
+use sha2::{Digest, Sha256};
+
+pub const WARNING: &str =
+    "SEEDED IDENTITIES ARE INSECURE: anyone knowing the seed can derive the private keys. \
+Only use --seed for screenshots, tests and tutorials.";
+
+/// Derives the key pair for `label` from the seed, so the same seed always yields the same DIDs.
+/// Without a seed a random key pair is generated.
+pub fn keypair(seed: Option<&str>, label: &str) -> Result<KeyPair> {
+    match seed {
+        Some(seed) => {
+            let private = Sha256::new()
+                .chain(seed.as_bytes())
+                .chain(b"/")
+                .chain(label.as_bytes())
+                .finalize();
+            KeyPair::try_from_ed25519_bytes(&private)
+        }
+        None => KeyPair::new_ed25519(),
+    }
+}
+
+/// Prints the warning so that it can't be missed in the terminal.
+pub fn warn() {
+    let line = "!".repeat(80);
+    eprintln!("{}", line);
+    eprintln!("{}", WARNING);
+    eprintln!("{}", line);
+}
@@ -0,0 +1,168 @@
+use crate::config::Config;
+use crate::integrity;
+use crate::issue;
+use crate::nodes;
+use crate::verify;
+use identity::credential::Credential;
+use identity::iota::{Client, Receipt, TangleRef};
+use identity::prelude::*;
+use std::time::{Duration, Instant};
+
+enum Outcome {
+    Pass,
+    Fail(String),
+    Skip,
+}
+
+struct Check {
+    name: &'static str,
+    outcome: Outcome,
+    elapsed: Duration,
+}
+
+/// Exercises create/publish/resolve/issue/sign/verify against the node and prints a pass/fail matrix.
+/// Steps whose input is missing because an earlier step failed are skipped.
+pub async fn run(config: &Config, node: Option<String>) -> bool {
+    let urls: Vec<String> = node
+        .map(|node| vec![node])
+        .unwrap_or_else(|| config.nodes.clone());
+    println!("Self-test against {}", urls.join(", "));
+    let mut checks: Vec<Check> = Vec::new();
+
+    let started = Instant::now();
+    let client: Option<Client> = record(
+        &mut checks,
+        "connect",
+        started,
+        nodes::client(&nodes::from_urls(&urls)).await,
+    );
+
+    let started = Instant::now();
+    let created: Option<(IotaDocument, KeyPair)> = record(&mut checks, "create", started, create());
+
+    let published: Option<IotaDocument> = match (&client, created.as_ref()) {
+        (Some(client), Some((document, _))) => {
+            let started = Instant::now();
+            let mut document: IotaDocument = document.clone();
+            let receipt: Result<Receipt> = client.publish_document(&document).await;
+            record(&mut checks, "publish", started, receipt).map(|receipt| {
+                document.set_message_id(*receipt.message_id());
+                document
+            })
+        }
+        _ => skip(&mut checks, "publish"),
+    };
+
+    let resolved: Option<IotaDocument> = match (&client, &published) {
+        (Some(client), Some(document)) => {
+            let started = Instant::now();
+            let resolved = integrity::resolve(client, document.id())
+                .await
+                .map(|(resolved, _)| resolved);
+            record(&mut checks, "resolve", started, resolved)
+        }
+        _ => skip(&mut checks, "resolve"),
+    };
+
+    let issued: Option<Credential> = match &resolved {
+        Some(document) => {
+            let started = Instant::now();
+            record(
+                &mut checks,
+                "issue",
+                started,
+                issue::issue_enrollment(document, document),
+            )
+        }
+        None => skip(&mut checks, "issue"),
+    };
+
+    let signed: Option<Credential> = match (issued, &resolved, &created) {
+        (Some(mut credential), Some(document), Some((_, key))) => {
+            let started = Instant::now();
+            let signed = document
+                .sign_data(&mut credential, key.private())
+                .map(|_| credential);
+            record(&mut checks, "sign", started, signed)
+        }
+        _ => skip(&mut checks, "sign"),
+    };
+
+    match (&client, &signed) {
+        (Some(client), Some(credential)) => {
+            let started = Instant::now();
+            let verified = match verify::verify_credential(client, credential).await {
+                Ok(validation) if validation.verified => Ok(()),
+                Ok(_) => Err("the credential did not verify".to_string()),
+                Err(error) => Err(error.to_string()),
+            };
+            record(&mut checks, "verify", started, verified);
+        }
+        _ => {
+            skip::<()>(&mut checks, "verify");
+        }
+    }
+
+    println!();
+    for check in &checks {
+        let (outcome, reason) = match &check.outcome {
+            Outcome::Pass => ("PASS", String::new()),
+            Outcome::Fail(reason) => ("FAIL", reason.clone()),
+            Outcome::Skip => ("SKIP", String::new()),
+        };
+        println!(
+            "{:<8} {:<4} {:>6} ms  {}",
+            check.name,
+            outcome,
+            check.elapsed.as_millis(),
+            reason
+        );
+    }
+
+    checks
+        .iter()
+        .all(|check| matches!(check.outcome, Outcome::Pass))
+}
+
+fn create() -> Result<(IotaDocument, KeyPair)> {
+    let key: KeyPair = KeyPair::new_ed25519()?;
+    let mut document: IotaDocument = IotaDocument::new(&key)?;
+    document.sign(key.private())?;
+    Ok((document, key))
+}
+
+fn record<T, E: ToString>(
+    checks: &mut Vec<Check>,
+    name: &'static str,
+    started: Instant,
+    result: std::result::Result<T, E>,
+) -> Option<T> {
+    let elapsed = started.elapsed();
+    match result {
+        Ok(value) => {
+            checks.push(Check {
+                name,
+                outcome: Outcome::Pass,
+                elapsed,
+            });
+            Some(value)
+        }
+        Err(error) => {
+            checks.push(Check {
+                name,
+                outcome: Outcome::Fail(error.to_string()),
+                elapsed,
+            });
+            None
+        }
+    }
+}
+
+fn skip<T>(checks: &mut Vec<Check>, name: &'static str) -> Option<T> {
+    checks.push(Check {
+        name,
+        outcome: Outcome::Skip,
+        elapsed: Duration::default(),
+    });
+    None
+}
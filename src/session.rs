@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+
+/// What is on screen, kept across restarts so a demo continues where it stopped.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Session {
+    /// Title of the active tab.
+    pub tab: String,
+    /// Seed label of the holder like `holder-2`, `None` for an imported holder.
+    pub holder: Option<String>,
+    pub holder_did: String,
+    /// Last issued credential, shown again if it was issued to the restored holder.
+    pub credential: String,
+    pub wallet_selected: Option<usize>,
+    pub wallet_filter: Option<String>,
+    pub wallet_archived: bool,
+    /// DID last explored on the Chain tab.
+    pub chain_did: String,
+}
+
+impl Session {
+    /// Loads the session, an empty one if the file is missing or unreadable.
+    pub fn load(path: &str) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Holders created so far when the holder with the seed label is the current one.
+pub fn holders_created(label: &str) -> usize {
+    label
+        .strip_prefix("holder-")
+        .and_then(|number| number.parse::<usize>().ok())
+        .map_or(1, |number| number + 1)
+}
@@ -0,0 +1,53 @@
+use crate::oob::PresentationRequest;
+use crate::wallet::Wallet;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// A credential type the holder shares with some verifiers without being asked each time.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Rule {
+    /// Credential type shared, like `UniversityDegreeCredential`.
+    pub credential_type: String,
+    /// DIDs of the verifiers it is shared with.
+    pub verifiers: Vec<String>,
+}
+
+/// The wallet credentials the rules share with the verifier of the request, the newest current
+/// one of each requested type. `None` unless the rules cover every requested type for this
+/// verifier and the wallet holds them, the holder then picks the credentials as usual.
+pub fn auto_select(
+    rules: &[Rule],
+    request: &PresentationRequest,
+    wallet: &Wallet,
+) -> Option<BTreeSet<String>> {
+    // A request for any credential leaves nothing a rule could decide.
+    if request.credential_types.is_empty() {
+        return None;
+    }
+    request
+        .credential_types
+        .iter()
+        .map(|credential_type| {
+            rules.iter().find(|rule| {
+                rule.credential_type == *credential_type
+                    && rule
+                        .verifiers
+                        .iter()
+                        .any(|verifier| *verifier == request.verifier)
+            })?;
+            wallet
+                .entries
+                .iter()
+                .filter(|entry| entry.archived.is_none() && entry.superseded_by.is_none())
+                .filter(|entry| {
+                    entry
+                        .credential
+                        .types
+                        .iter()
+                        .any(|type_| type_ == credential_type)
+                })
+                .max_by_key(|entry| entry.added.to_unix())
+                .map(|entry| entry.id.clone())
+        })
+        .collect()
+}
@@ -0,0 +1,125 @@
+use crate::account;
+use crate::config::{Config, SignerKind};
+use crate::did;
+use crate::keys::{self, KeyFormat};
+use crate::paths;
+use async_trait::async_trait;
+use identity::account::{Account, Result};
+use identity::credential::Credential;
+use identity::crypto::KeyPair;
+use identity::iota::{Client, IotaDID, IotaDocument};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Signs issued credentials without handing out the key.
+/// A new key backend (HSM, OS keychain) implements this trait and is selected in `open`.
+#[async_trait]
+pub trait Signer: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// The issuer identity credentials signed here verify against.
+    fn document(&self) -> &IotaDocument;
+    async fn sign(&self, credential: &mut Credential) -> Result<()>;
+}
+
+/// Signer shared with the HTTP server, emptied while a backup is restored so nothing keeps
+/// the account open.
+pub type Slot = Arc<RwLock<Option<Arc<dyn Signer>>>>;
+
+/// Opens the signer the config asks for.
+pub async fn open(
+    config: &Config,
+    account: Arc<Account>,
+    issuer: &IotaDocument,
+    client: &Client,
+) -> std::result::Result<Box<dyn Signer>, Box<dyn Error>> {
+    match config.signer {
+        SignerKind::Stronghold => Ok(Box::new(StrongholdSigner {
+            account,
+            document: issuer.clone(),
+        })),
+        SignerKind::SoftHsm => Ok(Box::new(
+            SoftHsm::open(Path::new(&config.data_dir).join("softhsm.json"), client).await?,
+        )),
+    }
+}
+
+/// The issuer key inside the Stronghold snapshot of the account.
+pub struct StrongholdSigner {
+    account: Arc<Account>,
+    document: IotaDocument,
+}
+
+#[async_trait]
+impl Signer for StrongholdSigner {
+    fn name(&self) -> &'static str {
+        "stronghold"
+    }
+
+    fn document(&self) -> &IotaDocument {
+        &self.document
+    }
+
+    async fn sign(&self, credential: &mut Credential) -> Result<()> {
+        self.account
+            .sign(self.document.id(), account::SIGNING_METHOD, credential)
+            .await
+    }
+}
+
+/// Sealed key file of the software HSM.
+#[derive(Deserialize, Serialize)]
+struct Sealed {
+    did: String,
+    private: String,
+}
+
+/// Demo of a hardware security module in software: the key of its own issuer identity is
+/// kept in a user-only file and never leaves this type, callers only get signatures.
+pub struct SoftHsm {
+    document: IotaDocument,
+    key: KeyPair,
+}
+
+impl SoftHsm {
+    /// Loads the sealed key, or creates and publishes a new identity for it on first use.
+    pub async fn open(path: PathBuf, client: &Client) -> std::result::Result<Self, Box<dyn Error>> {
+        if let Ok(json) = fs::read_to_string(&path) {
+            let sealed: Sealed = serde_json::from_str(&json)?;
+            let key: KeyPair = keys::import(&sealed.private)?;
+            let document: IotaDocument =
+                client.read_document(&IotaDID::parse(&sealed.did)?).await?;
+            return Ok(SoftHsm { document, key });
+        }
+        let (document, key, _) = did::create_did(client, KeyPair::new_ed25519()?).await?;
+        let sealed = Sealed {
+            did: document.id().to_string(),
+            private: keys::export(&key, KeyFormat::Hex)?,
+        };
+        if let Some(dir) = path.parent() {
+            paths::restrict_dir(dir)?;
+        }
+        paths::create_private(&path)?
+            .write_all(serde_json::to_string_pretty(&sealed)?.as_bytes())?;
+        Ok(SoftHsm { document, key })
+    }
+}
+
+#[async_trait]
+impl Signer for SoftHsm {
+    fn name(&self) -> &'static str {
+        "softhsm"
+    }
+
+    fn document(&self) -> &IotaDocument {
+        &self.document
+    }
+
+    async fn sign(&self, credential: &mut Credential) -> Result<()> {
+        self.document.sign_data(credential, self.key.private())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use crate::bus::Message;
+use crate::events::LogEntry;
+use crate::receipts::Inclusion;
+use crate::status::Status;
+use crate::wallet::Wallet;
+use identity::core::Timestamp;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// State shared between the render loop and the background tasks.
+#[derive(Debug, Default)]
+pub struct AppState {
+    pub issuer: String,
+    pub holder: String,
+    pub wallet: Wallet,
+    /// Last known status per wallet entry id.
+    pub statuses: HashMap<String, Status>,
+    /// Last known inclusion per published message id.
+    pub inclusions: HashMap<String, Inclusion>,
+    /// Events of the app and its background tasks, newest last.
+    pub log: Vec<LogEntry>,
+    /// Attempt counter of the running resolution or verification, shown in the footer.
+    pub progress: Option<String>,
+}
+
+/// Tasks only read the state and publish messages, the render loop is its only writer.
+pub type State = Arc<RwLock<AppState>>;
+
+impl AppState {
+    pub fn apply(&mut self, message: Message) {
+        match message {
+            Message::Log(message) => self.log.push(LogEntry {
+                time: Timestamp::now_utc(),
+                message,
+            }),
+            Message::Status { id, status } => {
+                self.statuses.insert(id, status);
+            }
+            Message::Inclusion {
+                message_id,
+                inclusion,
+            } => {
+                self.inclusions.insert(message_id, inclusion);
+            }
+            Message::Progress(progress) => self.progress = progress,
+            Message::Renewed(credential) => self.log.push(LogEntry {
+                time: Timestamp::now_utc(),
+                message: format!(
+                    "Renewed {} at /refresh",
+                    credential
+                        .id
+                        .as_ref()
+                        .map(|id| id.to_string())
+                        .unwrap_or_default()
+                ),
+            }),
+        }
+    }
+}
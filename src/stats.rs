@@ -0,0 +1,259 @@
+use crate::clock;
+use crate::dates;
+use crate::issued::IssuanceLog;
+use crate::redact;
+use crate::status::Status;
+use crate::wallet::{Entry, Wallet};
+use identity::core::Timestamp;
+use std::collections::{BTreeMap, HashMap};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{BarChart, Block, BorderType, Borders, Paragraph, Sparkline, Wrap},
+    Frame,
+};
+
+/// Days shown in the sparklines.
+const DAYS: usize = 30;
+const DAY_SECONDS: i64 = 24 * 60 * 60;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Counts {
+    pub issued: u64,
+    pub revoked: u64,
+    pub expired: u64,
+}
+
+/// Issued, revoked and expired credentials per credential type.
+pub fn per_type(log: &IssuanceLog, now: Timestamp) -> BTreeMap<String, Counts> {
+    let mut counts: BTreeMap<String, Counts> = BTreeMap::new();
+    for record in &log.records {
+        let entry = counts.entry(record.credential_type.clone()).or_default();
+        entry.issued += 1;
+        if record.revoked.is_some() {
+            entry.revoked += 1;
+        }
+        if record.expires.map_or(false, |expires| expires < now) {
+            entry.expired += 1;
+        }
+    }
+    counts
+}
+
+/// What the holder keeps in the wallet.
+#[derive(Debug, Default)]
+pub struct WalletStats {
+    pub held: usize,
+    pub archived: usize,
+    pub per_issuer: BTreeMap<String, usize>,
+    pub per_type: BTreeMap<String, usize>,
+    /// Size of the wallet as JSON, what the store writes.
+    pub bytes: usize,
+    /// Title and time added of the oldest and the newest credential.
+    pub oldest: Option<(String, Timestamp)>,
+    pub newest: Option<(String, Timestamp)>,
+    pub revoked: usize,
+    pub expired: usize,
+}
+
+/// Counts the wallet credentials, revoked and expired ones by their last known status.
+pub fn wallet(wallet: &Wallet, statuses: &HashMap<String, Status>) -> WalletStats {
+    let mut stats = WalletStats {
+        held: wallet.entries.len(),
+        bytes: serde_json::to_vec(wallet).map_or(0, |json| json.len()),
+        ..Default::default()
+    };
+    for entry in &wallet.entries {
+        *stats
+            .per_issuer
+            .entry(entry.credential.issuer.url().to_string())
+            .or_default() += 1;
+        *stats.per_type.entry(entry.title()).or_default() += 1;
+        if entry.archived.is_some() {
+            stats.archived += 1;
+        }
+        match statuses.get(&entry.id) {
+            Some(Status::Revoked) => stats.revoked += 1,
+            Some(Status::Expired) => stats.expired += 1,
+            _ => {}
+        }
+    }
+    let added = |entry: &Entry| (entry.title(), entry.added);
+    stats.oldest = wallet
+        .entries
+        .iter()
+        .min_by_key(|entry| entry.added.to_unix())
+        .map(added);
+    stats.newest = wallet
+        .entries
+        .iter()
+        .max_by_key(|entry| entry.added.to_unix())
+        .map(added);
+    stats
+}
+
+/// The wallet statistics in a compact column next to the credential list.
+pub fn render_wallet(stats: &WalletStats, presentation_mode: bool) -> Paragraph<'static> {
+    let row = |label: &str, value: String, color: Color| {
+        Spans::from(vec![
+            Span::raw(format!("{:<10}", label)),
+            Span::styled(value, Style::default().fg(color)),
+        ])
+    };
+    let added = |added: &Option<(String, Timestamp)>| {
+        added
+            .as_ref()
+            .map(|(title, time)| format!("{}, {}", title, dates::date_time(*time)))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let mut lines = vec![
+        row(
+            "Held",
+            format!("{}, {} archived", stats.held, stats.archived),
+            Color::Green,
+        ),
+        row("Revoked", stats.revoked.to_string(), Color::Red),
+        row("Expired", stats.expired.to_string(), Color::Yellow),
+        row(
+            "Storage",
+            format!("{:.1} KiB", stats.bytes as f64 / 1024.0),
+            Color::LightBlue,
+        ),
+        row("Oldest", added(&stats.oldest), Color::White),
+        row("Newest", added(&stats.newest), Color::White),
+    ];
+    for (heading, counts) in [
+        ("Per issuer", &stats.per_issuer),
+        ("Per type", &stats.per_type),
+    ]
+    .iter()
+    {
+        lines.push(Spans::from(Span::raw("")));
+        lines.push(Spans::from(Span::styled(
+            heading.to_string(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        lines.extend(counts.iter().map(|(name, count)| {
+            let name = if presentation_mode {
+                redact::dids(name)
+            } else {
+                name.clone()
+            };
+            Spans::from(vec![
+                Span::styled(format!("{:>4} ", count), Style::default().fg(Color::Green)),
+                Span::raw(name),
+            ])
+        }));
+    }
+    Paragraph::new(lines)
+        .block(block("Wallet statistics (S closes)".to_string()))
+        .wrap(Wrap { trim: true })
+}
+
+/// Events per day over the last `DAYS` days, oldest first.
+fn per_day(times: impl Iterator<Item = Timestamp>, now: Timestamp) -> Vec<u64> {
+    let mut days = vec![0; DAYS];
+    for time in times {
+        let age = (now.to_unix() - time.to_unix()) / DAY_SECONDS;
+        if (0..DAYS as i64).contains(&age) {
+            days[DAYS - 1 - age as usize] += 1;
+        }
+    }
+    days
+}
+
+fn block(title: String) -> Block<'static> {
+    Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::White))
+        .title(title)
+        .border_type(BorderType::Plain)
+}
+
+/// Counts per type as table and bar chart, issuances and revocations per day as sparklines.
+pub fn render<B: Backend>(f: &mut Frame<B>, area: Rect, log: &IssuanceLog) {
+    let now = clock::now();
+    let counts = per_type(log, now);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[0]);
+
+    let mut lines = vec![Spans::from(Span::styled(
+        format!(
+            "{:<30}{:>8}{:>8}{:>8}",
+            "Type", "issued", "revoked", "expired"
+        ),
+        Style::default().add_modifier(Modifier::BOLD),
+    ))];
+    lines.extend(counts.iter().map(|(credential_type, counts)| {
+        Spans::from(vec![
+            Span::raw(format!("{:<30}", credential_type)),
+            Span::styled(
+                format!("{:>8}", counts.issued),
+                Style::default().fg(Color::Green),
+            ),
+            Span::styled(
+                format!("{:>8}", counts.revoked),
+                Style::default().fg(Color::Red),
+            ),
+            Span::styled(
+                format!("{:>8}", counts.expired),
+                Style::default().fg(Color::Gray),
+            ),
+        ])
+    }));
+    if counts.is_empty() {
+        lines.push(Spans::from(Span::raw("Nothing issued yet.")));
+    }
+    f.render_widget(
+        Paragraph::new(lines).block(block(format!("Issued credentials ({})", log.records.len()))),
+        columns[0],
+    );
+
+    let labels: Vec<String> = counts
+        .keys()
+        .map(|credential_type| credential_type.trim_end_matches("Credential").to_string())
+        .collect();
+    let bars: Vec<(&str, u64)> = labels
+        .iter()
+        .zip(counts.values())
+        .map(|(label, counts)| (label.as_str(), counts.issued))
+        .collect();
+    f.render_widget(
+        BarChart::default()
+            .block(block("Issued per type".to_string()))
+            .data(&bars)
+            .bar_width(12)
+            .bar_style(Style::default().fg(Color::LightBlue))
+            .value_style(Style::default().fg(Color::Black).bg(Color::LightBlue)),
+        columns[1],
+    );
+
+    let lines = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+    let issued = per_day(log.records.iter().map(|record| record.issued), now);
+    let revoked = per_day(log.records.iter().filter_map(|record| record.revoked), now);
+    f.render_widget(
+        Sparkline::default()
+            .block(block(format!("Issued per day, last {} days", DAYS)))
+            .data(&issued)
+            .style(Style::default().fg(Color::Green)),
+        lines[0],
+    );
+    f.render_widget(
+        Sparkline::default()
+            .block(block(format!("Revoked per day, last {} days", DAYS)))
+            .data(&revoked)
+            .style(Style::default().fg(Color::Red)),
+        lines[1],
+    );
+}
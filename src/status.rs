@@ -0,0 +1,102 @@
+use crate::bus::{Bus, Message};
+use crate::clock;
+use crate::config::Config;
+use crate::integrity;
+use crate::nodes::{self, Nodes};
+use crate::revocation;
+use crate::state::State;
+use crate::wallet::Entry;
+use identity::credential::Credential;
+use identity::crypto::TrySignature;
+use identity::iota::{Client, IotaDID};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tui::style::Color;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Status {
+    Valid,
+    /// The issuer removed the verification method the credential was signed with, or
+    /// named the credential in its status list.
+    Revoked,
+    Invalid,
+    /// The expiration date has passed.
+    Expired,
+    /// The issuer or its status list could not be resolved.
+    Unknown,
+}
+
+impl Status {
+    pub fn badge(&self) -> (&'static str, Color) {
+        match self {
+            Status::Valid => ("[valid]", Color::Green),
+            Status::Revoked => ("[revoked]", Color::Red),
+            Status::Invalid => ("[invalid]", Color::Red),
+            Status::Expired => ("[expired]", Color::Yellow),
+            Status::Unknown => ("[unknown]", Color::Gray),
+        }
+    }
+}
+
+pub async fn check(client: &Client, credential: &Credential) -> Status {
+    let method: &str = match credential.signature() {
+        Some(signature) => signature.verification_method(),
+        None => return Status::Invalid,
+    };
+    if credential
+        .expiration_date
+        .map_or(false, |expires| expires < clock::now())
+    {
+        return Status::Expired;
+    }
+    let did: IotaDID = match IotaDID::parse(credential.issuer.url().as_str()) {
+        Ok(did) => did,
+        Err(_) => return Status::Invalid,
+    };
+    let issuer = match integrity::resolve(client, &did).await {
+        Ok((issuer, _)) => issuer,
+        Err(_) => return Status::Unknown,
+    };
+
+    if issuer.resolve(method).is_none() {
+        return Status::Revoked;
+    }
+    if issuer.verify_data(credential).is_err() {
+        return Status::Invalid;
+    }
+    match revocation::revoked(credential, &issuer).await {
+        Ok(Some(_)) => Status::Revoked,
+        Ok(None) => Status::Valid,
+        Err(_) => Status::Unknown,
+    }
+}
+
+/// Re-checks every wallet credential periodically and reports status changes on the bus.
+pub fn spawn_monitor(config: &Config, state: State, nodes: Nodes, bus: Bus) -> JoinHandle<()> {
+    let interval = Duration::from_secs(config.status_poll_interval);
+
+    tokio::spawn(async move {
+        loop {
+            if let Ok(client) = nodes::client(&nodes).await {
+                let entries: Vec<Entry> = state.read().await.wallet.entries.clone();
+                for entry in &entries {
+                    let status = check(&client, &entry.credential).await;
+                    let previous = state.read().await.statuses.get(&entry.id).copied();
+                    if let Some(previous) = previous.filter(|previous| *previous != status) {
+                        bus.log(format!(
+                            "{} is {:?} since the last check (was {:?})",
+                            entry.title(),
+                            status,
+                            previous
+                        ));
+                    }
+                    bus.publish(Message::Status {
+                        id: entry.id.clone(),
+                        status,
+                    });
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+    })
+}
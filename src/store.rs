@@ -0,0 +1,355 @@
+use crate::cas::{self, Cas};
+use crate::config::{Config, Storage};
+use crate::history::VerificationHistory;
+use crate::issued::IssuanceLog;
+use crate::paths;
+use crate::receipts::PublishReceipt;
+use crate::trust::TrustStore;
+use crate::wallet::Wallet;
+use identity::account::AccountStorage;
+use identity::core::{FromJson, ToJson};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Persistence of identities, wallet and publish receipts.
+/// A new backend implements this trait and is selected in `open`.
+pub trait IdentityStore: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Storage the account keeps identities and their keys in.
+    fn account_storage(&self) -> AccountStorage;
+    fn load_wallet(&self) -> Wallet;
+    fn save_wallet(&self, wallet: &Wallet) -> Result<(), Box<dyn Error>>;
+    fn receipts(&self) -> Vec<PublishReceipt>;
+    /// Appends the receipt of a publish to the receipts.
+    fn save_receipt(&self, receipt: PublishReceipt) -> Result<(), Box<dyn Error>>;
+    fn save_receipts(&self, receipts: &[PublishReceipt]) -> Result<(), Box<dyn Error>>;
+    /// Trust decisions of the verifier about issuers.
+    fn load_trust(&self) -> TrustStore;
+    fn save_trust(&self, trust: &TrustStore) -> Result<(), Box<dyn Error>>;
+    /// Recent verifications of the verifier.
+    fn load_history(&self) -> VerificationHistory;
+    fn save_history(&self, history: &VerificationHistory) -> Result<(), Box<dyn Error>>;
+    /// Credentials signed by the issuer, without their claims.
+    fn load_issued(&self) -> IssuanceLog;
+    fn save_issued(&self, issued: &IssuanceLog) -> Result<(), Box<dyn Error>>;
+    /// Where the identities are kept, for display.
+    fn location(&self) -> String;
+    /// Restricts the permissions of the stored files to the user.
+    fn secure(&self) -> io::Result<()>;
+}
+
+/// Password protecting the Stronghold snapshot and confirming key exports.
+pub fn password() -> String {
+    env::var("STRONGHOLD_PASSWORD").unwrap_or_else(|_| "my-password".to_string())
+}
+
+/// Opens the store the config asks for.
+pub fn open(config: &Config) -> Arc<dyn IdentityStore> {
+    match config.storage {
+        Storage::Stronghold => Arc::new(StrongholdStore::new(config)),
+        Storage::File => Arc::new(FileStore::new(&config.data_dir)),
+        Storage::Memory => Arc::new(MemoryStore::default()),
+    }
+}
+
+/// Identities in an encrypted Stronghold snapshot, wallet and receipts in JSON files.
+pub struct StrongholdStore {
+    snapshot_path: PathBuf,
+    password: String,
+    wallet_path: PathBuf,
+    receipts_path: PathBuf,
+    trust_path: PathBuf,
+    history_path: PathBuf,
+    issued_path: PathBuf,
+    /// Credentials, documents and reports the wallet, receipts and history reference.
+    cas: Cas,
+}
+
+impl StrongholdStore {
+    pub fn new(config: &Config) -> Self {
+        StrongholdStore {
+            snapshot_path: config.snapshot_path.clone().into(),
+            password: password(),
+            wallet_path: config.wallet_path.clone().into(),
+            receipts_path: Path::new(&config.data_dir).join("receipts.json"),
+            trust_path: Path::new(&config.data_dir).join("trust.json"),
+            history_path: Path::new(&config.data_dir).join("verifications.json"),
+            issued_path: Path::new(&config.data_dir).join("issued.json"),
+            cas: Cas::new(Path::new(&config.data_dir).join("cas")),
+        }
+    }
+}
+
+impl IdentityStore for StrongholdStore {
+    fn name(&self) -> &'static str {
+        "stronghold"
+    }
+
+    fn account_storage(&self) -> AccountStorage {
+        AccountStorage::Stronghold(self.snapshot_path.clone(), Some(self.password.clone()))
+    }
+
+    fn load_wallet(&self) -> Wallet {
+        self.cas
+            .read(&self.wallet_path, &cas::WALLET)
+            .unwrap_or_default()
+    }
+
+    fn save_wallet(&self, wallet: &Wallet) -> Result<(), Box<dyn Error>> {
+        self.cas.write(&self.wallet_path, wallet, &cas::WALLET)
+    }
+
+    fn receipts(&self) -> Vec<PublishReceipt> {
+        self.cas
+            .read(&self.receipts_path, &cas::RECEIPTS)
+            .unwrap_or_default()
+    }
+
+    fn save_receipt(&self, receipt: PublishReceipt) -> Result<(), Box<dyn Error>> {
+        append_receipt(&self.cas, &self.receipts_path, receipt)
+    }
+
+    fn save_receipts(&self, receipts: &[PublishReceipt]) -> Result<(), Box<dyn Error>> {
+        self.cas
+            .write(&self.receipts_path, &receipts, &cas::RECEIPTS)
+    }
+
+    fn load_trust(&self) -> TrustStore {
+        read_json(&self.trust_path).unwrap_or_default()
+    }
+
+    fn save_trust(&self, trust: &TrustStore) -> Result<(), Box<dyn Error>> {
+        write_json(&self.trust_path, trust)
+    }
+
+    fn load_history(&self) -> VerificationHistory {
+        self.cas
+            .read(&self.history_path, &cas::HISTORY)
+            .unwrap_or_default()
+    }
+
+    fn save_history(&self, history: &VerificationHistory) -> Result<(), Box<dyn Error>> {
+        self.cas.write(&self.history_path, history, &cas::HISTORY)
+    }
+
+    fn load_issued(&self) -> IssuanceLog {
+        read_json(&self.issued_path).unwrap_or_default()
+    }
+
+    fn save_issued(&self, issued: &IssuanceLog) -> Result<(), Box<dyn Error>> {
+        write_json(&self.issued_path, issued)
+    }
+
+    fn location(&self) -> String {
+        self.snapshot_path.display().to_string()
+    }
+
+    fn secure(&self) -> io::Result<()> {
+        if let Some(dir) = self
+            .snapshot_path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+        {
+            paths::restrict_dir(dir)?;
+        }
+        paths::restrict_file(&self.snapshot_path)
+    }
+}
+
+/// Everything as plain JSON files in one directory, the account only lives in memory.
+/// Meant for throwaway demo runs, identities are created anew on every start.
+pub struct FileStore {
+    dir: PathBuf,
+    cas: Cas,
+}
+
+impl FileStore {
+    pub fn new(dir: &str) -> Self {
+        FileStore {
+            dir: dir.into(),
+            cas: Cas::new(Path::new(dir).join("cas")),
+        }
+    }
+}
+
+impl IdentityStore for FileStore {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn account_storage(&self) -> AccountStorage {
+        AccountStorage::Memory
+    }
+
+    fn load_wallet(&self) -> Wallet {
+        self.cas
+            .read(&self.dir.join("wallet.json"), &cas::WALLET)
+            .unwrap_or_default()
+    }
+
+    fn save_wallet(&self, wallet: &Wallet) -> Result<(), Box<dyn Error>> {
+        self.cas
+            .write(&self.dir.join("wallet.json"), wallet, &cas::WALLET)
+    }
+
+    fn receipts(&self) -> Vec<PublishReceipt> {
+        self.cas
+            .read(&self.dir.join("receipts.json"), &cas::RECEIPTS)
+            .unwrap_or_default()
+    }
+
+    fn save_receipt(&self, receipt: PublishReceipt) -> Result<(), Box<dyn Error>> {
+        append_receipt(&self.cas, &self.dir.join("receipts.json"), receipt)
+    }
+
+    fn save_receipts(&self, receipts: &[PublishReceipt]) -> Result<(), Box<dyn Error>> {
+        self.cas
+            .write(&self.dir.join("receipts.json"), &receipts, &cas::RECEIPTS)
+    }
+
+    fn load_trust(&self) -> TrustStore {
+        read_json(&self.dir.join("trust.json")).unwrap_or_default()
+    }
+
+    fn save_trust(&self, trust: &TrustStore) -> Result<(), Box<dyn Error>> {
+        write_json(&self.dir.join("trust.json"), trust)
+    }
+
+    fn load_history(&self) -> VerificationHistory {
+        self.cas
+            .read(&self.dir.join("verifications.json"), &cas::HISTORY)
+            .unwrap_or_default()
+    }
+
+    fn save_history(&self, history: &VerificationHistory) -> Result<(), Box<dyn Error>> {
+        self.cas
+            .write(&self.dir.join("verifications.json"), history, &cas::HISTORY)
+    }
+
+    fn load_issued(&self) -> IssuanceLog {
+        read_json(&self.dir.join("issued.json")).unwrap_or_default()
+    }
+
+    fn save_issued(&self, issued: &IssuanceLog) -> Result<(), Box<dyn Error>> {
+        write_json(&self.dir.join("issued.json"), issued)
+    }
+
+    fn location(&self) -> String {
+        format!("{} (account in memory)", self.dir.display())
+    }
+
+    fn secure(&self) -> io::Result<()> {
+        paths::restrict_dir(&self.dir)
+    }
+}
+
+/// Nothing touches the disk, everything is gone when the app quits.
+/// Backs `--ephemeral` runs on shared machines.
+#[derive(Default)]
+pub struct MemoryStore {
+    /// The JSON the other stores would write, by file name.
+    files: Mutex<HashMap<&'static str, String>>,
+}
+
+impl MemoryStore {
+    fn read<T: for<'de> Deserialize<'de>>(&self, name: &str) -> Option<T> {
+        let files = self.files.lock().unwrap();
+        files.get(name).and_then(|json| T::from_json(json).ok())
+    }
+
+    fn write<T: Serialize>(&self, name: &'static str, value: &T) -> Result<(), Box<dyn Error>> {
+        let json = value.to_json()?;
+        self.files.lock().unwrap().insert(name, json);
+        Ok(())
+    }
+}
+
+impl IdentityStore for MemoryStore {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn account_storage(&self) -> AccountStorage {
+        AccountStorage::Memory
+    }
+
+    fn load_wallet(&self) -> Wallet {
+        self.read("wallet").unwrap_or_default()
+    }
+
+    fn save_wallet(&self, wallet: &Wallet) -> Result<(), Box<dyn Error>> {
+        self.write("wallet", wallet)
+    }
+
+    fn receipts(&self) -> Vec<PublishReceipt> {
+        self.read("receipts").unwrap_or_default()
+    }
+
+    fn save_receipt(&self, receipt: PublishReceipt) -> Result<(), Box<dyn Error>> {
+        let mut receipts: Vec<PublishReceipt> = self.receipts();
+        receipts.push(receipt);
+        self.save_receipts(&receipts)
+    }
+
+    fn save_receipts(&self, receipts: &[PublishReceipt]) -> Result<(), Box<dyn Error>> {
+        self.write("receipts", &receipts)
+    }
+
+    fn load_trust(&self) -> TrustStore {
+        self.read("trust").unwrap_or_default()
+    }
+
+    fn save_trust(&self, trust: &TrustStore) -> Result<(), Box<dyn Error>> {
+        self.write("trust", trust)
+    }
+
+    fn load_history(&self) -> VerificationHistory {
+        self.read("history").unwrap_or_default()
+    }
+
+    fn save_history(&self, history: &VerificationHistory) -> Result<(), Box<dyn Error>> {
+        self.write("history", history)
+    }
+
+    fn load_issued(&self) -> IssuanceLog {
+        self.read("issued").unwrap_or_default()
+    }
+
+    fn save_issued(&self, issued: &IssuanceLog) -> Result<(), Box<dyn Error>> {
+        self.write("issued", issued)
+    }
+
+    fn location(&self) -> String {
+        "memory, nothing is saved".to_string()
+    }
+
+    fn secure(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|json| T::from_json(&json).ok())
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, value.to_json_pretty()?)?;
+    Ok(())
+}
+
+fn append_receipt(cas: &Cas, path: &Path, receipt: PublishReceipt) -> Result<(), Box<dyn Error>> {
+    let mut receipts: Vec<PublishReceipt> = cas.read(path, &cas::RECEIPTS).unwrap_or_default();
+    receipts.push(receipt);
+    cas.write(path, &receipts, &cas::RECEIPTS)
+}
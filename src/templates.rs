@@ -0,0 +1,106 @@
+use identity::core::{Object, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Context of the W3C example credentials, defining the degree and enrollment claims.
+const EXAMPLES: &str = "https://www.w3.org/2018/credentials/examples/v1";
+const SCHEMA_ORG: &str = "https://schema.org";
+
+/// What a known credential type needs besides the base credentials context.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TypeTemplate {
+    /// JSON-LD contexts defining the type and its claims.
+    pub contexts: Vec<String>,
+    /// Required claims in the form syntax, like `degree.name` or `birthDate:date`.
+    pub claims: Vec<String>,
+}
+
+impl TypeTemplate {
+    /// The required claims with empty values, a starting point for the claims field.
+    pub fn skeleton(&self) -> String {
+        self.claims
+            .iter()
+            .map(|claim| format!("{}=", claim))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+/// Registry of the credential types issued in the examples.
+pub fn defaults() -> BTreeMap<String, TypeTemplate> {
+    let template = |contexts: &[&str], claims: &[&str]| TypeTemplate {
+        contexts: contexts.iter().map(|context| context.to_string()).collect(),
+        claims: claims.iter().map(|claim| claim.to_string()).collect(),
+    };
+    let mut types = BTreeMap::new();
+    types.insert(
+        "EnrollmentCredential".to_string(),
+        template(&[EXAMPLES], &["enrollment.type", "enrollment.name"]),
+    );
+    types.insert(
+        "MembershipCredential".to_string(),
+        template(
+            &[SCHEMA_ORG],
+            &["membership.organization", "membership.role"],
+        ),
+    );
+    types.insert(
+        "PersonCredential".to_string(),
+        template(&[SCHEMA_ORG], &["name", "birthDate:date"]),
+    );
+    types.insert(
+        "UniversityDegreeCredential".to_string(),
+        template(&[EXAMPLES], &["degree.type", "degree.name"]),
+    );
+    types
+}
+
+/// Known types starting with what was typed so far, ignoring case.
+pub fn suggest<'a>(types: &'a BTreeMap<String, TypeTemplate>, typed: &str) -> Vec<&'a str> {
+    let typed = typed.to_lowercase();
+    types
+        .keys()
+        .filter(|name| {
+            let name = name.to_lowercase();
+            name.starts_with(&typed) && name != typed
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+/// Checks that a known type gets its contexts and required claims, unknown types pass.
+pub fn check(
+    types: &BTreeMap<String, TypeTemplate>,
+    credential_type: &str,
+    contexts: &[String],
+    claims: &Object,
+) -> Result<(), String> {
+    let template = match types.get(credential_type) {
+        Some(template) => template,
+        None => return Ok(()),
+    };
+    if let Some(missing) = template
+        .contexts
+        .iter()
+        .find(|context| !contexts.contains(context))
+    {
+        return Err(format!("{} needs the context {}", credential_type, missing));
+    }
+    for claim in &template.claims {
+        let path = claim.split(':').next().unwrap_or_default();
+        if !has_claim(claims, path) {
+            return Err(format!("{} needs the claim `{}`", credential_type, path));
+        }
+    }
+    Ok(())
+}
+
+fn has_claim(claims: &Object, path: &str) -> bool {
+    match path.split_once('.') {
+        None => claims.contains_key(path),
+        Some((head, rest)) => match claims.get(head) {
+            Some(Value::Object(nested)) => has_claim(&nested.clone().into_iter().collect(), rest),
+            _ => false,
+        },
+    }
+}
@@ -0,0 +1,62 @@
+use crate::events::LogEntry;
+use identity::core::Timestamp;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Seconds a notification stays on screen.
+const TOAST_SECONDS: i64 = 6;
+const MAX_TOASTS: usize = 3;
+const TOAST_WIDTH: u16 = 50;
+const TOAST_HEIGHT: u16 = 4;
+
+/// Shows the latest events in the bottom right corner until they expire or are dismissed.
+#[derive(Default)]
+pub struct Toasts {
+    /// Events before this index were dismissed.
+    dismissed: usize,
+}
+
+impl Toasts {
+    pub fn dismiss(&mut self, entries: &[LogEntry]) {
+        self.dismissed = entries.len();
+    }
+
+    fn active<'a>(&self, entries: &'a [LogEntry]) -> Vec<&'a LogEntry> {
+        let now = Timestamp::now_utc().to_unix();
+        entries
+            .iter()
+            .skip(self.dismissed)
+            .filter(|entry| now - entry.time.to_unix() < TOAST_SECONDS)
+            .rev()
+            .take(MAX_TOASTS)
+            .collect()
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>, area: Rect, entries: &[LogEntry]) {
+        let width = TOAST_WIDTH.min(area.width);
+        let mut bottom = area.y + area.height;
+        for entry in self.active(entries) {
+            if bottom < area.y + TOAST_HEIGHT {
+                break;
+            }
+            bottom -= TOAST_HEIGHT;
+            let rect = Rect::new(area.x + area.width - width, bottom, width, TOAST_HEIGHT);
+            let toast = Paragraph::new(entry.message.clone())
+                .wrap(Wrap { trim: true })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .style(Style::default().fg(Color::LightCyan))
+                        .title("Notification (esc dismisses)")
+                        .border_type(BorderType::Rounded),
+                );
+            f.render_widget(Clear, rect);
+            f.render_widget(toast, rect);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use identity::core::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// What the verifier decided about an issuer seen for the first time.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    /// Accept this credential and ask again next time, never remembered.
+    Once,
+    Always,
+    Block,
+}
+
+impl FromStr for Decision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "o" | "once" => Ok(Decision::Once),
+            "a" | "always" => Ok(Decision::Always),
+            "b" | "block" => Ok(Decision::Block),
+            other => Err(format!("unknown trust decision {}", other)),
+        }
+    }
+}
+
+/// Verification outcomes and the remembered decision for one issuer.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct IssuerRecord {
+    pub decision: Option<Decision>,
+    pub verified: usize,
+    pub failed: usize,
+    pub last_seen: Option<Timestamp>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct TrustStore {
+    pub issuers: BTreeMap<String, IssuerRecord>,
+}
+
+impl TrustStore {
+    /// The remembered decision, `None` means the verifier has to be asked.
+    pub fn decision(&self, issuer: &str) -> Option<Decision> {
+        self.issuers.get(issuer).and_then(|record| record.decision)
+    }
+
+    pub fn record(&mut self, issuer: &str, verified: bool) {
+        let record = self.issuers.entry(issuer.to_string()).or_default();
+        if verified {
+            record.verified += 1;
+        } else {
+            record.failed += 1;
+        }
+        record.last_seen = Some(Timestamp::now_utc());
+    }
+
+    pub fn decide(&mut self, issuer: &str, decision: Decision) {
+        if decision != Decision::Once {
+            self.issuers.entry(issuer.to_string()).or_default().decision = Some(decision);
+        }
+    }
+}
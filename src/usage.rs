@@ -0,0 +1,170 @@
+use crate::clock;
+use crate::dates;
+use crate::issued::IssuanceLog;
+use identity::core::Timestamp;
+use identity::iota::IotaDocument;
+use std::collections::BTreeMap;
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Paragraph, Wrap},
+};
+
+/// What one verification method signed, from the issuance log.
+#[derive(Clone, Debug, Default)]
+pub struct Usage {
+    /// `issuer` or `holder`, empty for methods no longer in either document.
+    pub owner: &'static str,
+    pub credentials: usize,
+    pub presentations: usize,
+    pub last_used: Option<Timestamp>,
+    /// From when the method should no longer sign, by `rotation_due`.
+    pub rotation_due: Option<Timestamp>,
+    /// Signatures made on or after the rotation date.
+    pub overdue: usize,
+}
+
+/// `2021-12-31` or a full timestamp.
+fn parse_date(input: &str) -> Option<Timestamp> {
+    let input = input.trim();
+    if input.len() == 10 {
+        Timestamp::parse(&format!("{}T00:00:00Z", input)).ok()
+    } else {
+        Timestamp::parse(input).ok()
+    }
+}
+
+/// Signatures per verification method of the issuer and the holder, and of methods that
+/// signed before they were removed.
+pub fn per_method(
+    log: &IssuanceLog,
+    rotation_due: &BTreeMap<String, String>,
+    issuer: &IotaDocument,
+    holder: &IotaDocument,
+) -> BTreeMap<String, Usage> {
+    let due = |method: &str| rotation_due.get(method).and_then(|date| parse_date(date));
+    let mut usage: BTreeMap<String, Usage> = BTreeMap::new();
+    for (owner, document) in [("issuer", issuer), ("holder", holder)].iter() {
+        for method in document.methods() {
+            let id: String = method.id().to_string();
+            usage.insert(
+                id.clone(),
+                Usage {
+                    owner,
+                    rotation_due: due(&id),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+    // Credentials by their issuance date, presentations by their signing time.
+    let signed = log
+        .records
+        .iter()
+        .filter(|record| !record.method.is_empty())
+        .map(|record| (&record.method, record.issued, true))
+        .chain(
+            log.presentations
+                .iter()
+                .map(|presented| (&presented.method, presented.signed, false)),
+        );
+    for (method, time, credential) in signed {
+        let entry = usage.entry(method.clone()).or_insert_with(|| Usage {
+            rotation_due: due(method),
+            ..Default::default()
+        });
+        if credential {
+            entry.credentials += 1;
+        } else {
+            entry.presentations += 1;
+        }
+        if entry.rotation_due.map_or(false, |due| time >= due) {
+            entry.overdue += 1;
+        }
+        entry.last_used = entry.last_used.max(Some(time));
+    }
+    usage
+}
+
+/// One warning per method that signed on or after its rotation date.
+pub fn warnings(usage: &BTreeMap<String, Usage>) -> Vec<String> {
+    usage
+        .iter()
+        .filter(|(_, entry)| entry.overdue > 0)
+        .map(|(method, entry)| {
+            format!(
+                "{} was due for rotation on {} and still made {} signatures since",
+                method,
+                entry.rotation_due.map(dates::date).unwrap_or_default(),
+                entry.overdue
+            )
+        })
+        .collect()
+}
+
+/// Signatures made with methods past their rotation date, to notice new ones.
+pub fn overdue(usage: &BTreeMap<String, Usage>) -> usize {
+    usage.values().map(|entry| entry.overdue).sum()
+}
+
+/// The Keys tab: signatures per method, their rotation dates and the warnings.
+pub fn render(usage: &BTreeMap<String, Usage>) -> Paragraph<'static> {
+    let now: Timestamp = clock::now();
+    let mut lines = vec![Spans::from(Span::styled(
+        format!(
+            "{:<8}{:>12}{:>14}  {:<22}{:<14}Method",
+            "Owner", "credentials", "presentations", "Last used", "Rotation"
+        ),
+        Style::default().add_modifier(Modifier::BOLD),
+    ))];
+    for (method, entry) in usage {
+        let color = if entry.overdue > 0 {
+            Color::Red
+        } else if entry.rotation_due.map_or(false, |due| due <= now) {
+            Color::Yellow
+        } else if entry.owner.is_empty() {
+            Color::DarkGray
+        } else {
+            Color::White
+        };
+        lines.push(Spans::from(Span::styled(
+            format!(
+                "{:<8}{:>12}{:>14}  {:<22}{:<14}{}",
+                if entry.owner.is_empty() {
+                    "removed"
+                } else {
+                    entry.owner
+                },
+                entry.credentials,
+                entry.presentations,
+                entry
+                    .last_used
+                    .map(dates::date_time)
+                    .unwrap_or_else(|| "-".to_string()),
+                entry
+                    .rotation_due
+                    .map(dates::date)
+                    .unwrap_or_else(|| "-".to_string()),
+                method
+            ),
+            Style::default().fg(color),
+        )));
+    }
+    let warnings: Vec<String> = warnings(usage);
+    if !warnings.is_empty() {
+        lines.push(Spans::from(""));
+        lines.extend(warnings.into_iter().map(|warning| {
+            Spans::from(Span::styled(
+                format!("! {}", warning),
+                Style::default().fg(Color::LightRed),
+            ))
+        }));
+    }
+    Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title("Key usage")
+            .border_type(BorderType::Plain),
+    )
+}
@@ -0,0 +1,1047 @@
+use crate::clock::{self, Skew};
+use crate::consent::{self, Acceptance, Consented};
+use crate::integrity::{self, Integrity, Report};
+use crate::ledger::Ledger;
+use crate::proof::{self, ProofFormat};
+use crate::report::VerificationReport;
+use crate::revocation;
+use crate::trust::{Decision, TrustStore};
+use identity::core::{decode_b64, FromJson, Timestamp};
+use identity::credential::Credential;
+use identity::crypto::{Ed25519, TrySignature, Verify};
+use identity::iota::{IotaDID, IotaDocument};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use tokio::time::{timeout_at, Instant};
+
+/// Checks left unknown if the time budget runs out before they finish.
+const RESOLUTION: &str = "issuer resolution";
+const PROOF: &str = "proof";
+const PROOF_SET: &str = "proof set";
+const CONSENT: &str = "subject acceptance";
+const STATUS: &str = "status list";
+
+/// A credential taken apart, nothing is resolved yet.
+pub struct Parsed {
+    pub credential: Credential,
+    pub format: ProofFormat,
+    /// Verification method the proof claims to be made with.
+    pub method: String,
+    /// Signing input and signature of a JWS.
+    jws: Option<(String, Vec<u8>)>,
+    /// Acceptance counter-signed by the subject, if the credential came with one.
+    pub acceptance: Option<Acceptance>,
+    /// End of validity, the JWS `exp` or the expiration date of the credential.
+    pub expires: Option<Timestamp>,
+    /// When the proof was created, the JWS `iat` or the issuance date of the credential.
+    pub created: Timestamp,
+    /// The credential once for each proof if it carries a proof set, empty otherwise.
+    pub proofs: Vec<Value>,
+}
+
+impl Parsed {
+    /// DID whose document has to hold the signing method.
+    pub fn issuer(&self) -> Result<IotaDID, VerificationError> {
+        let did = match self.format {
+            ProofFormat::Jws => self.method.split('#').next().unwrap_or_default(),
+            ProofFormat::Embedded => self.credential.issuer.url().as_str(),
+        };
+        IotaDID::parse(did).map_err(schema)
+    }
+}
+
+/// Why a credential does not verify, each with its own message and code in the JSON output.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VerificationError {
+    /// The input is neither JSON nor a compact JWS.
+    MalformedJson(String),
+    /// The input is JSON but no credential with a proof.
+    SchemaViolation(String),
+    /// The issuer, or the subject of an acceptance, could not be resolved.
+    ResolutionFailed(String),
+    /// The proof does not verify against the issuer document.
+    SignatureMismatch,
+    /// The JWS is signed by another DID than the issuer the credential names.
+    IssuerMismatch(String),
+    /// The verifier blocked the issuer.
+    UntrustedIssuer(String),
+    /// The issuer removed the verification method the credential was signed with, or names
+    /// the credential in the status list of its `credentialStatus`.
+    Revoked,
+    /// The app's now lies outside the validity period.
+    Expired,
+    /// `verify_consent` is set and the subject signed no valid acceptance.
+    MissingConsent,
+    /// Checks were still running when the time budget ran out.
+    Timeout,
+    /// The proof was created outside the tolerated clock skew.
+    ClockSkew(String),
+}
+
+impl VerificationError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            VerificationError::MalformedJson(_) => "malformed_json",
+            VerificationError::SchemaViolation(_) => "schema_violation",
+            VerificationError::ResolutionFailed(_) => "resolution_failed",
+            VerificationError::SignatureMismatch => "signature_mismatch",
+            VerificationError::IssuerMismatch(_) => "issuer_mismatch",
+            VerificationError::UntrustedIssuer(_) => "untrusted_issuer",
+            VerificationError::Revoked => "revoked",
+            VerificationError::Expired => "expired",
+            VerificationError::MissingConsent => "missing_consent",
+            VerificationError::Timeout => "timeout",
+            VerificationError::ClockSkew(_) => "clock_skew",
+        }
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerificationError::MalformedJson(error) => {
+                write!(f, "the input is no credential JSON or JWS: {}", error)
+            }
+            VerificationError::SchemaViolation(error) => {
+                write!(
+                    f,
+                    "the credential does not follow the data model: {}",
+                    error
+                )
+            }
+            VerificationError::ResolutionFailed(error) => {
+                write!(f, "the issuer could not be resolved: {}", error)
+            }
+            VerificationError::SignatureMismatch => {
+                write!(f, "the proof does not match the issuer document")
+            }
+            VerificationError::IssuerMismatch(error) => write!(f, "{}", error),
+            VerificationError::UntrustedIssuer(issuer) => {
+                write!(f, "the issuer {} is blocked", issuer)
+            }
+            VerificationError::Revoked => {
+                write!(
+                    f,
+                    "the issuer revoked the credential or the method it was signed with"
+                )
+            }
+            VerificationError::Expired => {
+                write!(f, "the credential is outside its validity period")
+            }
+            VerificationError::MissingConsent => {
+                write!(f, "the subject did not accept the credential")
+            }
+            VerificationError::Timeout => {
+                write!(f, "the checks did not finish within the time budget")
+            }
+            VerificationError::ClockSkew(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for VerificationError {}
+
+fn malformed(error: impl ToString) -> VerificationError {
+    VerificationError::MalformedJson(error.to_string())
+}
+
+fn schema(error: impl ToString) -> VerificationError {
+    VerificationError::SchemaViolation(error.to_string())
+}
+
+/// Outcome of the stages up to the proof check.
+#[derive(Clone)]
+pub struct Verified {
+    pub credential: Credential,
+    pub format: ProofFormat,
+    pub verified: bool,
+    /// Whether the proof alone verifies, regardless of expiry and consent.
+    pub proof: bool,
+    /// Each proof of a proof set, empty for a credential with a single proof.
+    pub proofs: Vec<ProofCheck>,
+    /// Why the credential does not verify, `None` if it does.
+    pub error: Option<VerificationError>,
+    /// Integrity of the resolved issuer document.
+    pub integrity: Report,
+    /// Whether the subject acceptance verifies, `None` without an acceptance.
+    pub consent: Option<bool>,
+    /// When the result was checked if it comes from the verification cache.
+    pub cached: Option<Timestamp>,
+    /// Checks that did not finish within the time budget, their outcome is unknown.
+    pub unknown: Vec<String>,
+    /// Findings about the issuer document that leave the proof valid, like a rotated method.
+    pub warnings: Vec<String>,
+}
+
+/// What the verifier accepts, given the proof and the trust decisions about the issuer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Policy {
+    Accepted,
+    /// The proof does not verify.
+    Rejected,
+    /// The issuer is blocked.
+    Blocked,
+    /// The proof verifies but the issuer was never decided about.
+    Unknown,
+}
+
+impl Policy {
+    /// Why the verifier rejects the credential, `None` if it accepts it.
+    pub fn error(&self, issuer: &str, verified: &Verified) -> Option<VerificationError> {
+        match self {
+            Policy::Accepted | Policy::Unknown => None,
+            Policy::Rejected => verified.error.clone(),
+            Policy::Blocked => Some(VerificationError::UntrustedIssuer(issuer.to_string())),
+        }
+    }
+
+    pub fn outcome(&self, format: ProofFormat, error: Option<&VerificationError>) -> String {
+        match (self, error) {
+            (Policy::Accepted, _) | (Policy::Unknown, _) => {
+                format!("Credential verified ({:?} proof)", format)
+            }
+            (_, Some(error)) => format!("Credential rejected ({:?} proof): {}", format, error),
+            (_, None) => format!("Credential is NOT valid ({:?} proof)", format),
+        }
+    }
+}
+
+/// Reads a credential given as JSON with an embedded proof or as compact JWS.
+pub fn parse(input: &str) -> Result<Parsed, VerificationError> {
+    let input = input.trim();
+    if proof::is_jws(input) {
+        let parts: Vec<&str> = input.split('.').collect();
+        let header: Value =
+            Value::from_json_slice(&decode_b64(parts[0]).map_err(malformed)?).map_err(malformed)?;
+        let payload: Value =
+            Value::from_json_slice(&decode_b64(parts[1]).map_err(malformed)?).map_err(malformed)?;
+        let method = header["kid"]
+            .as_str()
+            .ok_or_else(|| schema("the JWS header names no kid"))?
+            .to_string();
+        // Only Ed25519 keys are published, a JWS claiming another algorithm can't be theirs.
+        if header["alg"].as_str() != Some("EdDSA") {
+            return Err(VerificationError::SignatureMismatch);
+        }
+        let credential = Credential::from_json_value(payload["vc"].clone()).map_err(schema)?;
+        credential.check_structure().map_err(schema)?;
+        // The kid picks the document the signature is checked against, so it has to belong to
+        // the issuer, or anyone could sign in the name of any issuer with their own key.
+        let signer: &str = method.split('#').next().unwrap_or_default();
+        let issuer: &str = credential.issuer.url().as_str();
+        if payload["iss"].as_str() != Some(issuer) || signer != issuer {
+            return Err(VerificationError::IssuerMismatch(format!(
+                "the JWS is signed by {} as {} but the credential names {} as issuer",
+                signer,
+                payload["iss"].as_str().unwrap_or("no iss"),
+                issuer
+            )));
+        }
+        return Ok(Parsed {
+            expires: payload["exp"]
+                .as_i64()
+                .map(Timestamp::from_unix)
+                .or(credential.expiration_date),
+            created: payload["iat"]
+                .as_i64()
+                .map(Timestamp::from_unix)
+                .unwrap_or(credential.issuance_date),
+            credential,
+            format: ProofFormat::Jws,
+            method,
+            jws: Some((
+                format!("{}.{}", parts[0], parts[1]),
+                decode_b64(parts[2]).map_err(malformed)?,
+            )),
+            acceptance: None,
+            proofs: Vec::new(),
+        });
+    }
+
+    let mut json: Value = serde_json::from_str(input).map_err(malformed)?;
+    // Of a proof set, the credential goes on with the issuer proof, the others are checked apart.
+    let proofs: Vec<Value> = split_proofs(&json);
+    if !proofs.is_empty() {
+        let issuer: String = match &json["issuer"] {
+            Value::String(issuer) => issuer.clone(),
+            issuer => issuer["id"].as_str().unwrap_or_default().to_string(),
+        };
+        json = signed_by(&proofs, &issuer)
+            .cloned()
+            .ok_or_else(|| schema("none of the proofs is made by the issuer"))?;
+    }
+    let (credential, acceptance) = match serde_json::from_value::<Consented>(json.clone()) {
+        Ok(consented) => (consented.credential, Some(consented.subject_acceptance)),
+        Err(_) => (Credential::from_json_value(json).map_err(schema)?, None),
+    };
+    credential.check_structure().map_err(schema)?;
+    let method = credential
+        .signature()
+        .ok_or_else(|| schema("the credential carries no proof"))?
+        .verification_method()
+        .to_string();
+    Ok(Parsed {
+        expires: credential.expiration_date,
+        created: credential.issuance_date,
+        credential,
+        format: ProofFormat::Embedded,
+        method,
+        jws: None,
+        acceptance,
+        proofs,
+    })
+}
+
+/// One proof of a credential or presentation signed by several parties, checked on its own.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProofCheck {
+    pub method: String,
+    /// `proofPurpose` of the proof, if it states one.
+    pub purpose: Option<String>,
+    /// Why the proof does not verify, `None` if it does.
+    pub error: Option<VerificationError>,
+}
+
+impl ProofCheck {
+    /// `[ok] assertionMethod by did:iota:...#key` line for the reports.
+    pub fn line(&self) -> String {
+        let mark = if self.error.is_none() {
+            "[ok]"
+        } else {
+            "[failed]"
+        };
+        format!("{} {}", mark, self.summary())
+    }
+
+    /// Purpose, method and error of the proof.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} by {}{}",
+            self.purpose.as_deref().unwrap_or("proof"),
+            self.method,
+            self.error
+                .as_ref()
+                .map(|error| format!(": {}", error))
+                .unwrap_or_default()
+        )
+    }
+}
+
+/// The document once with each of its proofs, empty unless it carries a proof set.
+pub fn split_proofs(json: &Value) -> Vec<Value> {
+    match json["proof"].as_array() {
+        Some(proofs) if proofs.len() > 1 => proofs
+            .iter()
+            .map(|proof| {
+                let mut single: Value = json.clone();
+                single["proof"] = proof.clone();
+                single
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The version of a split document whose proof `did` made.
+pub fn signed_by<'a>(versions: &'a [Value], did: &str) -> Option<&'a Value> {
+    versions.iter().find(|version| {
+        version["proof"]["verificationMethod"]
+            .as_str()
+            .map_or(false, |method| method.split('#').next() == Some(did))
+    })
+}
+
+/// Checks each proof of a split document against the document of the DID that made it.
+pub async fn check_proofs<T>(ledger: &Ledger, versions: &[Value]) -> Vec<ProofCheck>
+where
+    T: FromJson + Serialize + TrySignature,
+{
+    let mut checks = Vec::new();
+    for version in versions {
+        let proof: &Value = &version["proof"];
+        let method: String = proof["verificationMethod"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let error = check_single::<T>(ledger, version, &method).await.err();
+        checks.push(ProofCheck {
+            method,
+            purpose: proof["proofPurpose"].as_str().map(str::to_string),
+            error,
+        });
+    }
+    checks
+}
+
+async fn check_single<T>(
+    ledger: &Ledger,
+    version: &Value,
+    method: &str,
+) -> Result<(), VerificationError>
+where
+    T: FromJson + Serialize + TrySignature,
+{
+    let document: T = T::from_json_value(version.clone()).map_err(schema)?;
+    let did = IotaDID::parse(method.split('#').next().unwrap_or_default()).map_err(schema)?;
+    let (signer, report) = ledger
+        .resolve(&did)
+        .await
+        .map_err(|error| VerificationError::ResolutionFailed(error.to_string()))?;
+    if report.integrity == Integrity::Invalid {
+        return Err(VerificationError::SignatureMismatch);
+    }
+    signer.resolve(method).ok_or(VerificationError::Revoked)?;
+    signer
+        .verify_data(&document)
+        .map_err(|_| VerificationError::SignatureMismatch)
+}
+
+/// Resolves the issuer document and re-checks its message chain.
+pub async fn resolve(
+    ledger: &Ledger,
+    parsed: &Parsed,
+    pinned: Option<&str>,
+) -> Result<(IotaDocument, Report), VerificationError> {
+    let issuer = parsed.issuer()?;
+    match pinned {
+        Some(message_id) => ledger.resolve_at(&issuer, message_id).await,
+        None => ledger.resolve(&issuer).await,
+    }
+    .map_err(|error| VerificationError::ResolutionFailed(error.to_string()))
+}
+
+/// Checks the proof against the resolved issuer. It never verifies if the issuer document
+/// has a broken message chain, and counts as revoked if it no longer holds the signing method.
+pub fn check_proof(
+    parsed: &Parsed,
+    issuer: &IotaDocument,
+    report: &Report,
+) -> Result<(), VerificationError> {
+    if report.integrity == Integrity::Invalid {
+        return Err(VerificationError::SignatureMismatch);
+    }
+    let method = issuer
+        .resolve(parsed.method.as_str())
+        .ok_or(VerificationError::Revoked)?;
+    let verifies = match &parsed.jws {
+        Some((signing_input, signature)) => {
+            let public: Vec<u8> = method
+                .key_data()
+                .try_decode()
+                .map_err(|_| VerificationError::SignatureMismatch)?;
+            Ed25519::verify(signing_input.as_bytes(), signature, &public).is_ok()
+        }
+        None => issuer.verify_data(&parsed.credential).is_ok(),
+    };
+    if verifies {
+        Ok(())
+    } else {
+        Err(VerificationError::SignatureMismatch)
+    }
+}
+
+/// Whether the app's now lies outside the validity period of the credential. A credential
+/// issued ahead of now within the tolerated skew is already valid.
+pub fn check_expiry(parsed: &Parsed, skew: &Skew) -> bool {
+    let now = clock::now();
+    parsed.credential.issuance_date > skew.latest()
+        || parsed.expires.map_or(false, |expires| expires < now)
+}
+
+/// Applies the trust decisions of the verifier.
+pub fn policy(trust: &TrustStore, issuer: &str, verified: bool) -> Policy {
+    match (verified, trust.decision(issuer)) {
+        (false, _) => Policy::Rejected,
+        (true, Some(Decision::Block)) => Policy::Blocked,
+        (true, Some(_)) => Policy::Accepted,
+        (true, None) => Policy::Unknown,
+    }
+}
+
+/// Report of the verification, signed by the verifier when exported.
+pub fn report(verified: &Verified, outcome: &str, verifier: &str) -> VerificationReport {
+    let mut report = VerificationReport::new(
+        &verified.credential,
+        verified.format,
+        verified.verified,
+        outcome,
+        verifier,
+    );
+    report.cached = verified.cached;
+    report.error = verified
+        .error
+        .as_ref()
+        .map(|error| error.code().to_string());
+    report.proofs = verified.proofs.iter().map(ProofCheck::line).collect();
+    report.unknown = verified.unknown.clone();
+    report.warnings = verified.warnings.clone();
+    report
+}
+
+/// Checks the acceptance the subject counter-signed, `None` if the credential came without one.
+pub async fn check_consent(
+    ledger: &Ledger,
+    parsed: &Parsed,
+) -> Result<Option<bool>, Box<dyn Error>> {
+    match &parsed.acceptance {
+        Some(acceptance) => Ok(Some(
+            consent::check(ledger, &parsed.credential, acceptance).await?,
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Runs the stages that don't depend on the verifier: parse, resolve the issuer, check the proof,
+/// the validity period, the subject acceptance and the status list.
+/// `policy` and `report` follow with the trust decisions and identity of the verifier.
+/// Checks still running at the `deadline` are given up and listed as unknown,
+/// the credential then does not verify, rejected by the checks that did finish or for the timeout.
+/// Issuers with a version in `pinned`, message id by DID, are checked against that version.
+pub async fn verify(
+    ledger: &Ledger,
+    input: &str,
+    deadline: Instant,
+    skew: &Skew,
+    pinned: &BTreeMap<String, String>,
+) -> Result<Verified, VerificationError> {
+    let mut unknown: Vec<String> = Vec::new();
+    let parsed = parse(input)?;
+    let expired = check_expiry(&parsed, skew);
+    let mut warnings: Vec<String> = Vec::new();
+    let pinned: Option<&String> = pinned.get(parsed.issuer()?.as_str());
+    let resolved = resolve(ledger, &parsed, pinned.map(String::as_str));
+    let unresolved = |details: Vec<String>| -> Result<Report, VerificationError> {
+        Ok(Report {
+            did: parsed.issuer()?.to_string(),
+            integrity: Integrity::Unknown,
+            details,
+            versions: Vec::new(),
+        })
+    };
+    let (proof, integrity, issuer) = match within(deadline, resolved).await {
+        // The credential does not verify, reported like any other failed check.
+        Some(Err(error)) => {
+            let integrity = unresolved(vec![error.to_string()])?;
+            (Some(Err(error)), integrity, None)
+        }
+        Some(Ok((issuer, mut integrity))) => {
+            warnings = integrity::check_method(
+                &integrity,
+                &issuer,
+                &parsed.method,
+                parsed.credential.issuance_date,
+            );
+            integrity.details.extend(warnings.iter().cloned());
+            if let Some(message_id) = pinned {
+                warnings.push(format!(
+                    "Checked against the pinned issuer document {}, not the latest",
+                    message_id
+                ));
+            }
+            let proof = check_proof(&parsed, &issuer, &integrity);
+            (Some(proof), integrity, Some(issuer))
+        }
+        None => {
+            unknown.push(RESOLUTION.to_string());
+            unknown.push(PROOF.to_string());
+            (None, unresolved(Vec::new())?, None)
+        }
+    };
+    let proofs: Vec<ProofCheck> = if parsed.proofs.is_empty() {
+        Vec::new()
+    } else {
+        within(deadline, check_proofs::<Credential>(ledger, &parsed.proofs))
+            .await
+            .unwrap_or_else(|| {
+                unknown.push(PROOF_SET.to_string());
+                Vec::new()
+            })
+    };
+    let consent = match within(deadline, check_consent(ledger, &parsed)).await {
+        Some(consent) => {
+            consent.map_err(|error| VerificationError::ResolutionFailed(error.to_string()))?
+        }
+        None => {
+            unknown.push(CONSENT.to_string());
+            None
+        }
+    };
+    // An unreachable status list is no reason to reject, a revocation may be missed though.
+    // Only a credential the issuer signed gets to name a status list to fetch.
+    let revoked: bool = match (&proof, &issuer) {
+        (Some(Ok(())), Some(issuer)) => {
+            match within(deadline, revocation::revoked(&parsed.credential, issuer)).await {
+                Some(Ok(revoked)) => revoked.is_some(),
+                Some(Err(error)) => {
+                    warnings.push(format!("The status list could not be checked: {}", error));
+                    false
+                }
+                None => {
+                    unknown.push(STATUS.to_string());
+                    false
+                }
+            }
+        }
+        _ => false,
+    };
+    let skewed: Option<VerificationError> = match skew.check(parsed.created) {
+        Ok(warning) => {
+            warnings.extend(warning);
+            None
+        }
+        Err(error) => Some(VerificationError::ClockSkew(error)),
+    };
+    // With a proof set, every proof has to hold, not only the one of the issuer.
+    // A check that failed outweighs the ones left unknown.
+    let error = match &proof {
+        Some(Err(error)) => Some(error.clone()),
+        _ => match proofs.iter().find_map(|check| check.error.clone()) {
+            Some(error) => Some(error),
+            None if revoked => Some(VerificationError::Revoked),
+            None if skewed.is_some() => skewed,
+            None if expired => Some(VerificationError::Expired),
+            None if !unknown.is_empty() => Some(VerificationError::Timeout),
+            None => None,
+        },
+    };
+    Ok(Verified {
+        credential: parsed.credential,
+        format: parsed.format,
+        verified: error.is_none(),
+        proof: matches!(proof, Some(Ok(()))),
+        proofs,
+        error,
+        integrity,
+        consent,
+        cached: None,
+        unknown,
+        warnings,
+    })
+}
+
+/// The output of the stage, `None` if it is still running at the deadline.
+async fn within<T>(deadline: Instant, stage: impl Future<Output = T>) -> Option<T> {
+    timeout_at(deadline, stage).await.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::did;
+    use crate::issue;
+    use crate::ledger::MemoryTangle;
+    use identity::core::{encode_b64, json, Object, ToJson};
+    use identity::crypto::KeyPair;
+    use std::time::Duration;
+
+    fn identity() -> (IotaDocument, KeyPair) {
+        let key: KeyPair = KeyPair::new_ed25519().unwrap();
+        let document: IotaDocument = IotaDocument::new(&key).unwrap();
+        (document, key)
+    }
+
+    fn credential(issuer: &IotaDocument, holder: &IotaDocument) -> Credential {
+        let mut claims = Object::new();
+        claims.insert("degree".to_string(), "Bachelor of Science".into());
+        issue::issue_claims(
+            issuer,
+            "https://example.edu/credentials/3732",
+            holder.id().as_str(),
+            "DegreeCredential",
+            &[],
+            claims,
+        )
+        .unwrap()
+    }
+
+    fn report(issuer: &IotaDocument) -> Report {
+        Report {
+            did: issuer.id().to_string(),
+            integrity: Integrity::Signed,
+            details: Vec::new(),
+            versions: Vec::new(),
+        }
+    }
+
+    fn method(document: &IotaDocument) -> String {
+        document.authentication().id().to_string()
+    }
+
+    #[test]
+    fn parses_and_checks_an_embedded_proof() {
+        let (issuer, key) = identity();
+        let (holder, _) = identity();
+        let signed: String = proof::sign(
+            &issuer,
+            &key,
+            credential(&issuer, &holder),
+            ProofFormat::Embedded,
+        )
+        .unwrap();
+
+        let parsed: Parsed = parse(&signed).unwrap();
+        assert_eq!(parsed.format, ProofFormat::Embedded);
+        assert_eq!(parsed.issuer().unwrap().as_str(), issuer.id().as_str());
+        assert!(parsed.proofs.is_empty());
+        assert_eq!(check_proof(&parsed, &issuer, &report(&issuer)), Ok(()));
+    }
+
+    #[test]
+    fn rejects_a_tampered_embedded_proof() {
+        let (issuer, key) = identity();
+        let (holder, _) = identity();
+        let signed: String = proof::sign(
+            &issuer,
+            &key,
+            credential(&issuer, &holder),
+            ProofFormat::Embedded,
+        )
+        .unwrap();
+        let mut json: Value = serde_json::from_str(&signed).unwrap();
+        json["credentialSubject"]["degree"] = Value::from("Doctor of Philosophy");
+
+        let parsed: Parsed = parse(&json.to_string()).unwrap();
+        assert_eq!(
+            check_proof(&parsed, &issuer, &report(&issuer)),
+            Err(VerificationError::SignatureMismatch)
+        );
+    }
+
+    #[test]
+    fn parses_and_checks_a_compact_jws() {
+        let (issuer, key) = identity();
+        let (holder, _) = identity();
+        let jws: String = proof::sign(
+            &issuer,
+            &key,
+            credential(&issuer, &holder),
+            ProofFormat::Jws,
+        )
+        .unwrap();
+
+        let parsed: Parsed = parse(&jws).unwrap();
+        assert_eq!(parsed.format, ProofFormat::Jws);
+        assert_eq!(parsed.method, method(&issuer));
+        assert_eq!(check_proof(&parsed, &issuer, &report(&issuer)), Ok(()));
+    }
+
+    #[test]
+    fn rejects_a_jws_whose_kid_is_not_the_issuer() {
+        let (victim, _) = identity();
+        let (attacker, attacker_key) = identity();
+        let (holder, _) = identity();
+        let forged: Credential = credential(&victim, &holder);
+
+        // Signed and named `iss` by the attacker, `vc.issuer` claims the victim.
+        let jws: String =
+            proof::sign_jws(&attacker, &method(&attacker), &attacker_key, &forged).unwrap();
+        assert_eq!(parse(&jws).err().map(|e| e.code()), Some("issuer_mismatch"));
+
+        // `iss` and `vc.issuer` claim the victim, the kid points at the attacker's key.
+        let jws: String =
+            proof::sign_jws(&victim, &method(&attacker), &attacker_key, &forged).unwrap();
+        assert_eq!(parse(&jws).err().map(|e| e.code()), Some("issuer_mismatch"));
+    }
+
+    #[test]
+    fn rejects_a_jws_with_another_algorithm() {
+        let (issuer, key) = identity();
+        let (holder, _) = identity();
+        let jws: String = proof::sign(
+            &issuer,
+            &key,
+            credential(&issuer, &holder),
+            ProofFormat::Jws,
+        )
+        .unwrap();
+        let header = json!({ "alg": "HS256", "typ": "JWT", "kid": method(&issuer) });
+        let parts: Vec<&str> = jws.split('.').collect();
+        let altered: String = format!(
+            "{}.{}.{}",
+            encode_b64(&header.to_json_vec().unwrap()),
+            parts[1],
+            parts[2]
+        );
+
+        assert_eq!(
+            parse(&altered).err(),
+            Some(VerificationError::SignatureMismatch)
+        );
+    }
+
+    #[test]
+    fn rejects_a_proof_against_another_document() {
+        let (issuer, key) = identity();
+        let (other, _) = identity();
+        let (holder, _) = identity();
+        let signed: String = proof::sign(
+            &issuer,
+            &key,
+            credential(&issuer, &holder),
+            ProofFormat::Embedded,
+        )
+        .unwrap();
+
+        let parsed: Parsed = parse(&signed).unwrap();
+        assert_eq!(
+            check_proof(&parsed, &other, &report(&other)),
+            Err(VerificationError::Revoked)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(matches!(
+            parse("not a credential"),
+            Err(VerificationError::MalformedJson(_))
+        ));
+        assert!(matches!(
+            parse("a.b.c"),
+            Err(VerificationError::MalformedJson(_))
+        ));
+        assert!(matches!(
+            parse("{}"),
+            Err(VerificationError::SchemaViolation(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_a_credential_without_proof() {
+        let (issuer, _) = identity();
+        let (holder, _) = identity();
+        let unsigned: String = credential(&issuer, &holder).to_string();
+
+        assert!(matches!(
+            parse(&unsigned),
+            Err(VerificationError::SchemaViolation(_))
+        ));
+    }
+
+    #[test]
+    fn checks_the_validity_period() {
+        let (issuer, key) = identity();
+        let (holder, _) = identity();
+        let now: i64 = clock::now().to_unix();
+        let signed = |issued: i64, expires: Option<i64>| {
+            let mut credential: Credential = credential(&issuer, &holder);
+            credential.issuance_date = Timestamp::from_unix(issued);
+            credential.expiration_date = expires.map(Timestamp::from_unix);
+            proof::sign(&issuer, &key, credential, ProofFormat::Embedded).unwrap()
+        };
+        let skew = Skew::default();
+
+        let valid: Parsed = parse(&signed(now - 60, Some(now + 3600))).unwrap();
+        assert!(!check_expiry(&valid, &skew));
+        let without_expiry: Parsed = parse(&signed(now - 60, None)).unwrap();
+        assert!(!check_expiry(&without_expiry, &skew));
+        let expired: Parsed = parse(&signed(now - 7200, Some(now - 3600))).unwrap();
+        assert!(check_expiry(&expired, &skew));
+        let within_skew: Parsed = parse(&signed(now + 60, None)).unwrap();
+        assert!(!check_expiry(&within_skew, &skew));
+        let ahead: Parsed = parse(&signed(now + 3600, None)).unwrap();
+        assert!(check_expiry(&ahead, &skew));
+    }
+
+    #[test]
+    fn splits_a_proof_set() {
+        let json = json!({
+            "id": "urn:uuid:1",
+            "proof": [
+                { "verificationMethod": "did:iota:issuer#key-1" },
+                { "verificationMethod": "did:iota:subject#key-1" },
+            ],
+        });
+        let versions: Vec<Value> = split_proofs(&json);
+
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[1]["id"], "urn:uuid:1");
+        assert_eq!(
+            versions[1]["proof"]["verificationMethod"],
+            "did:iota:subject#key-1"
+        );
+        assert_eq!(signed_by(&versions, "did:iota:issuer"), Some(&versions[0]));
+        assert_eq!(signed_by(&versions, "did:iota:other"), None);
+
+        let single = json!({ "proof": { "verificationMethod": "did:iota:issuer#key-1" } });
+        assert!(split_proofs(&single).is_empty());
+    }
+
+    #[test]
+    fn parses_a_credential_with_a_proof_set() {
+        let (issuer, key) = identity();
+        let (holder, _) = identity();
+        let signed: String = proof::sign(
+            &issuer,
+            &key,
+            credential(&issuer, &holder),
+            ProofFormat::Embedded,
+        )
+        .unwrap();
+        let mut json: Value = serde_json::from_str(&signed).unwrap();
+        let issuer_proof: Value = json["proof"].clone();
+        let mut holder_proof: Value = issuer_proof.clone();
+        holder_proof["verificationMethod"] = Value::from(method(&holder));
+        json["proof"] = json!([issuer_proof, holder_proof]);
+
+        let parsed: Parsed = parse(&json.to_string()).unwrap();
+        assert_eq!(parsed.proofs.len(), 2);
+        assert_eq!(parsed.method, issuer_proof["verificationMethod"]);
+        assert_eq!(check_proof(&parsed, &issuer, &report(&issuer)), Ok(()));
+
+        // Without a proof of the issuer the credential has nothing to go on.
+        json["proof"] = json!([holder_proof.clone(), holder_proof]);
+        assert!(matches!(
+            parse(&json.to_string()),
+            Err(VerificationError::SchemaViolation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn reports_an_unresolved_issuer_as_not_verified() {
+        let (issuer, key) = identity();
+        let (holder, _) = identity();
+        let signed: String = proof::sign(
+            &issuer,
+            &key,
+            credential(&issuer, &holder),
+            ProofFormat::Embedded,
+        )
+        .unwrap();
+        let ledger = Ledger::Memory(MemoryTangle::default());
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        let verified: Verified = verify(
+            &ledger,
+            &signed,
+            deadline,
+            &Skew::default(),
+            &BTreeMap::new(),
+        )
+        .await
+        .unwrap();
+        assert!(!verified.verified);
+        assert!(!verified.proof);
+        assert!(matches!(
+            verified.error,
+            Some(VerificationError::ResolutionFailed(_))
+        ));
+        assert_eq!(verified.integrity.integrity, Integrity::Unknown);
+    }
+
+    /// An issuer published to the in-memory ledger, so `verify` resolves it.
+    fn published(memory: &MemoryTangle) -> (IotaDocument, KeyPair) {
+        let key: KeyPair = KeyPair::new_ed25519().unwrap();
+        let mut document: IotaDocument = did::new_document(&key).unwrap();
+        memory.publish(&mut document);
+        (document, key)
+    }
+
+    async fn verify_in(memory: &MemoryTangle, input: &str) -> Result<Verified, VerificationError> {
+        let ledger = Ledger::Memory(memory.clone());
+        let deadline = Instant::now() + Duration::from_secs(5);
+        verify(&ledger, input, deadline, &Skew::default(), &BTreeMap::new()).await
+    }
+
+    #[tokio::test]
+    async fn verify_fails_each_bad_credential_in_its_stage() {
+        let memory = MemoryTangle::default();
+        let (issuer, key) = published(&memory);
+        let (attacker, attacker_key) = published(&memory);
+        let (holder, _) = identity();
+
+        let valid: String = proof::sign(
+            &issuer,
+            &key,
+            credential(&issuer, &holder),
+            ProofFormat::Embedded,
+        )
+        .unwrap();
+        let verified: Verified = verify_in(&memory, &valid).await.unwrap();
+        assert!(verified.verified);
+        assert_eq!(verified.error, None);
+
+        // Parsing rejects a JWS with another algorithm before anything is resolved.
+        let jws: String = proof::sign(
+            &issuer,
+            &key,
+            credential(&issuer, &holder),
+            ProofFormat::Jws,
+        )
+        .unwrap();
+        let header = json!({ "alg": "HS256", "typ": "JWT", "kid": method(&issuer) });
+        let parts: Vec<&str> = jws.split('.').collect();
+        let altered: String = format!(
+            "{}.{}.{}",
+            encode_b64(&header.to_json_vec().unwrap()),
+            parts[1],
+            parts[2]
+        );
+        let error = verify_in(&memory, &altered).await.err();
+        assert_eq!(error.map(|e| e.code()), Some("signature_mismatch"));
+
+        // And a JWS the attacker signed and named `iss` for a credential of the issuer.
+        let forged: String = proof::sign_jws(
+            &attacker,
+            &method(&attacker),
+            &attacker_key,
+            &credential(&issuer, &holder),
+        )
+        .unwrap();
+        let error = verify_in(&memory, &forged).await.err();
+        assert_eq!(error.map(|e| e.code()), Some("issuer_mismatch"));
+
+        // The proof of an expired credential holds, the validity period rejects it.
+        let now: i64 = clock::now().to_unix();
+        let mut expired: Credential = credential(&issuer, &holder);
+        expired.issuance_date = Timestamp::from_unix(now - 7200);
+        expired.expiration_date = Some(Timestamp::from_unix(now - 3600));
+        let expired: String = proof::sign(&issuer, &key, expired, ProofFormat::Embedded).unwrap();
+        let verified: Verified = verify_in(&memory, &expired).await.unwrap();
+        assert!(verified.proof);
+        assert!(!verified.verified);
+        assert_eq!(verified.error.map(|e| e.code()), Some("expired"));
+
+        // Changed claims resolve the issuer and fail the proof check.
+        let mut json: Value = serde_json::from_str(&valid).unwrap();
+        json["credentialSubject"]["degree"] = Value::from("Doctor of Philosophy");
+        let verified: Verified = verify_in(&memory, &json.to_string()).await.unwrap();
+        assert!(!verified.proof);
+        assert_eq!(verified.error.map(|e| e.code()), Some("signature_mismatch"));
+    }
+
+    #[tokio::test]
+    async fn fetches_no_status_list_outside_the_issuer_services() {
+        let memory = MemoryTangle::default();
+        let (issuer, key) = published(&memory);
+        let (holder, _) = identity();
+        let mut json: Value = credential(&issuer, &holder).to_json_value().unwrap();
+        json["credentialStatus"] = json!({
+            "id": "http://127.0.0.1:9/status#https://example.edu/credentials/3732",
+            "type": revocation::STATUS_TYPE,
+        });
+        let credential: Credential = Credential::from_json_value(json).unwrap();
+        let signed: String = proof::sign(&issuer, &key, credential, ProofFormat::Embedded).unwrap();
+
+        // The issuer document names no service, the list is not requested.
+        let verified: Verified = verify_in(&memory, &signed).await.unwrap();
+        assert!(verified.verified);
+        assert!(verified
+            .warnings
+            .iter()
+            .any(|warning| warning.ends_with("is not under a service endpoint of the issuer")));
+
+        // A credential the issuer did not sign gets no status list checked at all.
+        let mut json: Value = serde_json::from_str(&signed).unwrap();
+        json["credentialSubject"]["degree"] = Value::from("Doctor of Philosophy");
+        let verified: Verified = verify_in(&memory, &json.to_string()).await.unwrap();
+        assert!(!verified.proof);
+        assert!(!verified
+            .warnings
+            .iter()
+            .any(|warning| warning.starts_with("The status list")));
+    }
+}
@@ -0,0 +1,170 @@
+use crate::armor;
+use crate::issue;
+use crate::verification::{self, ProofCheck};
+use identity::core::{FromJson, ToJson};
+use identity::credential::{Credential, Presentation};
+use identity::iota::{Client, CredentialValidation, CredentialValidator, PresentationValidation};
+use identity::prelude::*;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use tui::{
+    style::{Color, Style},
+    text::{Span, Spans},
+};
+
+/// Outcome of one credential inside a presentation.
+#[derive(Clone, Debug)]
+pub struct CredentialResult {
+    pub title: String,
+    pub issuer: String,
+    pub issuer_verified: bool,
+    pub verified: bool,
+    /// Issued by its own subject.
+    pub self_issued: bool,
+}
+
+/// Presentation outcome grouped per credential and per issuer.
+#[derive(Clone, Debug)]
+pub struct PresentationReport {
+    pub holder: String,
+    pub holder_verified: bool,
+    pub verified: bool,
+    pub credentials: Vec<CredentialResult>,
+    /// Each proof if the presentation carries a proof set.
+    pub proofs: Vec<ProofCheck>,
+}
+
+impl PresentationReport {
+    pub fn new(validation: &PresentationValidation) -> Self {
+        let credentials = validation
+            .credentials
+            .iter()
+            .map(|credential| CredentialResult {
+                title: credential
+                    .credential
+                    .types
+                    .iter()
+                    .last()
+                    .cloned()
+                    .unwrap_or_default(),
+                issuer: credential.issuer.did.to_string(),
+                issuer_verified: credential.issuer.verified,
+                verified: credential.verified,
+                self_issued: issue::is_self_issued(&credential.credential),
+            })
+            .collect();
+        PresentationReport {
+            holder: validation.holder.did.to_string(),
+            holder_verified: validation.holder.verified,
+            verified: validation.verified,
+            credentials,
+            proofs: Vec::new(),
+        }
+    }
+
+    /// Adds the checks of a proof set, the presentation only verifies if all of them hold.
+    pub fn with_proofs(mut self, proofs: Vec<ProofCheck>) -> Self {
+        self.verified &= proofs.iter().all(|check| check.error.is_none());
+        self.proofs = proofs;
+        self
+    }
+
+    pub fn by_issuer(&self) -> BTreeMap<&str, Vec<&CredentialResult>> {
+        let mut issuers: BTreeMap<&str, Vec<&CredentialResult>> = BTreeMap::new();
+        for credential in &self.credentials {
+            issuers
+                .entry(credential.issuer.as_str())
+                .or_default()
+                .push(credential);
+        }
+        issuers
+    }
+
+    pub fn lines<'a>(&self) -> Vec<Spans<'a>> {
+        let mark = |verified: bool| {
+            if verified {
+                Span::styled("[ok] ", Style::default().fg(Color::Green))
+            } else {
+                Span::styled("[failed] ", Style::default().fg(Color::Red))
+            }
+        };
+        let mut lines = vec![
+            Spans::from(vec![
+                mark(self.verified),
+                Span::raw(format!(
+                    "Presentation of {} credentials",
+                    self.credentials.len()
+                )),
+            ]),
+            Spans::from(vec![
+                mark(self.holder_verified),
+                Span::raw(format!("Holder {}", self.holder)),
+            ]),
+        ];
+        for check in &self.proofs {
+            lines.push(Spans::from(vec![
+                mark(check.error.is_none()),
+                Span::raw(check.summary()),
+            ]));
+        }
+        for (issuer, credentials) in self.by_issuer() {
+            let issuer_verified = credentials.iter().all(|c| c.issuer_verified);
+            lines.push(Spans::from(vec![
+                mark(issuer_verified),
+                Span::raw(format!("Issuer {}", issuer)),
+            ]));
+            for credential in credentials {
+                lines.push(Spans::from(vec![
+                    Span::raw("    "),
+                    mark(credential.verified),
+                    Span::raw(credential.title.clone()),
+                    Span::styled(
+                        if credential.self_issued {
+                            " [self-issued]"
+                        } else {
+                            ""
+                        },
+                        Style::default().fg(Color::Yellow),
+                    ),
+                ]));
+            }
+        }
+        lines
+    }
+}
+
+/// Resolves the issuer on the Tangle and checks the credential signature.
+pub async fn verify_credential(
+    client: &Client,
+    credential: &Credential,
+) -> Result<CredentialValidation> {
+    let validator: CredentialValidator<'_, Client> = CredentialValidator::new(client);
+    validator.check(&credential.to_json()?).await
+}
+
+/// Reads a presentation, armored or not. Of one carrying a proof set it returns the version with the holder proof,
+/// together with the versions for each proof to check them with `verification::check_proofs`.
+pub fn parse_presentation(
+    input: &str,
+) -> std::result::Result<(Presentation, Vec<Value>), Box<dyn Error>> {
+    let json: Value = serde_json::from_str(&armor::dearmor(input)?)?;
+    let proofs: Vec<Value> = verification::split_proofs(&json);
+    if proofs.is_empty() {
+        return Ok((Presentation::from_json_value(json)?, proofs));
+    }
+    let holder: &str = json["holder"].as_str().unwrap_or_default();
+    let signed: Value = verification::signed_by(&proofs, holder)
+        .cloned()
+        .ok_or("none of the proofs is made by the holder")?;
+    Ok((Presentation::from_json_value(signed)?, proofs))
+}
+
+/// Resolves holder and issuers on the Tangle and checks all signatures of the presentation.
+pub async fn verify_presentation(
+    client: &Client,
+    presentation: &Presentation,
+) -> Result<PresentationValidation> {
+    let validator: CredentialValidator<'_, Client> = CredentialValidator::new(client);
+    validator.check_presentation(&presentation.to_json()?).await
+}
@@ -0,0 +1,615 @@
+use crate::armor::{self, Kind};
+use crate::branding;
+use crate::clock;
+use crate::consent::{Acceptance, Consented};
+use crate::dates;
+use crate::handle;
+use crate::issue;
+use crate::pseudonym::Pseudonyms;
+use crate::status::Status;
+use identity::core::{Timestamp, ToJson};
+use identity::credential::Credential;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph},
+};
+
+/// A credential held by the holder with its user-defined organisation.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Entry {
+    pub id: String,
+    pub credential: Credential,
+    pub added: Timestamp,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// The issuer could not be resolved when the credential arrived, so it was kept unverified.
+    #[serde(default)]
+    pub unverified: bool,
+    /// Archived credentials are hidden from the default view but kept.
+    #[serde(default)]
+    pub archived: Option<Timestamp>,
+    /// Id of the older credential of the same type, issuer and subject this one replaces.
+    #[serde(default)]
+    pub supersedes: Option<String>,
+    #[serde(default)]
+    pub superseded_by: Option<String>,
+    /// Acceptance of the credential counter-signed by the holder.
+    #[serde(default)]
+    pub acceptance: Option<Acceptance>,
+    #[serde(default)]
+    pub appearance: Appearance,
+    /// Shown as a card on the Home tab.
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// How the holder wants a credential listed, kept next to it so the signed credential is
+/// never touched.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Appearance {
+    /// Shown instead of the credential type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Hex color like `#12107c`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Emoji or short symbol in front of the name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+impl Appearance {
+    /// Parses `name=My degree, color=#12107c, icon=🎓`, an empty input resets all of them.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut appearance = Appearance::default();
+        for pair in input
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let (key, value) = match pair.find('=') {
+                Some(index) => (pair[..index].trim(), pair[index + 1..].trim()),
+                None => return Err(format!("{} is no key=value pair", pair)),
+            };
+            let value = Some(value.to_string()).filter(|value| !value.is_empty());
+            match key {
+                "name" => appearance.name = value,
+                "color" => {
+                    if let Some(color) = &value {
+                        branding::color(color)
+                            .ok_or_else(|| format!("{} is no hex color like #12107c", color))?;
+                    }
+                    appearance.color = value;
+                }
+                "icon" => appearance.icon = value,
+                other => return Err(format!("unknown display preference {}", other)),
+            }
+        }
+        Ok(appearance)
+    }
+}
+
+impl Entry {
+    pub fn title(&self) -> String {
+        self.credential
+            .types
+            .iter()
+            .last()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The name the holder gave the credential, its type otherwise.
+    pub fn name(&self) -> String {
+        self.appearance.name.clone().unwrap_or_else(|| self.title())
+    }
+
+    fn subjects(&self) -> Vec<String> {
+        subjects(&self.credential)
+    }
+
+    /// Whether both credentials state the same kind of claims by the same issuer about the same subject.
+    fn same_claim(&self, other: &Entry) -> bool {
+        claim(&self.credential) == claim(&other.credential)
+    }
+}
+
+/// Type, issuer and subjects of the credential, what a reissue keeps.
+fn claim(credential: &Credential) -> (Option<&String>, &str, Vec<String>) {
+    (
+        credential.types.iter().last(),
+        credential.issuer.url().as_str(),
+        subjects(credential),
+    )
+}
+
+fn subjects(credential: &Credential) -> Vec<String> {
+    credential
+        .credential_subject
+        .iter()
+        .filter_map(|subject| subject.id.as_ref().map(|id| id.to_string()))
+        .collect()
+}
+
+/// How an imported credential relates to the held ones.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Duplicate {
+    /// The same credential is held already.
+    Exact,
+    /// A credential of the same type, issuer and subject is held, like a copy reissued with
+    /// other dates. Carries the id of the newest one.
+    Near(String),
+}
+
+/// What happened to a credential arriving in the wallet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Arrival {
+    Added,
+    /// Kept, but marked as not verified.
+    Unverified,
+    /// The same credential is already held.
+    Held,
+    /// Not stored because it failed verification.
+    Rejected(Status),
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Wallet {
+    pub entries: Vec<Entry>,
+    /// DIDs shown as cards on the Home tab, like the holder's own or an issuer's.
+    #[serde(default)]
+    pub pinned_identities: BTreeSet<String>,
+}
+
+impl Wallet {
+    /// Stores an arriving credential according to the status it was checked with.
+    /// Invalid, revoked and expired credentials are refused, credentials of an issuer that can't be
+    /// resolved are kept but marked unverified.
+    pub fn receive(
+        &mut self,
+        credential: Credential,
+        status: Status,
+    ) -> Result<Arrival, Box<dyn Error>> {
+        match status {
+            Status::Invalid | Status::Revoked | Status::Expired => Ok(Arrival::Rejected(status)),
+            Status::Valid | Status::Unknown => {
+                if !self.add(credential)? {
+                    return Ok(Arrival::Held);
+                }
+                if status == Status::Valid {
+                    return Ok(Arrival::Added);
+                }
+                if let Some(entry) = self.entries.last_mut() {
+                    entry.unverified = true;
+                }
+                Ok(Arrival::Unverified)
+            }
+        }
+    }
+
+    /// Whether the same credential, or one of the same type, issuer and subject that is not
+    /// archived, is held already.
+    pub fn duplicate_of(
+        &self,
+        credential: &Credential,
+    ) -> Result<Option<Duplicate>, Box<dyn Error>> {
+        if self.holds(&handle::digest(&credential.to_json()?)) {
+            return Ok(Some(Duplicate::Exact));
+        }
+        Ok(self
+            .entries
+            .iter()
+            .filter(|entry| entry.archived.is_none())
+            .filter(|entry| claim(&entry.credential) == claim(credential))
+            .max_by_key(|entry| entry.credential.issuance_date.to_unix())
+            .map(|entry| Duplicate::Near(entry.id.clone())))
+    }
+
+    fn holds(&self, id: &str) -> bool {
+        // Older entries may carry the hash of another serialization, compare canonical forms.
+        self.entries.iter().any(|entry| {
+            entry.id == id
+                || entry
+                    .credential
+                    .to_json()
+                    .map(|json| handle::digest(&json) == id)
+                    .unwrap_or(false)
+        })
+    }
+
+    /// Keeps the newer of the held credential and its near duplicate. A newer duplicate is added
+    /// with the tags, folder, appearance and pin of the held one, which is archived as superseded
+    /// by it. Returns whether the duplicate was the newer one.
+    pub fn merge(&mut self, held: &str, credential: Credential) -> Result<bool, Box<dyn Error>> {
+        let older = match self.get_mut(held) {
+            Some(entry) if entry.credential.issuance_date < credential.issuance_date => entry,
+            _ => return Ok(false),
+        };
+        older.archived.get_or_insert_with(Timestamp::now_utc);
+        let (tags, folder, appearance, pinned) = (
+            older.tags.clone(),
+            older.folder.clone(),
+            older.appearance.clone(),
+            std::mem::take(&mut older.pinned),
+        );
+        if !self.add(credential)? {
+            return Ok(false);
+        }
+        let newer = self
+            .entries
+            .last_mut()
+            .expect("the credential was just added");
+        newer.tags = tags;
+        newer.folder = folder;
+        newer.appearance = appearance;
+        newer.pinned = pinned;
+        newer.supersedes = Some(held.to_string());
+        let id = newer.id.clone();
+        if let Some(older) = self.get_mut(held) {
+            older.superseded_by = Some(id);
+        }
+        Ok(true)
+    }
+
+    /// Stores the credential unless the exact same credential is already held.
+    pub fn add(&mut self, credential: Credential) -> Result<bool, Box<dyn Error>> {
+        let id = handle::digest(&credential.to_json()?);
+        if self.holds(&id) {
+            return Ok(false);
+        }
+        self.entries.push(Entry {
+            id,
+            credential,
+            added: Timestamp::now_utc(),
+            tags: BTreeSet::new(),
+            folder: None,
+            unverified: false,
+            archived: None,
+            supersedes: None,
+            superseded_by: None,
+            acceptance: None,
+            appearance: Appearance::default(),
+            pinned: false,
+        });
+        Ok(true)
+    }
+
+    /// Entries carrying the tag, or all entries without a filter.
+    /// Archived entries are only listed with `archived`, and then only them.
+    pub fn filtered(&self, tag: Option<&str>, archived: bool) -> Vec<&Entry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.archived.is_some() == archived)
+            .filter(|entry| tag.map(|tag| entry.tags.contains(tag)).unwrap_or(true))
+            .collect()
+    }
+
+    /// Pins the credential to the Home tab or unpins it, returns whether it is pinned now.
+    pub fn toggle_pin(&mut self, id: &str) -> Option<bool> {
+        let entry = self.get_mut(id)?;
+        entry.pinned = !entry.pinned;
+        Some(entry.pinned)
+    }
+
+    /// Pins the DID to the Home tab or unpins it, returns whether it is pinned now.
+    pub fn toggle_identity_pin(&mut self, did: &str) -> bool {
+        if self.pinned_identities.remove(did) {
+            return false;
+        }
+        self.pinned_identities.insert(did.to_string());
+        true
+    }
+
+    /// Archives the credential or restores it from the archive, returns whether it is archived now.
+    pub fn toggle_archive(&mut self, id: &str) -> Option<bool> {
+        let entry = self.get_mut(id)?;
+        entry.archived = match entry.archived {
+            Some(_) => None,
+            None => Some(Timestamp::now_utc()),
+        };
+        Some(entry.archived.is_some())
+    }
+
+    /// Links the credential to the newest held credential it supersedes and returns that one's id.
+    pub fn link_predecessor(&mut self, id: &str) -> Option<String> {
+        let entry = self.entries.iter().find(|entry| entry.id == id)?;
+        let predecessor = self
+            .entries
+            .iter()
+            .filter(|other| other.id != id && other.superseded_by.is_none())
+            .filter(|other| other.same_claim(entry))
+            .max_by_key(|other| other.added.to_unix())?
+            .id
+            .clone();
+        self.get_mut(&predecessor)?.superseded_by = Some(id.to_string());
+        self.get_mut(id)?.supersedes = Some(predecessor.clone());
+        Some(predecessor)
+    }
+
+    /// The supersession chain the credential is part of, oldest first.
+    pub fn chain(&self, id: &str) -> Vec<&Entry> {
+        let find = |id: &str| self.entries.iter().find(|entry| entry.id == id);
+        let mut oldest = match find(id) {
+            Some(entry) => entry,
+            None => return Vec::new(),
+        };
+        while let Some(older) = oldest.supersedes.as_deref().and_then(find) {
+            oldest = older;
+        }
+        let mut chain = vec![oldest];
+        while let Some(newer) = chain[chain.len() - 1]
+            .superseded_by
+            .as_deref()
+            .and_then(find)
+        {
+            // A chain edited by hand could loop, don't follow it twice.
+            if chain.iter().any(|entry| entry.id == newer.id) {
+                break;
+            }
+            chain.push(newer);
+        }
+        chain
+    }
+
+    /// Puts the renewed credential in place of the held one, keeping tags, folder, appearance
+    /// and chain.
+    /// Returns the new id.
+    pub fn swap(
+        &mut self,
+        id: &str,
+        credential: Credential,
+    ) -> Result<Option<String>, Box<dyn Error>> {
+        let new_id = handle::digest(&credential.to_json()?);
+        let entry = match self.get_mut(id) {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        entry.id = new_id.clone();
+        entry.credential = credential;
+        entry.unverified = false;
+        // The acceptance was signed over the old credential.
+        entry.acceptance = None;
+        for other in self.entries.iter_mut() {
+            if other.supersedes.as_deref() == Some(id) {
+                other.supersedes = Some(new_id.clone());
+            }
+            if other.superseded_by.as_deref() == Some(id) {
+                other.superseded_by = Some(new_id.clone());
+            }
+        }
+        Ok(Some(new_id))
+    }
+
+    /// Removes the credential for good.
+    pub fn delete(&mut self, id: &str) -> Option<Entry> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+        Some(self.entries.remove(index))
+    }
+
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut Entry> {
+        self.entries.iter_mut().find(|entry| entry.id == id)
+    }
+
+    pub fn tags(&self) -> BTreeSet<String> {
+        self.entries
+            .iter()
+            .flat_map(|entry| entry.tags.iter().cloned())
+            .collect()
+    }
+
+    /// Removes the tag from every credential and returns how many carried it.
+    pub fn delete_tag(&mut self, tag: &str) -> usize {
+        self.entries
+            .iter_mut()
+            .filter(|entry| entry.tags.remove(tag))
+            .count()
+    }
+
+    /// Writes the credential into `dir`, together with its acceptance if the holder signed one.
+    pub fn export_entry(&self, id: &str, dir: &str) -> Result<Option<PathBuf>, Box<dyn Error>> {
+        let entry = match self.entries.iter().find(|entry| entry.id == id) {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let json = match &entry.acceptance {
+            Some(acceptance) => serde_json::to_string_pretty(&Consented {
+                credential: entry.credential.clone(),
+                subject_acceptance: acceptance.clone(),
+            })?,
+            None => entry.credential.to_json_pretty()?,
+        };
+        fs::create_dir_all(dir)?;
+        let path = Path::new(dir).join(format!("{}.json", &entry.id[..16]));
+        fs::write(
+            path.with_extension("asc"),
+            armor::encode(Kind::Credential, &json),
+        )?;
+        fs::write(&path, json)?;
+        Ok(Some(path))
+    }
+
+    /// Writes every credential of the folder as its own JSON file into `<dir>/<folder>`.
+    pub fn export_folder(&self, folder: &str, dir: &str) -> Result<PathBuf, Box<dyn Error>> {
+        let target = Path::new(dir).join(folder);
+        fs::create_dir_all(&target)?;
+        for entry in self
+            .entries
+            .iter()
+            .filter(|entry| entry.folder.as_deref() == Some(folder))
+        {
+            fs::write(
+                target.join(format!("{}.json", &entry.id[..16])),
+                entry.credential.to_json_pretty()?,
+            )?;
+        }
+        Ok(target)
+    }
+}
+
+/// The supersession chain of the selected credential, oldest first.
+pub fn render_chain<'a>(chain: &[&Entry], selected: &str) -> Paragraph<'a> {
+    let lines: Vec<Spans> = chain
+        .iter()
+        .map(|entry| {
+            let style = if entry.id == selected {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            let state = match (&entry.archived, &entry.superseded_by) {
+                (Some(_), _) => " (replaced, archived)",
+                (None, Some(_)) => " (superseded, kept)",
+                (None, None) => " (current)",
+            };
+            Spans::from(vec![Span::styled(
+                format!(
+                    "{} {:.12}{}",
+                    dates::date_time(entry.added),
+                    entry.id,
+                    state
+                ),
+                style,
+            )])
+        })
+        .collect();
+    Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title("Supersession chain")
+            .border_type(BorderType::Plain),
+    )
+}
+
+pub const DELETE_WARNING: &str =
+    "Deleting removes the credential from the wallet for good, archiving (z) only hides it.";
+
+/// Parses a comma separated tag list.
+pub fn parse_tags(input: &str) -> BTreeSet<String> {
+    input
+        .split(',')
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+pub fn render<'a>(
+    entries: &[&Entry],
+    statuses: &HashMap<String, Status>,
+    marked: &BTreeSet<String>,
+    filter: Option<&str>,
+    archived: bool,
+    pseudonyms: &Pseudonyms,
+    prompt: Option<&str>,
+) -> List<'a> {
+    let items: Vec<ListItem> = entries
+        .iter()
+        .map(|entry| {
+            let folder = entry
+                .folder
+                .as_ref()
+                .map(|folder| format!("{}/", folder))
+                .unwrap_or_default();
+            let tags: Vec<String> = entry.tags.iter().map(|tag| format!("#{}", tag)).collect();
+            let status = statuses.get(&entry.id);
+            let (badge, color) = status
+                .map(Status::badge)
+                .unwrap_or(("[checking]", Color::Gray));
+            // Once the issuer resolves and the credential verifies, the mark is no longer relevant.
+            let arrival = if entry.unverified && status != Some(&Status::Valid) {
+                "[unverified on arrival] "
+            } else {
+                ""
+            };
+            let pseudonym = if entry
+                .subjects()
+                .iter()
+                .any(|subject| pseudonyms.get(subject).is_some())
+            {
+                "[pseudonym] "
+            } else {
+                ""
+            };
+            let self_issued = if issue::is_self_issued(&entry.credential) {
+                "[self-issued] "
+            } else {
+                ""
+            };
+            let expiry = entry
+                .credential
+                .expiration_date
+                .map(|expires| {
+                    let verb = if expires > clock::now() {
+                        "expires"
+                    } else {
+                        "expired"
+                    };
+                    format!(" {} {}", verb, dates::relative(expires))
+                })
+                .unwrap_or_default();
+            let mark = if marked.contains(&entry.id) {
+                "[*] "
+            } else {
+                "[ ] "
+            };
+            let icon = entry
+                .appearance
+                .icon
+                .as_ref()
+                .map(|icon| format!("{} ", icon))
+                .unwrap_or_default();
+            let name = match entry.appearance.color.as_deref().and_then(branding::color) {
+                Some(color) => Style::default().fg(color),
+                None => Style::default(),
+            };
+            ListItem::new(Spans::from(vec![
+                Span::raw(mark),
+                Span::styled(format!("{} ", badge), Style::default().fg(color)),
+                Span::styled(arrival, Style::default().fg(Color::Magenta)),
+                Span::styled(pseudonym, Style::default().fg(Color::Cyan)),
+                Span::styled(self_issued, Style::default().fg(Color::Yellow)),
+                Span::styled(folder, Style::default().fg(Color::Gray)),
+                Span::styled(format!("{}{} ", icon, entry.name()), name),
+                Span::styled(tags.join(" "), Style::default().fg(Color::LightBlue)),
+                Span::styled(expiry, Style::default().fg(Color::Gray)),
+            ]))
+        })
+        .collect();
+
+    let view = match (archived, filter) {
+        (true, Some(tag)) => format!("Archived wallet #{}", tag),
+        (true, None) => "Archived wallet".to_string(),
+        (false, Some(tag)) => format!("Wallet #{}", tag),
+        (false, None) => "Wallet".to_string(),
+    };
+    let title = match prompt {
+        Some(prompt) => prompt.to_string(),
+        None => format!(
+            "{} (t tags, f folder, N renames, / filter, x export folder, X exports all shown, I imports, d delete tag, space marks, b presents, o derives over-X, A self-attests, enter verifies, R refreshes, e exports, P pairs a device, L picks a LAN instance, T sends to it, z archives, F pins to Home, Z shows archived, S statistics, del deletes)",
+            view
+        ),
+    };
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(title)
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+}